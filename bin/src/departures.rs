@@ -0,0 +1,37 @@
+use std::error::Error;
+use std::fmt;
+use std::io::Write;
+
+use simulation::Dataset;
+
+#[derive(Debug)]
+struct UnknownStationError(String);
+
+impl fmt::Display for UnknownStationError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "no station named {}", self.0)
+    }
+}
+
+impl Error for UnknownStationError {}
+
+/// Writes the departure board of the station, one departure per line.
+pub(crate) fn list_departures(
+    dataset: &Dataset,
+    station_name: &str,
+    from: u32,
+    count: usize,
+    output: &mut impl Write,
+) -> Result<(), Box<dyn Error>> {
+    let station = dataset
+        .station_index(station_name)
+        .ok_or_else(|| UnknownStationError(station_name.to_string()))?;
+    let departures = dataset.departures(station, from, count);
+    if departures.is_empty() {
+        writeln!(output, "No departures from {}", station_name)?;
+    }
+    for departure in departures {
+        writeln!(output, "{}", departure)?;
+    }
+    Ok(())
+}
@@ -248,6 +248,9 @@ impl<'a, W: Write> Inspector<'a, W> {
             shapes.push(node.in_directions(), position);
         }
 
+        if route.has_synthesized_shape() {
+            self.write("‹synthesized shape›")?;
+        }
         self.write(StopLocationsDisplay(stop_locations.upstream, self.format))?;
         self.write(ShapeDisplay(shapes.upstream, self.format))?;
         stop_locations.downstream.reverse();
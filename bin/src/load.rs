@@ -1,15 +1,50 @@
 use std::error::Error;
+use std::fmt;
 use std::fs::File;
 use std::io::Read;
 use std::path::Path;
 
-pub(crate) fn load(binary: impl AsRef<Path>) -> Result<(), Box<dyn Error>> {
+use chrono::NaiveDate;
+
+#[derive(Debug)]
+struct DateNotStoredError(NaiveDate);
+
+impl fmt::Display for DateNotStoredError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "no trains stored for {}", self.0)
+    }
+}
+
+impl Error for DateNotStoredError {}
+
+fn load_stored(binary: impl AsRef<Path>) -> Result<storage::Dataset, Box<dyn Error>> {
     let mut file = File::open(binary)?;
     let mut data = Vec::new();
     file.read_to_end(&mut data)?;
-    let dataset = bincode::deserialize::<storage::Dataset>(&data)
-        .unwrap()
-        .load();
+    Ok(bincode::deserialize::<storage::Dataset>(&data)?)
+}
+
+/// Loads a binary export with the simulation starting at midnight of the date.
+///
+/// Returns the first date the export was stored for as well.
+pub(crate) fn load_dataset_at(
+    binary: impl AsRef<Path>,
+    date: NaiveDate,
+) -> Result<(simulation::Dataset, NaiveDate), Box<dyn Error>> {
+    let dataset = load_stored(binary)?;
+    let first_date = NaiveDate::parse_from_str(dataset.metadata().first_date(), "%F")?;
+    let day = (date - first_date).num_days();
+    let mut dataset = dataset.load();
+    if day < 0 || !dataset.select_day(day as usize) {
+        return Err(Box::new(DateNotStoredError(date)));
+    }
+    Ok((dataset, first_date))
+}
+
+pub(crate) fn load(binary: impl AsRef<Path>) -> Result<(), Box<dyn Error>> {
+    let dataset = load_stored(binary)?;
+    println!("{}", dataset.metadata());
+    let dataset = dataset.load();
 
     println!(
         "Loaded {} stations and {} lines",
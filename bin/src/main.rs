@@ -1,25 +1,38 @@
 use std::convert::TryInto;
 use std::error::Error;
+use std::ffi::OsStr;
 use std::fmt;
 use std::fs::{self, File};
 use std::io::{self, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-use chrono::NaiveDate;
+use chrono::{FixedOffset, NaiveDate};
 use clap::{clap_app, value_t};
 use rustyline::Editor;
 
 use import::profile::{DEFAULT_PROFILE_NAME, PROFILE_NAMES};
-use import::shape::SmoothMode;
-use import::ImportedDataset;
+use import::realtime::{Realtime, VehiclePositions};
+use import::shape::{FallbackMode, SmoothMode};
+use import::{ImportedDataset, InvalidDateRangeError};
+use simulation::parse_time;
 
 mod compress;
+mod departures;
 mod inspect;
 mod load;
+mod plausibility;
+mod scenario;
+mod simulate;
+mod validate;
 
 use compress::compress;
+use departures::list_departures;
 use inspect::{inspect, Format};
-use load::load;
+use load::{load, load_dataset_at};
+use plausibility::check_plausibility;
+use scenario::{read_scenario, report_scenario};
+use simulate::{read_demand, serve, write_snapshots};
+use validate::{validate, ReportFormat};
 
 fn validate_date(value: String) -> Result<(), String> {
     NaiveDate::parse_from_str(&value, "%F")
@@ -27,6 +40,15 @@ fn validate_date(value: String) -> Result<(), String> {
         .map_err(|error| format!("{}, it must be in the format yyyy-mm-dd", error))
 }
 
+fn validate_time(value: String) -> Result<(), String> {
+    parse_time(&value).map(|_| ()).ok_or_else(|| {
+        format!(
+            "invalid time {}, it must be in the format hh:mm[:ss]",
+            value
+        )
+    })
+}
+
 #[derive(Debug)]
 struct NoDatasetImportedError;
 
@@ -38,6 +60,47 @@ impl fmt::Display for NoDatasetImportedError {
 
 impl Error for NoDatasetImportedError {}
 
+/// The imported feed a realtime feed refers to is missing or not known.
+#[derive(Debug)]
+struct UnknownFeedError {
+    feed: Option<String>,
+    feeds: Vec<String>,
+}
+
+impl fmt::Display for UnknownFeedError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match &self.feed {
+            None => write!(
+                formatter,
+                "several feeds are imported, give the realtime feed as FEED=FILE with FEED one of {}",
+                self.feeds.join(", ")
+            ),
+            Some(feed) if self.feeds.is_empty() => write!(
+                formatter,
+                "cannot apply realtime feed to {}, a single feed is imported",
+                feed
+            ),
+            Some(feed) => write!(
+                formatter,
+                "feed {} is not imported, expected one of {}",
+                feed,
+                self.feeds.join(", ")
+            ),
+        }
+    }
+}
+
+impl Error for UnknownFeedError {}
+
+/// Splits a realtime feed given as `FEED=FILE` into the namespace of the imported feed and
+/// the path.
+fn split_feed(value: &OsStr) -> (Option<&str>, &Path) {
+    match value.to_str().and_then(|value| value.split_once('=')) {
+        Some((namespace, path)) => (Some(namespace), Path::new(path)),
+        None => (None, Path::new(value)),
+    }
+}
+
 struct CommandRunner {
     dataset: Option<ImportedDataset>,
 }
@@ -55,12 +118,17 @@ impl CommandRunner {
                 (@arg archive: <ARCHIVE> "Path where the zipped archive should be created")
             )
             (@subcommand import =>
-                (about: "Imports a dataset")
+                (about: "Imports one or several datasets")
                 (@setting TrailingVarArg)
-                (@arg dataset: <DATASET> "Path to gtfs dataset")
+                (@arg dataset: <DATASET> +multiple "Paths to gtfs datasets")
                 (@arg shape_smoothing: --("shape-smoothing") [MODE] +case_insensitive
                     possible_values(&SmoothMode::variants()) default_value("full")
                     "Smooth mode for processing shapes")
+                (@arg shape_fallback: --("shape-fallback") [MODE] +case_insensitive
+                    possible_values(&FallbackMode::variants()) default_value("straight")
+                    "Mode for synthesizing shapes of trips without one")
+                (@arg merge_distance: --("merge-stations") [METERS]
+                    "Merges stations of different datasets within this distance and with similar names")
                 (@arg command: [COMMAND] +last +multiple "Command to run afterwards"))
             (@subcommand inspect =>
                 (about: "Inspects the imported dataset")
@@ -71,13 +139,75 @@ impl CommandRunner {
                     "Output format")
                 (@arg output: --output [FILE] "Path to output file")
             )
+            (@subcommand plausibility =>
+                (about: "Lists trips of the imported dataset with implausible speeds or duplicates")
+                (@arg agency_name: --agency [AGENCY] "Filter after agency name")
+                (@arg line_name: [LINE] "Line name to check")
+                (@arg output: --output [FILE] "Path to output file")
+            )
             (@subcommand store =>
                 (about: "Generates a binary export from the imported dataset")
                 (@arg profile: --profile [PROFILE]
                     possible_values(PROFILE_NAMES) default_value(DEFAULT_PROFILE_NAME)
                     "Profile used for exporting")
                 (@arg date: --date [DATE] {validate_date} default_value("2019-08-26")
-                    "Date in the format yyyy-mm-dd"))
+                    "Date in the format yyyy-mm-dd")
+                (@arg until: --until [DATE] {validate_date}
+                    "Last date to store as well, in the format yyyy-mm-dd")
+                (@arg realtime: --realtime [FILE] +multiple number_of_values(1)
+                    "GTFS Realtime feed with TripUpdates or ServiceAlerts to apply at the dates, \
+                    given as FEED=FILE with the imported feed it refers to if there are several")
+                (@arg utc_offset: --("utc-offset") [HOURS] default_value("0") allow_hyphen_values(true)
+                    "UTC offset of the timetable, used for absolute times in the realtime feed"))
+            (@subcommand validate =>
+                (about: "Checks a dataset for errors without importing it")
+                (@arg dataset: <DATASET> "Path to gtfs dataset")
+                (@arg max_stop_distance: --("max-stop-distance") [METERS] default_value("100")
+                    "Maximal distance of stops to the shape of their trips")
+                (@arg format: --format [FORMAT] +case_insensitive
+                    possible_values(&ReportFormat::variants()) default_value("text")
+                    "Report format")
+                (@arg output: --output [FILE] "Path to output file"))
+            (@subcommand simulate =>
+                (about: "Runs a binary export and writes GTFS Realtime vehicle positions")
+                (@arg binary: [BINARY] default_value("wasm/www/data.bin") "Path to stored data")
+                (@arg date: --date [DATE] {validate_date} default_value("2019-08-26")
+                    "Date to simulate, one of the dates the binary export was stored for")
+                (@arg from: --from [TIME] {validate_time} default_value("0:00")
+                    "Simulated time of the first snapshot")
+                (@arg until: --until [TIME] {validate_time} default_value("24:00")
+                    "Simulated time after which no more snapshots are written, may be past 24:00")
+                (@arg interval: --interval [SECONDS] default_value("30")
+                    "Simulated seconds between two snapshots")
+                (@arg utc_offset: --("utc-offset") [HOURS] default_value("0") allow_hyphen_values(true)
+                    "UTC offset of the timetable, used for the timestamps")
+                (@arg output: --output [DIRECTORY] default_value("vehicle_positions")
+                    "Directory where the snapshots are written")
+                (@arg serve: --serve [ADDRESS] conflicts_with("output")
+                    "Serves snapshots over HTTP instead, with the simulated time following the clock")
+                (@arg scenario: --scenario [FILE] "Disruption scenario to apply to the timetables")
+                (@arg demand: --demand [FILE]
+                    "Passenger flows between stations in JSON, reported as vehicle occupancy")
+                (@arg gravity: --gravity [PASSENGERS] conflicts_with("demand")
+                    "Synthesizes the passenger flows with a gravity model, totalling the passengers per hour"))
+            (@subcommand scenario =>
+                (about: "Applies a disruption scenario to a binary export and reports the affected trips and stops")
+                (@arg scenario: <SCENARIO> "Path to the scenario file in JSON")
+                (@arg binary: [BINARY] default_value("wasm/www/data.bin") "Path to stored data")
+                (@arg date: --date [DATE] {validate_date} default_value("2019-08-26")
+                    "Date to apply the scenario at, one of the dates the binary export was stored for")
+                (@arg output: --output [FILE] "Path to output file"))
+            (@subcommand departures =>
+                (about: "Lists the next departures from a station of a binary export")
+                (@arg station: <STATION> "Name of the station")
+                (@arg binary: [BINARY] default_value("wasm/www/data.bin") "Path to stored data")
+                (@arg date: --date [DATE] {validate_date} default_value("2019-08-26")
+                    "Date of the departures, one of the dates the binary export was stored for")
+                (@arg from: --from [TIME] {validate_time} default_value("0:00")
+                    "Time from which on departures are listed")
+                (@arg count: --count [COUNT] default_value("10") "Number of departures to list")
+                (@arg scenario: --scenario [FILE] "Disruption scenario to apply to the timetables")
+                (@arg output: --output [FILE] "Path to output file"))
             (@subcommand load =>
                 (about: "Loads a binary export to check for possible errors")
                 (@arg binary: [BINARY] default_value("wasm/www/data.bin") "Path to stored data")));
@@ -153,9 +283,22 @@ impl CommandRunner {
                     let app = self.build_app(true);
                     app.get_matches_from(command)
                 });
-                let path = import_matches.value_of_os("dataset").unwrap();
+                let paths = import_matches
+                    .values_of_os("dataset")
+                    .unwrap()
+                    .collect::<Vec<_>>();
                 let shape_smoothing = value_t!(import_matches, "shape_smoothing", SmoothMode)?;
-                self.dataset = Some(ImportedDataset::import(path, shape_smoothing)?);
+                let shape_fallback = value_t!(import_matches, "shape_fallback", FallbackMode)?;
+                let merge_distance = match import_matches.value_of("merge_distance") {
+                    Some(_) => Some(value_t!(import_matches, "merge_distance", f64)?),
+                    None => None,
+                };
+                self.dataset = Some(ImportedDataset::import_feeds(
+                    &paths,
+                    shape_smoothing,
+                    shape_fallback,
+                    merge_distance,
+                )?);
                 if let Some(command_matches) = command_matches {
                     return self.execute(command_matches);
                 }
@@ -165,13 +308,131 @@ impl CommandRunner {
                 let profile = store_matches.value_of("profile").unwrap().try_into()?;
                 let date_formatted = store_matches.value_of("date").unwrap();
                 let date = NaiveDate::parse_from_str(date_formatted, "%F")?;
+                let until = match store_matches.value_of("until") {
+                    Some(until) => NaiveDate::parse_from_str(until, "%F")?,
+                    None => date,
+                };
+                let days = (until - date).num_days() + 1;
+                if days < 1 || days as usize > storage::MAX_DAYS {
+                    return Err(Box::new(InvalidDateRangeError {
+                        first: date,
+                        last: until,
+                    }));
+                }
                 let file = File::create("wasm/www/data.bin")?;
-                self.dataset()?.store_into(file, profile, date)?;
+                match store_matches.values_of_os("realtime") {
+                    Some(paths) => {
+                        let utc_offset = value_t!(store_matches, "utc_offset", i32)?;
+                        let utc_offset = FixedOffset::east(utc_offset * 3600);
+                        let namespaces = self.dataset()?.namespaces();
+                        let mut realtime = Realtime::default();
+                        for value in paths {
+                            let (namespace, path) = split_feed(value);
+                            let known = match namespace {
+                                Some(namespace) => {
+                                    namespaces.iter().any(|known| known == namespace)
+                                }
+                                None => namespaces.is_empty(),
+                            };
+                            if !known {
+                                return Err(Box::new(UnknownFeedError {
+                                    feed: namespace.map(str::to_string),
+                                    feeds: namespaces.to_vec(),
+                                }));
+                            }
+                            realtime.extend(Realtime::read(path, utc_offset, namespace)?);
+                        }
+                        self.dataset()?
+                            .store_realtime_into(file, profile, date, until, &realtime)?;
+                    }
+                    None => self.dataset()?.store_into(file, profile, date, until)?,
+                }
             }
             ("load", Some(load_matches)) => {
                 let binary = load_matches.value_of_os("binary").unwrap();
                 load(binary)?;
             }
+            ("simulate", Some(simulate_matches)) => {
+                let binary = simulate_matches.value_of_os("binary").unwrap();
+                let date =
+                    NaiveDate::parse_from_str(simulate_matches.value_of("date").unwrap(), "%F")?;
+                let from = parse_time(simulate_matches.value_of("from").unwrap()).unwrap();
+                let until = parse_time(simulate_matches.value_of("until").unwrap()).unwrap();
+                let interval = value_t!(simulate_matches, "interval", u32)?.max(1);
+                let utc_offset = value_t!(simulate_matches, "utc_offset", i32)?;
+                let (mut dataset, first_date) = load_dataset_at(binary, date)?;
+                if let Some(path) = simulate_matches.value_of_os("scenario") {
+                    dataset.apply_scenario(&read_scenario(path)?);
+                }
+                if let Some(path) = simulate_matches.value_of_os("demand") {
+                    let ignored = dataset.set_demand(&read_demand(path)?);
+                    if ignored > 0 {
+                        println!(
+                            "Ignored {} flows with unknown or ambiguous stations",
+                            ignored
+                        );
+                    }
+                } else if simulate_matches.is_present("gravity") {
+                    dataset.set_gravity_demand(value_t!(simulate_matches, "gravity", f32)?);
+                }
+                let vehicle_positions =
+                    VehiclePositions::new(first_date, FixedOffset::east(utc_offset * 3600));
+                match simulate_matches.value_of("serve") {
+                    Some(address) => serve(dataset, vehicle_positions, from, interval, address)?,
+                    None => {
+                        let output = simulate_matches.value_of_os("output").unwrap();
+                        write_snapshots(dataset, vehicle_positions, from, until, interval, output)?;
+                    }
+                }
+            }
+            ("scenario", Some(scenario_matches)) => {
+                let scenario = read_scenario(scenario_matches.value_of_os("scenario").unwrap())?;
+                let binary = scenario_matches.value_of_os("binary").unwrap();
+                let date =
+                    NaiveDate::parse_from_str(scenario_matches.value_of("date").unwrap(), "%F")?;
+                let (mut dataset, _) = load_dataset_at(binary, date)?;
+                let mut output: Box<dyn Write> = match scenario_matches.value_of_os("output") {
+                    Some(path) => Box::new(File::create(path)?),
+                    None => Box::new(io::stdout()),
+                };
+                report_scenario(&mut dataset, &scenario, &mut output)?;
+            }
+            ("departures", Some(departures_matches)) => {
+                let station = departures_matches.value_of("station").unwrap();
+                let binary = departures_matches.value_of_os("binary").unwrap();
+                let date =
+                    NaiveDate::parse_from_str(departures_matches.value_of("date").unwrap(), "%F")?;
+                let from = parse_time(departures_matches.value_of("from").unwrap()).unwrap();
+                let count = value_t!(departures_matches, "count", usize)?;
+                let (mut dataset, _) = load_dataset_at(binary, date)?;
+                if let Some(path) = departures_matches.value_of_os("scenario") {
+                    dataset.apply_scenario(&read_scenario(path)?);
+                }
+                let mut output: Box<dyn Write> = match departures_matches.value_of_os("output") {
+                    Some(path) => Box::new(File::create(path)?),
+                    None => Box::new(io::stdout()),
+                };
+                list_departures(&dataset, station, from, count, &mut output)?;
+            }
+            ("plausibility", Some(plausibility_matches)) => {
+                let agency_name = plausibility_matches.value_of("agency_name");
+                let line_name = plausibility_matches.value_of("line_name");
+                let mut output: Box<dyn Write> = match plausibility_matches.value_of_os("output") {
+                    Some(path) => Box::new(File::create(path)?),
+                    None => Box::new(io::stdout()),
+                };
+                check_plausibility(self.dataset()?, agency_name, line_name, &mut output)?;
+            }
+            ("validate", Some(validate_matches)) => {
+                let path = validate_matches.value_of_os("dataset").unwrap();
+                let max_stop_distance = value_t!(validate_matches, "max_stop_distance", f64)?;
+                let format = value_t!(validate_matches, "format", ReportFormat)?;
+                let mut output: Box<dyn Write> = match validate_matches.value_of_os("output") {
+                    Some(path) => Box::new(File::create(path)?),
+                    None => Box::new(io::stdout()),
+                };
+                validate(path, max_stop_distance, format, &mut output)?;
+            }
             ("inspect", Some(inspect_matches)) => {
                 let line_name = inspect_matches.value_of("line_name").unwrap();
                 let agency_name = inspect_matches.value_of("agency_name");
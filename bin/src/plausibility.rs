@@ -0,0 +1,40 @@
+use std::io::{self, Write};
+
+use import::ImportedDataset;
+
+pub(crate) fn check_plausibility(
+    dataset: &ImportedDataset,
+    agency_name: Option<&str>,
+    line_name: Option<&str>,
+    output: &mut impl Write,
+) -> io::Result<()> {
+    let agencies = dataset.agencies().filter(|agency| match agency_name {
+        Some(agency_name) => agency.name().contains(agency_name),
+        None => true,
+    });
+
+    let mut implausible_lines = 0;
+    for agency in agencies {
+        let lines = agency.lines().iter().filter(|line| match line_name {
+            Some(line_name) => line.name() == line_name,
+            None => true,
+        });
+        for line in lines {
+            let implausibilities = line.implausibilities();
+            if implausibilities.is_empty() {
+                continue;
+            }
+
+            implausible_lines += 1;
+            writeln!(output, "{} ({}):", line.name(), agency.name())?;
+            for implausibility in implausibilities {
+                writeln!(output, "    {}", implausibility)?;
+            }
+        }
+    }
+    writeln!(
+        output,
+        "Found {} lines with implausible trips",
+        implausible_lines
+    )
+}
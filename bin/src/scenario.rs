@@ -0,0 +1,41 @@
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufReader, Write};
+use std::path::Path;
+
+use simulation::{Dataset, Impact, Scenario};
+
+pub(crate) fn read_scenario(path: impl AsRef<Path>) -> Result<Scenario, Box<dyn Error>> {
+    let file = File::open(path)?;
+    Ok(serde_json::from_reader(BufReader::new(file))?)
+}
+
+/// Applies the scenario and lists the trips and stops affected by each disruption.
+pub(crate) fn report_scenario(
+    dataset: &mut Dataset,
+    scenario: &Scenario,
+    output: &mut impl Write,
+) -> Result<(), Box<dyn Error>> {
+    if !scenario.name().is_empty() {
+        writeln!(output, "Scenario: {}", scenario.name())?;
+    }
+
+    let impacts = dataset.apply_scenario(scenario);
+    let mut total = Impact::default();
+    for (disruption, impact) in scenario.disruptions().iter().zip(impacts) {
+        match impact {
+            Some(impact) => {
+                writeln!(output, "{}: {}", disruption, impact)?;
+                total += impact;
+            }
+            None => writeln!(
+                output,
+                "{}: no line named {}",
+                disruption,
+                disruption.line()
+            )?,
+        }
+    }
+    writeln!(output, "Total: {}", total)?;
+    Ok(())
+}
@@ -0,0 +1,101 @@
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use import::realtime::VehiclePositions;
+use simulation::{Dataset, Demand};
+
+pub(crate) fn read_demand(path: impl AsRef<Path>) -> Result<Demand, Box<dyn Error>> {
+    let file = File::open(path)?;
+    Ok(serde_json::from_reader(BufReader::new(file))?)
+}
+
+/// Advances the simulation and writes a snapshot every interval of simulated time.
+pub(crate) fn write_snapshots(
+    mut dataset: Dataset,
+    vehicle_positions: VehiclePositions,
+    from: u32,
+    until: u32,
+    interval: u32,
+    directory: impl AsRef<Path>,
+) -> Result<(), Box<dyn Error>> {
+    let directory = directory.as_ref();
+    fs::create_dir_all(directory)?;
+
+    dataset.set_time(from);
+    let mut time = from;
+    let mut count = 0;
+    while time <= until {
+        let path = directory.join(format!(
+            "{:02}{:02}{:02}.pb",
+            time / 3600,
+            time / 60 % 60,
+            time % 60
+        ));
+        File::create(path)?.write_all(&vehicle_positions.encode(&dataset))?;
+        count += 1;
+
+        dataset.update(interval);
+        time += interval;
+    }
+    println!("Wrote {} snapshots to {}", count, directory.display());
+    Ok(())
+}
+
+/// Time a client may take to send its request before the connection is dropped.
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Reads the request of the connection and answers with the snapshot.
+///
+/// Clients which do not send a complete request in time are dropped, so that they cannot stall
+/// the others.
+fn respond(stream: io::Result<TcpStream>, snapshot: &[u8]) -> io::Result<()> {
+    let mut stream = stream?;
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut reader = BufReader::new(&stream);
+    let mut line = String::new();
+    while reader.read_line(&mut line)? > 2 {
+        line.clear();
+    }
+
+    write!(
+        stream,
+        "HTTP/1.1 200 OK\r\nContent-Type: application/x-protobuf\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        snapshot.len()
+    )?;
+    stream.write_all(snapshot)
+}
+
+/// Serves the latest snapshot over HTTP, with the simulated time following the wall clock.
+///
+/// Failed connections are logged without stopping the server.
+pub(crate) fn serve(
+    mut dataset: Dataset,
+    vehicle_positions: VehiclePositions,
+    from: u32,
+    interval: u32,
+    address: &str,
+) -> Result<(), Box<dyn Error>> {
+    let listener = TcpListener::bind(address)?;
+    println!("Serving vehicle positions on http://{}/", address);
+
+    dataset.set_time(from);
+    let mut time = from;
+    let started = Instant::now();
+    for stream in listener.incoming() {
+        let elapsed = started.elapsed().as_secs() as u32;
+        let current = from + elapsed / interval * interval;
+        if current > time {
+            dataset.update(current - time);
+            time = current;
+        }
+
+        if let Err(error) = respond(stream, &vehicle_positions.encode(&dataset)) {
+            eprintln!("Connection failed: {}", error);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,29 @@
+use std::error::Error;
+use std::ffi::OsStr;
+use std::io::Write;
+
+use clap::arg_enum;
+
+use import::validation::Validator;
+
+arg_enum! {
+    #[derive(Debug, Clone, Copy)]
+    pub enum ReportFormat {
+        Text,
+        Json,
+    }
+}
+
+pub(crate) fn validate(
+    path: impl AsRef<OsStr>,
+    max_stop_distance: f64,
+    format: ReportFormat,
+    output: &mut impl Write,
+) -> Result<(), Box<dyn Error>> {
+    let report = Validator::new(max_stop_distance).validate(path)?;
+    match format {
+        ReportFormat::Text => writeln!(output, "{}", report)?,
+        ReportFormat::Json => writeln!(output, "{}", report.to_json()?)?,
+    }
+    Ok(())
+}
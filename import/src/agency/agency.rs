@@ -5,13 +5,18 @@ create_id_type!(AgencyId);
 
 #[derive(Debug, PartialEq)]
 pub struct Agency {
+    id: AgencyId,
     name: String,
     lines: Vec<Line>,
 }
 
 impl Agency {
-    pub(crate) fn new(name: String, lines: Vec<Line>) -> Agency {
-        Agency { name, lines }
+    pub(crate) fn new(id: AgencyId, name: String, lines: Vec<Line>) -> Agency {
+        Agency { id, name, lines }
+    }
+
+    pub(crate) fn id(&self) -> &AgencyId {
+        &self.id
     }
 
     pub fn name(&self) -> &str {
@@ -29,6 +34,7 @@ pub(crate) mod fixtures {
 
     pub(crate) fn pubtrans(lines: Vec<Line>) -> Agency {
         Agency {
+            id: "1".into(),
             name: "Public Transport".to_string(),
             lines,
         }
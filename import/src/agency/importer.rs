@@ -1,9 +1,8 @@
 use std::collections::HashMap;
-use std::error::Error;
 
 use super::{Agency, AgencyId, AgencyRecord};
 use crate::utils::{Action, Dataset};
-use crate::Line;
+use crate::{ImportError, Line};
 
 pub(crate) struct Importer;
 
@@ -11,12 +10,12 @@ impl Importer {
     pub(crate) fn import(
         dataset: &mut impl Dataset,
         mut lines: HashMap<AgencyId, Vec<Line>>,
-    ) -> Result<Vec<Agency>, Box<dyn Error>> {
+    ) -> Result<Vec<Agency>, ImportError> {
         let mut agencies = Vec::new();
 
         let action = Action::start("Importing agencies");
         for result in action.read_csv(dataset, "agency.txt")? {
-            let record: AgencyRecord = result?;
+            let (_, record): (_, AgencyRecord) = result?;
             let agency = record.import(&mut lines);
             agencies.push(agency);
         }
@@ -14,7 +14,7 @@ pub(super) struct AgencyRecord {
 impl AgencyRecord {
     pub(super) fn import(self, lines: &mut HashMap<AgencyId, Vec<Line>>) -> Agency {
         let lines = lines.remove(&self.agency_id).unwrap_or_else(Vec::new);
-        Agency::new(self.agency_name, lines)
+        Agency::new(self.agency_id, self.agency_name, lines)
     }
 }
 
@@ -2,7 +2,7 @@ use std::fmt;
 
 use geomorph::coord::Coord;
 use geomorph::utm::Utm;
-use na::Point2;
+use na::{Point2, Vector2};
 
 pub type Point = Point2<f64>;
 
@@ -23,6 +23,11 @@ pub fn transform(point: Point) -> Point2<f32> {
     Point2::new(translated.x.round() as f32, -translated.y.round() as f32)
 }
 
+pub fn transform_back(point: Point2<f32>) -> Point {
+    let translated = Vector2::new(f64::from(point.x), -f64::from(point.y));
+    project(52.51, 13.39) + translated
+}
+
 pub(crate) struct PointDebug {
     position: Point,
     precision: usize,
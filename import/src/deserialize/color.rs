@@ -26,19 +26,24 @@ impl<'de> Visitor<'de> for ColorVisitor {
             )));
         }
 
-        let component = |number: usize| {
-            let slice = &value[2 * number + 1..=2 * number + 2];
-            u8::from_str_radix(slice, 16).map_err(|_| {
-                E::custom(format_args!(
-                    "invalid hex string: {}, invalid digit in {}",
-                    value, slice
-                ))
-            })
-        };
-        Ok(Color::new(component(0)?, component(1)?, component(2)?))
+        parse_hex_digits(value, &value[1..])
     }
 }
 
+/// Parses the six hex digits of a color, reporting errors against the whole value.
+pub(super) fn parse_hex_digits<E: DeserializeError>(value: &str, digits: &str) -> Result<Color, E> {
+    let component = |number: usize| {
+        let slice = &digits[2 * number..2 * number + 2];
+        u8::from_str_radix(slice, 16).map_err(|_| {
+            E::custom(format_args!(
+                "invalid hex string: {}, invalid digit in {}",
+                value, slice
+            ))
+        })
+    };
+    Ok(Color::new(component(0)?, component(1)?, component(2)?))
+}
+
 pub(crate) fn color<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
     deserializer.deserialize_str(ColorVisitor)
 }
@@ -34,10 +34,37 @@ impl<'de> Visitor<'de> for DurationVisitor {
     }
 }
 
+struct OptionalDurationVisitor;
+
+impl<'de> Visitor<'de> for OptionalDurationVisitor {
+    type Value = Option<Duration>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("time string or empty string")
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Option<Duration>, E>
+    where
+        E: DeserializeError,
+    {
+        match value {
+            "" => Ok(None),
+            _ => DurationVisitor.visit_str(value).map(Some),
+        }
+    }
+}
+
 pub(crate) fn duration<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
     deserializer.deserialize_str(DurationVisitor)
 }
 
+/// Deserializes an optional time as used in `stop_times.txt`, where an empty value means unset.
+pub(crate) fn optional_duration<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Duration>, D::Error> {
+    deserializer.deserialize_str(OptionalDurationVisitor)
+}
+
 #[cfg(test)]
 mod tests {
     use serde::de::value::{Error as ValueError, StrDeserializer, U64Deserializer};
@@ -84,4 +111,15 @@ mod tests {
             "invalid type: integer `0`, expected time string"
         );
     }
+
+    #[test]
+    fn test_optional() {
+        let deserializer: StrDeserializer<ValueError> = "1:34:56".into_deserializer();
+        assert_eq!(
+            optional_duration(deserializer),
+            Ok(Some(from_hms(1, 34, 56)))
+        );
+        let deserializer: StrDeserializer<ValueError> = "".into_deserializer();
+        assert_eq!(optional_duration(deserializer), Ok(None));
+    }
 }
@@ -0,0 +1,85 @@
+use std::fmt;
+
+use serde::de::{Error as DeserializeError, Visitor};
+use serde::Deserializer;
+
+use super::color::parse_hex_digits;
+use simulation::Color;
+
+struct GtfsColorVisitor;
+
+impl<'de> Visitor<'de> for GtfsColorVisitor {
+    type Value = Option<Color>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("color hex string without leading #")
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Option<Color>, E>
+    where
+        E: DeserializeError,
+    {
+        match value.len() {
+            0 => Ok(None),
+            6 => parse_hex_digits(value, value).map(Some),
+            length => Err(E::custom(format_args!(
+                "invalid hex string: {}, expected 6 instead of {} characters",
+                value, length
+            ))),
+        }
+    }
+}
+
+/// Deserializes an optional color as used in `routes.txt`, where an empty value means unset.
+pub(crate) fn gtfs_color<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Color>, D::Error> {
+    deserializer.deserialize_str(GtfsColorVisitor)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::de::value::{Error as ValueError, StrDeserializer, U64Deserializer};
+    use serde::de::IntoDeserializer;
+
+    use super::*;
+
+    #[test]
+    fn test_six_digit_hex() {
+        let deserializer: StrDeserializer<ValueError> = "FF0420".into_deserializer();
+        assert_eq!(gtfs_color(deserializer), Ok(Some(Color::new(255, 4, 32))));
+    }
+
+    #[test]
+    fn test_empty() {
+        let deserializer: StrDeserializer<ValueError> = "".into_deserializer();
+        assert_eq!(gtfs_color(deserializer), Ok(None));
+    }
+
+    #[test]
+    fn test_invalid_digit() {
+        let deserializer: StrDeserializer<ValueError> = "12345g".into_deserializer();
+        assert_eq!(
+            gtfs_color(deserializer).unwrap_err().to_string(),
+            "invalid hex string: 12345g, invalid digit in 5g"
+        );
+    }
+
+    #[test]
+    fn test_leading_hash() {
+        let deserializer: StrDeserializer<ValueError> = "#ff0420".into_deserializer();
+        assert_eq!(
+            gtfs_color(deserializer).unwrap_err().to_string(),
+            "invalid hex string: #ff0420, expected 6 instead of 7 characters"
+        );
+    }
+
+    #[test]
+    fn test_invalid_type() {
+        let deserializer: U64Deserializer<ValueError> = 0u64.into_deserializer();
+        assert_eq!(
+            gtfs_color(deserializer).unwrap_err().to_string(),
+            "invalid type: integer `0`, expected color hex string without leading #"
+        );
+    }
+}
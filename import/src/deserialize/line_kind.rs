@@ -19,12 +19,37 @@ impl<'de> Visitor<'de> for LineKindVisitor {
         E: DeserializeError,
     {
         match value {
-            100 => Ok(LineKind::Railway),
+            // basic route types
+            0 => Ok(LineKind::Tram),
+            1 => Ok(LineKind::UrbanRailway),
+            2 => Ok(LineKind::Railway),
+            3 => Ok(LineKind::Bus),
+            4 => Ok(LineKind::WaterTransport),
+            5 => Ok(LineKind::CableTram),
+            6 => Ok(LineKind::AerialLift),
+            7 => Ok(LineKind::Funicular),
+            11 => Ok(LineKind::Trolleybus),
+            12 => Ok(LineKind::Monorail),
+            // extended route types
+            106 => Ok(LineKind::RegionalRailway),
             109 => Ok(LineKind::SuburbanRailway),
-            400 => Ok(LineKind::UrbanRailway),
-            3 | 700 => Ok(LineKind::Bus),
-            900 => Ok(LineKind::Tram),
-            1000 => Ok(LineKind::WaterTransport),
+            100..=117 => Ok(LineKind::Railway),
+            200..=209 => Ok(LineKind::Coach),
+            300 => Ok(LineKind::SuburbanRailway),
+            405 => Ok(LineKind::Monorail),
+            400..=404 => Ok(LineKind::UrbanRailway),
+            717 => Ok(LineKind::Taxi),
+            700..=716 => Ok(LineKind::Bus),
+            800 => Ok(LineKind::Trolleybus),
+            900..=906 => Ok(LineKind::Tram),
+            1000..=1021 | 1200 => Ok(LineKind::WaterTransport),
+            1100..=1114 => Ok(LineKind::Air),
+            1300..=1307 => Ok(LineKind::AerialLift),
+            1400..=1402 => Ok(LineKind::Funicular),
+            1500..=1507 | 1600..=1604 => Ok(LineKind::Taxi),
+            1701 => Ok(LineKind::CableTram),
+            // miscellaneous services such as horse-drawn carriages run on roads
+            1700 | 1702 => Ok(LineKind::Bus),
             _ => Err(E::custom(format!("unknown line kind of value: {}", value))),
         }
     }
@@ -62,11 +87,83 @@ mod tests {
     }
 
     #[test]
-    fn test_unknown_line_kind() {
+    fn test_deserialize_basic() {
         let deserializer: U64Deserializer<ValueError> = 0u64.into_deserializer();
+        assert_eq!(line_kind(deserializer), Ok(LineKind::Tram));
+        let deserializer: U64Deserializer<ValueError> = 1u64.into_deserializer();
+        assert_eq!(line_kind(deserializer), Ok(LineKind::UrbanRailway));
+        let deserializer: U64Deserializer<ValueError> = 2u64.into_deserializer();
+        assert_eq!(line_kind(deserializer), Ok(LineKind::Railway));
+        let deserializer: U64Deserializer<ValueError> = 4u64.into_deserializer();
+        assert_eq!(line_kind(deserializer), Ok(LineKind::WaterTransport));
+        let deserializer: U64Deserializer<ValueError> = 5u64.into_deserializer();
+        assert_eq!(line_kind(deserializer), Ok(LineKind::CableTram));
+        let deserializer: U64Deserializer<ValueError> = 6u64.into_deserializer();
+        assert_eq!(line_kind(deserializer), Ok(LineKind::AerialLift));
+        let deserializer: U64Deserializer<ValueError> = 7u64.into_deserializer();
+        assert_eq!(line_kind(deserializer), Ok(LineKind::Funicular));
+        let deserializer: U64Deserializer<ValueError> = 11u64.into_deserializer();
+        assert_eq!(line_kind(deserializer), Ok(LineKind::Trolleybus));
+        let deserializer: U64Deserializer<ValueError> = 12u64.into_deserializer();
+        assert_eq!(line_kind(deserializer), Ok(LineKind::Monorail));
+    }
+
+    #[test]
+    fn test_deserialize_extended() {
+        let deserializer: U64Deserializer<ValueError> = 102u64.into_deserializer();
+        assert_eq!(line_kind(deserializer), Ok(LineKind::Railway));
+        let deserializer: U64Deserializer<ValueError> = 106u64.into_deserializer();
+        assert_eq!(line_kind(deserializer), Ok(LineKind::RegionalRailway));
+        let deserializer: U64Deserializer<ValueError> = 200u64.into_deserializer();
+        assert_eq!(line_kind(deserializer), Ok(LineKind::Coach));
+        let deserializer: U64Deserializer<ValueError> = 401u64.into_deserializer();
+        assert_eq!(line_kind(deserializer), Ok(LineKind::UrbanRailway));
+        let deserializer: U64Deserializer<ValueError> = 405u64.into_deserializer();
+        assert_eq!(line_kind(deserializer), Ok(LineKind::Monorail));
+        let deserializer: U64Deserializer<ValueError> = 715u64.into_deserializer();
+        assert_eq!(line_kind(deserializer), Ok(LineKind::Bus));
+        let deserializer: U64Deserializer<ValueError> = 800u64.into_deserializer();
+        assert_eq!(line_kind(deserializer), Ok(LineKind::Trolleybus));
+        let deserializer: U64Deserializer<ValueError> = 1100u64.into_deserializer();
+        assert_eq!(line_kind(deserializer), Ok(LineKind::Air));
+        let deserializer: U64Deserializer<ValueError> = 1200u64.into_deserializer();
+        assert_eq!(line_kind(deserializer), Ok(LineKind::WaterTransport));
+        let deserializer: U64Deserializer<ValueError> = 1301u64.into_deserializer();
+        assert_eq!(line_kind(deserializer), Ok(LineKind::AerialLift));
+        let deserializer: U64Deserializer<ValueError> = 1400u64.into_deserializer();
+        assert_eq!(line_kind(deserializer), Ok(LineKind::Funicular));
+        let deserializer: U64Deserializer<ValueError> = 1501u64.into_deserializer();
+        assert_eq!(line_kind(deserializer), Ok(LineKind::Taxi));
+    }
+
+    #[test]
+    fn test_deserialize_full_extended() {
+        let deserializer: U64Deserializer<ValueError> = 300u64.into_deserializer();
+        assert_eq!(line_kind(deserializer), Ok(LineKind::SuburbanRailway));
+        let deserializer: U64Deserializer<ValueError> = 717u64.into_deserializer();
+        assert_eq!(line_kind(deserializer), Ok(LineKind::Taxi));
+        let deserializer: U64Deserializer<ValueError> = 1012u64.into_deserializer();
+        assert_eq!(line_kind(deserializer), Ok(LineKind::WaterTransport));
+        let deserializer: U64Deserializer<ValueError> = 1102u64.into_deserializer();
+        assert_eq!(line_kind(deserializer), Ok(LineKind::Air));
+        let deserializer: U64Deserializer<ValueError> = 1401u64.into_deserializer();
+        assert_eq!(line_kind(deserializer), Ok(LineKind::Funicular));
+        let deserializer: U64Deserializer<ValueError> = 1601u64.into_deserializer();
+        assert_eq!(line_kind(deserializer), Ok(LineKind::Taxi));
+        let deserializer: U64Deserializer<ValueError> = 1700u64.into_deserializer();
+        assert_eq!(line_kind(deserializer), Ok(LineKind::Bus));
+        let deserializer: U64Deserializer<ValueError> = 1701u64.into_deserializer();
+        assert_eq!(line_kind(deserializer), Ok(LineKind::CableTram));
+        let deserializer: U64Deserializer<ValueError> = 1702u64.into_deserializer();
+        assert_eq!(line_kind(deserializer), Ok(LineKind::Bus));
+    }
+
+    #[test]
+    fn test_unknown_line_kind() {
+        let deserializer: U64Deserializer<ValueError> = 8u64.into_deserializer();
         assert_eq!(
             line_kind(deserializer).unwrap_err().to_string(),
-            "unknown line kind of value: 0"
+            "unknown line kind of value: 8"
         );
     }
 
@@ -1,13 +1,19 @@
 mod color;
 mod direction;
 mod duration;
+mod gtfs_color;
 mod line_kind;
 mod naive_date;
 mod numeric_bool;
+mod pathway_mode;
+mod transfer_kind;
 
 pub(crate) use color::color;
 pub(crate) use direction::direction;
-pub(crate) use duration::duration;
+pub(crate) use duration::{duration, optional_duration};
+pub(crate) use gtfs_color::gtfs_color;
 pub(crate) use line_kind::line_kind;
-pub(crate) use naive_date::naive_date;
+pub(crate) use naive_date::{naive_date, optional_naive_date};
 pub(crate) use numeric_bool::numeric_bool;
+pub(crate) use pathway_mode::pathway_mode;
+pub(crate) use transfer_kind::transfer_kind;
@@ -22,6 +22,26 @@ impl<'de> Visitor<'de> for NaiveDateVisitor {
     }
 }
 
+struct OptionalNaiveDateVisitor;
+
+impl<'de> Visitor<'de> for OptionalNaiveDateVisitor {
+    type Value = Option<NaiveDate>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("date string or empty string")
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Option<NaiveDate>, E>
+    where
+        E: DeserializeError,
+    {
+        match value {
+            "" => Ok(None),
+            _ => NaiveDateVisitor.visit_str(value).map(Some),
+        }
+    }
+}
+
 pub(crate) fn naive_date<'de, D>(deserializer: D) -> Result<NaiveDate, D::Error>
 where
     D: Deserializer<'de>,
@@ -29,6 +49,14 @@ where
     deserializer.deserialize_str(NaiveDateVisitor)
 }
 
+/// Deserializes an optional date, where an empty value means unset.
+pub(crate) fn optional_naive_date<'de, D>(deserializer: D) -> Result<Option<NaiveDate>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_str(OptionalNaiveDateVisitor)
+}
+
 #[cfg(test)]
 mod tests {
     use serde::de::value::{Error as ValueError, StrDeserializer, U64Deserializer};
@@ -54,6 +82,17 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_optional_date() {
+        let deserializer: StrDeserializer<ValueError> = "20190711".into_deserializer();
+        assert_eq!(
+            optional_naive_date(deserializer),
+            Ok(Some(NaiveDate::from_ymd(2019, 7, 11)))
+        );
+        let deserializer: StrDeserializer<ValueError> = "".into_deserializer();
+        assert_eq!(optional_naive_date(deserializer), Ok(None));
+    }
+
     #[test]
     fn test_invalid_type() {
         let deserializer: U64Deserializer<ValueError> = 0u64.into_deserializer();
@@ -0,0 +1,85 @@
+use std::fmt;
+
+use serde::de::{Error as DeserializeError, Visitor};
+use serde::Deserializer;
+
+use simulation::complex::PathwayMode;
+
+struct PathwayModeVisitor;
+
+impl<'de> Visitor<'de> for PathwayModeVisitor {
+    type Value = PathwayMode;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("integer between 1 and 7")
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<PathwayMode, E>
+    where
+        E: DeserializeError,
+    {
+        match value {
+            1 => Ok(PathwayMode::Walkway),
+            2 => Ok(PathwayMode::Stairs),
+            3 => Ok(PathwayMode::MovingSidewalk),
+            4 => Ok(PathwayMode::Escalator),
+            5 => Ok(PathwayMode::Elevator),
+            6 => Ok(PathwayMode::FareGate),
+            7 => Ok(PathwayMode::ExitGate),
+            _ => Err(E::custom(format!(
+                "unknown pathway mode of value: {}",
+                value
+            ))),
+        }
+    }
+}
+
+pub(crate) fn pathway_mode<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<PathwayMode, D::Error> {
+    deserializer.deserialize_u64(PathwayModeVisitor)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::de::value::{Error as ValueError, StrDeserializer, U64Deserializer};
+    use serde::de::IntoDeserializer;
+
+    use super::*;
+
+    #[test]
+    fn test_deserialize() {
+        let deserializer: U64Deserializer<ValueError> = 1u64.into_deserializer();
+        assert_eq!(pathway_mode(deserializer), Ok(PathwayMode::Walkway));
+        let deserializer: U64Deserializer<ValueError> = 2u64.into_deserializer();
+        assert_eq!(pathway_mode(deserializer), Ok(PathwayMode::Stairs));
+        let deserializer: U64Deserializer<ValueError> = 3u64.into_deserializer();
+        assert_eq!(pathway_mode(deserializer), Ok(PathwayMode::MovingSidewalk));
+        let deserializer: U64Deserializer<ValueError> = 4u64.into_deserializer();
+        assert_eq!(pathway_mode(deserializer), Ok(PathwayMode::Escalator));
+        let deserializer: U64Deserializer<ValueError> = 5u64.into_deserializer();
+        assert_eq!(pathway_mode(deserializer), Ok(PathwayMode::Elevator));
+        let deserializer: U64Deserializer<ValueError> = 6u64.into_deserializer();
+        assert_eq!(pathway_mode(deserializer), Ok(PathwayMode::FareGate));
+        let deserializer: U64Deserializer<ValueError> = 7u64.into_deserializer();
+        assert_eq!(pathway_mode(deserializer), Ok(PathwayMode::ExitGate));
+    }
+
+    #[test]
+    fn test_unknown_pathway_mode() {
+        let deserializer: U64Deserializer<ValueError> = 0u64.into_deserializer();
+        assert_eq!(
+            pathway_mode(deserializer).unwrap_err().to_string(),
+            "unknown pathway mode of value: 0"
+        );
+    }
+
+    #[test]
+    fn test_empty() {
+        let deserializer: StrDeserializer<ValueError> = "".into_deserializer();
+        assert_eq!(
+            pathway_mode(deserializer).unwrap_err().to_string(),
+            "invalid type: string \"\", expected integer between 1 and 7"
+        );
+    }
+}
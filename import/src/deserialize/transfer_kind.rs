@@ -0,0 +1,117 @@
+use std::fmt;
+
+use serde::de::{Error as DeserializeError, Unexpected, Visitor};
+use serde::Deserializer;
+
+use simulation::transfer::Kind as TransferKind;
+
+struct TransferKindVisitor;
+
+impl<'de> Visitor<'de> for TransferKindVisitor {
+    type Value = TransferKind;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("integer between 0 and 5")
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<TransferKind, E>
+    where
+        E: DeserializeError,
+    {
+        match value {
+            0 => Ok(TransferKind::Recommended),
+            1 => Ok(TransferKind::Timed),
+            2 => Ok(TransferKind::MinimumTime),
+            3 => Ok(TransferKind::Forbidden),
+            // staying seated in the vehicle is as good as a timed transfer
+            4 => Ok(TransferKind::Timed),
+            5 => Ok(TransferKind::Forbidden),
+            _ => Err(E::custom(format!(
+                "unknown transfer kind of value: {}",
+                value
+            ))),
+        }
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<TransferKind, E>
+    where
+        E: DeserializeError,
+    {
+        match value {
+            "" => Ok(TransferKind::Recommended),
+            _ => match value.parse() {
+                Ok(value) => self.visit_u64(value),
+                Err(_) => Err(E::invalid_value(Unexpected::Str(value), &self)),
+            },
+        }
+    }
+}
+
+/// Deserializes the kind of a transfer, where an empty value means a recommended transfer.
+pub(crate) fn transfer_kind<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<TransferKind, D::Error> {
+    deserializer.deserialize_str(TransferKindVisitor)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::de::value::{Error as ValueError, StrDeserializer, U64Deserializer};
+    use serde::de::IntoDeserializer;
+
+    use super::*;
+
+    #[test]
+    fn test_deserialize() {
+        let deserializer: U64Deserializer<ValueError> = 0u64.into_deserializer();
+        assert_eq!(transfer_kind(deserializer), Ok(TransferKind::Recommended));
+        let deserializer: U64Deserializer<ValueError> = 1u64.into_deserializer();
+        assert_eq!(transfer_kind(deserializer), Ok(TransferKind::Timed));
+        let deserializer: U64Deserializer<ValueError> = 2u64.into_deserializer();
+        assert_eq!(transfer_kind(deserializer), Ok(TransferKind::MinimumTime));
+        let deserializer: U64Deserializer<ValueError> = 3u64.into_deserializer();
+        assert_eq!(transfer_kind(deserializer), Ok(TransferKind::Forbidden));
+    }
+
+    #[test]
+    fn test_in_seat_transfer() {
+        let deserializer: U64Deserializer<ValueError> = 4u64.into_deserializer();
+        assert_eq!(transfer_kind(deserializer), Ok(TransferKind::Timed));
+    }
+
+    #[test]
+    fn test_in_seat_transfer_not_allowed() {
+        let deserializer: U64Deserializer<ValueError> = 5u64.into_deserializer();
+        assert_eq!(transfer_kind(deserializer), Ok(TransferKind::Forbidden));
+    }
+
+    #[test]
+    fn test_unknown_transfer_kind() {
+        let deserializer: U64Deserializer<ValueError> = 6u64.into_deserializer();
+        assert_eq!(
+            transfer_kind(deserializer).unwrap_err().to_string(),
+            "unknown transfer kind of value: 6"
+        );
+    }
+
+    #[test]
+    fn test_str() {
+        let deserializer: StrDeserializer<ValueError> = "2".into_deserializer();
+        assert_eq!(transfer_kind(deserializer), Ok(TransferKind::MinimumTime));
+    }
+
+    #[test]
+    fn test_empty() {
+        let deserializer: StrDeserializer<ValueError> = "".into_deserializer();
+        assert_eq!(transfer_kind(deserializer), Ok(TransferKind::Recommended));
+    }
+
+    #[test]
+    fn test_invalid_str() {
+        let deserializer: StrDeserializer<ValueError> = "a".into_deserializer();
+        assert_eq!(
+            transfer_kind(deserializer).unwrap_err().to_string(),
+            "invalid value: string \"a\", expected integer between 0 and 5"
+        );
+    }
+}
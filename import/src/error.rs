@@ -0,0 +1,179 @@
+use std::error::Error;
+use std::fmt;
+use std::path::PathBuf;
+
+use chrono::NaiveDate;
+
+/// Reasons why a GTFS feed could not be imported.
+///
+/// Errors about a single record carry the name of the GTFS file and the line of the record
+/// within it, so that broken feeds can be fixed by hand.
+#[derive(Debug)]
+pub enum ImportError {
+    /// The dataset archive or directory could not be opened.
+    Archive {
+        path: PathBuf,
+        source: Box<dyn Error>,
+    },
+    /// A required file is not part of the dataset.
+    MissingFile { file: &'static str },
+    /// A file is part of the dataset, but could not be read.
+    Unreadable {
+        file: &'static str,
+        source: Box<dyn Error>,
+    },
+    /// A record could not be parsed.
+    Parse {
+        file: &'static str,
+        line: Option<u64>,
+        source: csv::Error,
+    },
+    /// A record refers to an id which is not defined by the feed.
+    DanglingReference {
+        file: &'static str,
+        line: u64,
+        field: &'static str,
+        id: String,
+    },
+    /// A record is well-formed, but contradicts the rest of the feed.
+    InvalidRecord {
+        file: &'static str,
+        line: u64,
+        field: &'static str,
+        id: String,
+        reason: &'static str,
+    },
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportError::Archive { path, source } => {
+                write!(formatter, "could not open {}: {}", path.display(), source)
+            }
+            ImportError::MissingFile { file } => write!(formatter, "missing file {}", file),
+            ImportError::Unreadable { file, source } => {
+                write!(formatter, "could not read {}: {}", file, source)
+            }
+            ImportError::Parse {
+                file,
+                line: Some(line),
+                source,
+            } => write!(formatter, "{}, line {}: {}", file, line, source),
+            ImportError::Parse {
+                file,
+                line: None,
+                source,
+            } => write!(formatter, "{}: {}", file, source),
+            ImportError::DanglingReference {
+                file,
+                line,
+                field,
+                id,
+            } => write!(
+                formatter,
+                "{}, line {}: {} {} not found",
+                file, line, field, id
+            ),
+            ImportError::InvalidRecord {
+                file,
+                line,
+                field,
+                id,
+                reason,
+            } => write!(
+                formatter,
+                "{}, line {}: {} {} {}",
+                file, line, field, id, reason
+            ),
+        }
+    }
+}
+
+impl Error for ImportError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ImportError::Archive { source, .. } | ImportError::Unreadable { source, .. } => {
+                Some(source.as_ref())
+            }
+            ImportError::Parse { source, .. } => Some(source),
+            ImportError::MissingFile { .. }
+            | ImportError::DanglingReference { .. }
+            | ImportError::InvalidRecord { .. } => None,
+        }
+    }
+}
+
+/// The dates to store a dataset for do not span between 1 and `storage::MAX_DAYS` days.
+#[derive(Debug)]
+pub struct InvalidDateRangeError {
+    pub first: NaiveDate,
+    pub last: NaiveDate,
+}
+
+impl fmt::Display for InvalidDateRangeError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            formatter,
+            "cannot store from {} until {}, the range must span between 1 and {} days",
+            self.first,
+            self.last,
+            storage::MAX_DAYS
+        )
+    }
+}
+
+impl Error for InvalidDateRangeError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_dangling_reference() {
+        let error = ImportError::DanglingReference {
+            file: "trips.txt",
+            line: 3,
+            field: "route_id",
+            id: "u4".to_string(),
+        };
+        assert_eq!(
+            error.to_string(),
+            "trips.txt, line 3: route_id u4 not found"
+        );
+        assert!(error.source().is_none());
+    }
+
+    #[test]
+    fn test_display_invalid_record() {
+        let error = ImportError::InvalidRecord {
+            file: "stops.txt",
+            line: 2,
+            field: "stop_id",
+            id: "hauptbahnhof".to_string(),
+            reason: "is a station and must not have a parent station",
+        };
+        assert_eq!(
+            error.to_string(),
+            "stops.txt, line 2: stop_id hauptbahnhof is a station and must not have a parent station"
+        );
+    }
+
+    #[test]
+    fn test_display_missing_file() {
+        let error = ImportError::MissingFile { file: "stops.txt" };
+        assert_eq!(error.to_string(), "missing file stops.txt");
+    }
+
+    #[test]
+    fn test_display_invalid_date_range() {
+        let error = InvalidDateRangeError {
+            first: NaiveDate::from_ymd(2020, 1, 2),
+            last: NaiveDate::from_ymd(2020, 1, 1),
+        };
+        assert_eq!(
+            error.to_string(),
+            "cannot store from 2020-01-02 until 2020-01-01, the range must span between 1 and 63 days"
+        );
+    }
+}
@@ -0,0 +1,60 @@
+use chrono::NaiveDate;
+
+/// Publisher and validity of a feed, as given in `feed_info.txt`.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct FeedInfo {
+    publisher_name: String,
+    publisher_url: String,
+    version: Option<String>,
+    start_date: Option<NaiveDate>,
+    end_date: Option<NaiveDate>,
+}
+
+impl FeedInfo {
+    pub(crate) fn new(
+        publisher_name: String,
+        publisher_url: String,
+        version: Option<String>,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Self {
+        Self {
+            publisher_name,
+            publisher_url,
+            version,
+            start_date,
+            end_date,
+        }
+    }
+
+    pub(crate) fn store(&self) -> storage::FeedInfo {
+        let format = |date: NaiveDate| date.format("%F").to_string();
+        storage::FeedInfo::new(
+            self.publisher_name.clone(),
+            self.publisher_url.clone(),
+            self.version.clone(),
+            self.start_date.map(format),
+            self.end_date.map(format),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_store() {
+        let feed_info = FeedInfo::new(
+            "VBB".to_string(),
+            "https://www.vbb.de".to_string(),
+            Some("2019-08".to_string()),
+            Some(NaiveDate::from_ymd(2019, 8, 1)),
+            Some(NaiveDate::from_ymd(2019, 12, 31)),
+        );
+        assert_eq!(
+            feed_info.store(),
+            storage::fixtures::metadata::vbb().feeds()[0]
+        );
+    }
+}
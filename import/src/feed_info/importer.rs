@@ -0,0 +1,80 @@
+use super::{FeedInfo, FeedInfoRecord};
+use crate::utils::{Action, Dataset};
+use crate::ImportError;
+
+pub(crate) struct Importer;
+
+impl Importer {
+    /// Imports the optional `feed_info.txt`, which describes the whole feed in its first row.
+    pub(crate) fn import(dataset: &mut impl Dataset) -> Result<Option<FeedInfo>, ImportError> {
+        if !dataset.contains("feed_info.txt") {
+            return Ok(None);
+        }
+
+        let action = Action::start("Importing feed info");
+        let feed_info = match action.read_csv(dataset, "feed_info.txt")?.next() {
+            Some(result) => {
+                let (_, record): (_, FeedInfoRecord) = result?;
+                Some(record.import())
+            }
+            None => None,
+        };
+        action.complete("Imported feed info");
+        Ok(feed_info)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::dataset;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_from_csv() {
+        let mut dataset = dataset!(
+            feed_info:
+                feed_publisher_name, feed_publisher_url,   feed_lang, feed_start_date, feed_end_date, feed_version;
+                "VBB",               "https://www.vbb.de", "de",      20190801,        20191231,      "2019-08"
+        );
+
+        assert_eq!(
+            Importer::import(&mut dataset).unwrap(),
+            Some(FeedInfo::new(
+                "VBB".to_string(),
+                "https://www.vbb.de".to_string(),
+                Some("2019-08".to_string()),
+                Some(NaiveDate::from_ymd(2019, 8, 1)),
+                Some(NaiveDate::from_ymd(2019, 12, 31)),
+            ))
+        );
+    }
+
+    #[test]
+    fn test_without_dates() {
+        let mut dataset = dataset!(
+            feed_info:
+                feed_publisher_name, feed_publisher_url,   feed_lang;
+                "VBB",               "https://www.vbb.de", "de"
+        );
+
+        assert_eq!(
+            Importer::import(&mut dataset).unwrap(),
+            Some(FeedInfo::new(
+                "VBB".to_string(),
+                "https://www.vbb.de".to_string(),
+                None,
+                None,
+                None,
+            ))
+        );
+    }
+
+    #[test]
+    fn test_missing_file() {
+        let mut dataset = dataset!();
+        assert_eq!(Importer::import(&mut dataset).unwrap(), None);
+    }
+}
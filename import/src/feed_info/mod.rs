@@ -0,0 +1,8 @@
+mod feed_info;
+mod importer;
+mod record;
+
+use record::FeedInfoRecord;
+
+pub(crate) use feed_info::FeedInfo;
+pub(crate) use importer::Importer;
@@ -0,0 +1,29 @@
+use serde_derive::Deserialize;
+
+use chrono::NaiveDate;
+
+use super::FeedInfo;
+use crate::deserialize;
+
+#[derive(Debug, Deserialize)]
+pub(super) struct FeedInfoRecord {
+    feed_publisher_name: String,
+    feed_publisher_url: String,
+    feed_version: Option<String>,
+    #[serde(default, deserialize_with = "deserialize::optional_naive_date")]
+    feed_start_date: Option<NaiveDate>,
+    #[serde(default, deserialize_with = "deserialize::optional_naive_date")]
+    feed_end_date: Option<NaiveDate>,
+}
+
+impl FeedInfoRecord {
+    pub(super) fn import(self) -> FeedInfo {
+        FeedInfo::new(
+            self.feed_publisher_name,
+            self.feed_publisher_url,
+            self.feed_version,
+            self.feed_start_date,
+            self.feed_end_date,
+        )
+    }
+}
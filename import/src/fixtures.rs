@@ -1,6 +1,6 @@
 pub(crate) use crate::agency::fixtures::agencies;
 pub(crate) use crate::line::fixtures::{incomplete_lines, lines};
-pub(crate) use crate::location::fixtures::locations;
+pub(crate) use crate::location::fixtures::{complexes, locations};
 pub(crate) use crate::service::fixtures::services;
 pub(crate) use crate::shape::fixtures::shapes;
 pub(crate) use crate::trip::fixtures::{
@@ -1,106 +1,399 @@
 #![allow(clippy::module_inception, clippy::zero_prefixed_literal)]
 
+use std::collections::{HashMap, HashSet};
 use std::error::Error;
 use std::ffi::OsStr;
 use std::fs::File;
 use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::rc::Rc;
 
-use chrono::NaiveDate;
+use chrono::{DateTime, NaiveDate, SecondsFormat, Utc};
+use itertools::Itertools;
 
 use zip::ZipArchive;
 
 mod agency;
 pub mod coord;
 mod deserialize;
+mod error;
+mod feed_info;
 pub mod line;
 mod location;
 pub mod profile;
+pub mod realtime;
 mod service;
 pub mod shape;
+mod transfer;
 pub mod trip;
 mod utils;
+pub mod validation;
 
 #[cfg(test)]
 mod fixtures;
 
 use crate::agency::Agency;
+use crate::feed_info::FeedInfo;
 use crate::line::Line;
-use crate::location::Linearizer;
+use crate::location::{Linearizer, Location, LocationId};
 use crate::profile::Profile;
-use crate::shape::SmoothMode;
+use crate::realtime::Realtime;
+use crate::shape::{FallbackMode, SmoothMode};
+use crate::transfer::Transfer;
 use crate::trip::Scheduler;
-use crate::utils::Dataset;
+use crate::utils::{Dataset, Namespaced};
+
+pub use crate::error::{ImportError, InvalidDateRangeError};
 
 pub struct ImportedDataset {
+    feed_infos: Vec<FeedInfo>,
+    agencies: Vec<Agency>,
+    transfers: Vec<Transfer>,
+    locations: HashMap<LocationId, Rc<Location>>,
+    merged_stations: HashMap<LocationId, LocationId>,
+    namespaces: Vec<String>,
+    shape_smoothing: SmoothMode,
+    imported_at: DateTime<Utc>,
+}
+
+/// The imported content of a single feed.
+struct Feed {
+    info: Option<FeedInfo>,
     agencies: Vec<Agency>,
+    transfers: Vec<Transfer>,
+    locations: HashMap<LocationId, Rc<Location>>,
 }
 
 impl ImportedDataset {
     fn fetch(
         mut dataset: impl Dataset,
         shape_smoothing: SmoothMode,
-    ) -> Result<Self, Box<dyn Error>> {
+        shape_fallback: FallbackMode,
+    ) -> Result<Feed, ImportError> {
+        let info = feed_info::Importer::import(&mut dataset)?;
         let services = service::Importer::import(&mut dataset)?;
         let locations = location::Importer::import(&mut dataset)?;
+        let transfers = transfer::Importer::import(&mut dataset, &locations)?;
         let shapes = shape::Importer::import(&mut dataset, shape_smoothing)?;
         let line_importer = line::Importer::import(&mut dataset)?;
         let trip_importer = trip::Importer::new(
             &services,
             &locations,
             &shapes,
+            shape_fallback,
             line_importer.id_mapping(),
             line_importer.line_count(),
         );
         let routes = trip_importer.import(&mut dataset)?;
-        let lines = line_importer.finish(routes)?;
+        let lines = line_importer.finish(routes);
         let agencies = agency::Importer::import(&mut dataset, lines)?;
-        Ok(Self { agencies })
+        Ok(Feed {
+            info,
+            agencies,
+            transfers,
+            locations,
+        })
     }
 
-    pub fn import(
-        path: impl AsRef<OsStr>,
+    fn fetch_namespaced(
+        dataset: impl Dataset,
+        namespace: Option<String>,
         shape_smoothing: SmoothMode,
-    ) -> Result<Self, Box<dyn Error>> {
-        let path = Path::new(&path);
+        shape_fallback: FallbackMode,
+    ) -> Result<Feed, ImportError> {
+        match namespace {
+            Some(namespace) => Self::fetch(
+                Namespaced::new(dataset, namespace),
+                shape_smoothing,
+                shape_fallback,
+            ),
+            None => Self::fetch(dataset, shape_smoothing, shape_fallback),
+        }
+    }
+
+    pub(crate) fn open_archive(path: &Path) -> Result<ZipArchive<File>, Box<dyn Error>> {
+        Ok(ZipArchive::new(File::open(path)?)?)
+    }
+
+    fn open(
+        path: &Path,
+        namespace: Option<String>,
+        shape_smoothing: SmoothMode,
+        shape_fallback: FallbackMode,
+    ) -> Result<Feed, ImportError> {
         if path.is_dir() {
             let mut path = PathBuf::from(&path);
             path.push(".txt");
-            Self::fetch(path, shape_smoothing)
+            Self::fetch_namespaced(path, namespace, shape_smoothing, shape_fallback)
         } else {
-            let archive = ZipArchive::new(File::open(&path)?)?;
-            Self::fetch(archive, shape_smoothing)
+            let archive = Self::open_archive(path).map_err(|source| ImportError::Archive {
+                path: path.to_path_buf(),
+                source,
+            })?;
+            Self::fetch_namespaced(archive, namespace, shape_smoothing, shape_fallback)
         }
     }
 
+    /// Derives a distinct namespace for every feed from its file name.
+    fn derive_namespaces(paths: &[&Path]) -> Vec<String> {
+        let mut used = HashSet::new();
+        paths
+            .iter()
+            .map(|path| {
+                let stem = path
+                    .file_stem()
+                    .map_or("feed".into(), |stem| stem.to_string_lossy());
+                let mut namespace = stem.to_string();
+                let mut index = 2;
+                while !used.insert(namespace.clone()) {
+                    namespace = format!("{}{}", stem, index);
+                    index += 1;
+                }
+                namespace
+            })
+            .collect()
+    }
+
+    pub fn import(
+        path: impl AsRef<OsStr>,
+        shape_smoothing: SmoothMode,
+        shape_fallback: FallbackMode,
+    ) -> Result<Self, ImportError> {
+        Self::import_feeds(&[path], shape_smoothing, shape_fallback, None)
+    }
+
+    /// Imports several feeds into one dataset.
+    ///
+    /// If there is more than one feed, their ids are prefixed with the file name of their feed.
+    /// With a `merge_distance` in meters, stations of different feeds that are closer than it and
+    /// have similar names are stored as one station.
+    pub fn import_feeds(
+        paths: &[impl AsRef<OsStr>],
+        shape_smoothing: SmoothMode,
+        shape_fallback: FallbackMode,
+        merge_distance: Option<f64>,
+    ) -> Result<Self, ImportError> {
+        let paths = paths.iter().map(Path::new).collect::<Vec<_>>();
+        let namespaces = match paths.len() {
+            1 => Vec::new(),
+            _ => Self::derive_namespaces(&paths),
+        };
+        let feed_namespaces = match paths.len() {
+            1 => vec![None],
+            _ => namespaces.iter().cloned().map(Some).collect(),
+        };
+
+        let imported_at = Utc::now();
+        let mut feed_infos = Vec::new();
+        let mut agencies = Vec::new();
+        let mut transfers = Vec::new();
+        let mut locations = HashMap::new();
+        let mut stations = Vec::new();
+        for (path, namespace) in paths.into_iter().zip(feed_namespaces) {
+            let feed = Self::open(path, namespace, shape_smoothing, shape_fallback)?;
+            feed_infos.extend(feed.info);
+            agencies.extend(feed.agencies);
+            transfers.extend(feed.transfers);
+            stations.push(
+                feed.locations
+                    .values()
+                    .unique_by(|location| location.id())
+                    .cloned()
+                    .collect(),
+            );
+            locations.extend(feed.locations);
+        }
+
+        let merged_stations = merge_distance.map_or_else(HashMap::new, |distance| {
+            location::merge_stations(&stations, distance)
+        });
+        Ok(Self {
+            feed_infos,
+            agencies,
+            transfers,
+            locations,
+            merged_stations,
+            namespaces,
+            shape_smoothing,
+            imported_at,
+        })
+    }
+
     pub fn agencies(&self) -> impl Iterator<Item = &Agency> {
         self.agencies.iter()
     }
 
-    fn store(&self, profile: Profile, date: NaiveDate) -> storage::Dataset {
-        let mut linearizer = Linearizer::new();
+    /// The namespaces prefixed to the ids of the feeds, which is empty for a single feed.
+    ///
+    /// Realtime updates referring to one of the feeds have to be read with its namespace.
+    pub fn namespaces(&self) -> &[String] {
+        &self.namespaces
+    }
+
+    /// Stores the trains of the service days from the first to the last date.
+    ///
+    /// Fails if the range is empty or longer than `storage::MAX_DAYS`.
+    fn store(
+        &self,
+        profile: Profile,
+        first_date: NaiveDate,
+        last_date: NaiveDate,
+        realtime: Option<&Realtime>,
+    ) -> Result<storage::Dataset, InvalidDateRangeError> {
+        let mut dates = Vec::new();
+        let mut date = first_date;
+        while date <= last_date {
+            dates.push(date);
+            date = date.succ();
+        }
+        if dates.is_empty() || dates.len() > storage::MAX_DAYS {
+            return Err(InvalidDateRangeError {
+                first: first_date,
+                last: last_date,
+            });
+        }
+
+        // the stop ids of the trips are the ones of the stations
+        let realtime = realtime
+            .map(|realtime| realtime.resolve_stops(|stop| Some(self.locations.get(stop)?.id())));
+        let realtime = realtime.as_ref();
+
+        let mut linearizer = Linearizer::with_merged_stations(self.merged_stations.clone());
         let mut scheduler = Scheduler::new();
-        let lines = profile
-            .filter(self.agencies())
-            .into_iter()
-            .map(|line| line.store(date, &mut linearizer, &mut scheduler))
+        let selected_lines = profile.filter(self.agencies());
+        let lines = selected_lines
+            .iter()
+            .map(|(_, line)| line.store(&dates, realtime, &mut linearizer, &mut scheduler))
             .collect();
 
+        let alerts = realtime.map_or_else(Vec::new, |realtime| {
+            let find_station = |stop: &LocationId| {
+                let location = self.locations.get(stop)?;
+                linearizer.find(location)
+            };
+            realtime
+                .alerts()
+                .filter_map(|alert| alert.store(&dates, &selected_lines, find_station))
+                .collect()
+        });
+
+        let transfers = Transfer::store_all(&self.transfers, &linearizer);
+
         let stations = linearizer
             .into_iter()
             .map(|location| location.store())
             .collect();
 
-        storage::Dataset::new(stations, scheduler.schedules(), lines)
+        let metadata = storage::Metadata::new(
+            self.feed_infos.iter().map(FeedInfo::store).collect(),
+            first_date.format("%F").to_string(),
+            last_date.format("%F").to_string(),
+            profile.to_string(),
+            self.shape_smoothing.to_string(),
+            self.imported_at.to_rfc3339_opts(SecondsFormat::Secs, false),
+        );
+        Ok(storage::Dataset::new(
+            metadata,
+            dates.len(),
+            stations,
+            scheduler.schedules(),
+            lines,
+            transfers,
+            alerts,
+        ))
     }
 
+    /// Stores the trains running from the first to the last date, both included.
+    ///
+    /// Each train is stored once with the days it runs on, so the range may span at most
+    /// `storage::MAX_DAYS` days.
     pub fn store_into(
         &self,
         writer: impl Write,
         profile: Profile,
-        date: NaiveDate,
-    ) -> bincode::Result<()> {
-        let dataset = self.store(profile, date);
-        bincode::serialize_into(writer, &dataset)
+        first_date: NaiveDate,
+        last_date: NaiveDate,
+    ) -> Result<(), Box<dyn Error>> {
+        let dataset = self.store(profile, first_date, last_date, None)?;
+        bincode::serialize_into(writer, &dataset)?;
+        Ok(())
+    }
+
+    /// Stores the trains as they actually ran at the dates, according to the realtime updates.
+    ///
+    /// Service alerts active at the dates are stored for the lines and stations they affect.
+    pub fn store_realtime_into(
+        &self,
+        writer: impl Write,
+        profile: Profile,
+        first_date: NaiveDate,
+        last_date: NaiveDate,
+        realtime: &Realtime,
+    ) -> Result<(), Box<dyn Error>> {
+        let dataset = self.store(profile, first_date, last_date, Some(realtime))?;
+        bincode::serialize_into(writer, &dataset)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::realtime::feed::{self, FeedEntity, FeedHeader, FeedMessage, TripDescriptor};
+    use chrono::FixedOffset;
+    use prost::Message;
+    use test_utils::time;
+
+    fn delay(trip_id: &str, delay: i32) -> Vec<u8> {
+        let message = FeedMessage {
+            header: FeedHeader {
+                gtfs_realtime_version: "2.0".to_string(),
+                timestamp: None,
+            },
+            entity: vec![FeedEntity {
+                id: "1".to_string(),
+                is_deleted: None,
+                trip_update: Some(feed::TripUpdate {
+                    trip: TripDescriptor {
+                        trip_id: Some(trip_id.to_string()),
+                        ..TripDescriptor::default()
+                    },
+                    stop_time_update: Vec::new(),
+                    delay: Some(delay),
+                }),
+                vehicle: None,
+                alert: None,
+            }],
+        };
+        let mut bytes = Vec::new();
+        message.encode(&mut bytes).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_realtime_of_namespaced_feed() {
+        let dataset = ImportedDataset::import_feeds(
+            &["tests/data/feeds/north", "tests/data/feeds/south"],
+            SmoothMode::Off,
+            FallbackMode::Straight,
+            None,
+        )
+        .unwrap();
+        assert_eq!(dataset.namespaces(), ["north", "south"]);
+
+        let realtime =
+            Realtime::decode(&delay("t1", 120), FixedOffset::east(0), Some("south")).unwrap();
+        let date = NaiveDate::from_ymd(2019, 1, 7);
+        let stored = dataset
+            .store(Profile::BerlinBrandenburg, date, date, Some(&realtime))
+            .unwrap()
+            .load();
+        // the trip of the other feed keeps its time
+        let times = (0..stored.station_count())
+            .flat_map(|station| stored.departures(station, 0, 10))
+            .map(|departure| departure.expected_time())
+            .sorted()
+            .collect::<Vec<_>>();
+        assert_eq!(times, [time!(9:00:00), time!(9:02:00)]);
     }
 }
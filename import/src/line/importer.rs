@@ -1,10 +1,10 @@
 use std::collections::HashMap;
-use std::error::Error;
 
 use super::{IncompleteLine, Line, LineColorRecord, LineId, LineRecord};
 use crate::agency::AgencyId;
 use crate::trip::Route;
 use crate::utils::{Action, Dataset};
+use crate::ImportError;
 
 pub(crate) struct Importer {
     id_mapping: HashMap<LineId, usize>,
@@ -12,19 +12,19 @@ pub(crate) struct Importer {
 }
 
 impl Importer {
-    pub(crate) fn import(dataset: &mut impl Dataset) -> Result<Importer, Box<dyn Error>> {
+    pub(crate) fn import(dataset: &mut impl Dataset) -> Result<Importer, ImportError> {
         let mut importer = Self::import_lines(dataset)?;
         importer.import_colors(dataset)?;
         Ok(importer)
     }
 
-    fn import_lines(dataset: &mut impl Dataset) -> Result<Self, Box<dyn Error>> {
+    fn import_lines(dataset: &mut impl Dataset) -> Result<Self, ImportError> {
         let mut id_mapping = HashMap::new();
         let mut incomplete_lines = Vec::new();
 
         let action = Action::start("Importing lines");
         for result in action.read_csv(dataset, "routes.txt")? {
-            let record: LineRecord = result?;
+            let (_, record): (_, LineRecord) = result?;
             record.deduplicate(&mut id_mapping, &mut incomplete_lines);
         }
         action.complete(&format!("Imported {} lines", incomplete_lines.len()));
@@ -34,12 +34,16 @@ impl Importer {
         })
     }
 
-    fn import_colors(&mut self, dataset: &mut impl Dataset) -> Result<(), Box<dyn Error>> {
+    fn import_colors(&mut self, dataset: &mut impl Dataset) -> Result<(), ImportError> {
+        if !dataset.contains("colors.txt") {
+            return Ok(());
+        }
+
         let mut colors = HashMap::new();
 
         let action = Action::start("Importing colors");
         for result in action.read_csv(dataset, "colors.txt")? {
-            let record: LineColorRecord = result?;
+            let (_, record): (_, LineColorRecord) = result?;
             record.import(&mut colors);
         }
 
@@ -59,19 +63,21 @@ impl Importer {
         self.incomplete_lines.len()
     }
 
-    pub(crate) fn finish(
-        self,
-        mut routes: Vec<Vec<Route>>,
-    ) -> Result<HashMap<AgencyId, Vec<Line>>, Box<dyn Error>> {
+    /// Completes the lines with their routes, which are expected in the order of the lines.
+    pub(crate) fn finish(self, routes: Vec<Vec<Route>>) -> HashMap<AgencyId, Vec<Line>> {
+        let mut ids = vec![Vec::new(); self.incomplete_lines.len()];
+        for (id, &position) in &self.id_mapping {
+            ids[position].push(id.clone());
+        }
+
         let mut lines = HashMap::new();
         let mut action = Action::start("Adding routes to lines");
-        let incomplete_lines = self.incomplete_lines.into_iter().rev();
-        for incomplete_line in action.wrap_iter(incomplete_lines) {
-            incomplete_line.finish(routes.pop().unwrap(), &mut lines);
+        let incomplete_lines = self.incomplete_lines.into_iter().zip(ids).zip(routes);
+        for ((incomplete_line, ids), routes) in action.wrap_iter(incomplete_lines) {
+            incomplete_line.finish(ids, routes, &mut lines);
         }
         action.complete("Added routes to lines");
-
-        Ok(lines)
+        lines
     }
 }
 
@@ -80,6 +86,7 @@ mod tests {
     use super::*;
     use crate::dataset;
     use crate::fixtures::{lines, routes};
+    use simulation::line::Kind;
     use test_utils::{assert_eq_alternate, map};
 
     #[test]
@@ -143,18 +150,44 @@ mod tests {
         );
 
         let importer = Importer::import(&mut dataset).unwrap();
-        let lines = importer
-            .finish(vec![
-                vec![],
-                vec![],
-                vec![],
-                vec![routes::tram_12::oranienburger_tor_am_kupfergraben()],
-            ])
-            .unwrap();
+        let lines = importer.finish(vec![
+            vec![],
+            vec![],
+            vec![],
+            vec![routes::tram_12::oranienburger_tor_am_kupfergraben()],
+        ]);
         assert_eq!(lines.len(), 2);
         assert!(lines[&"1".into()].contains(&lines::s1()));
         assert!(lines[&"1".into()].contains(&lines::s42()));
         assert!(lines[&"2".into()].contains(&lines::u4()));
         assert!(lines[&"2".into()].contains(&lines::tram_12_with_route()));
     }
+
+    #[test]
+    fn test_finish_with_route_colors() {
+        let mut dataset = dataset!(
+            routes:
+                route_id, agency_id, route_short_name, route_type, route_color, route_text_color;
+                1,        1,         "S1",             109,        "dc6ba6",    "";
+                2,        1,         "S42",            109,        "",          "";
+                3,        2,         "U4",             400,        "ffd900",    "000000";
+                4,        2,         "12",             900,        "",          ""
+        );
+
+        let importer = Importer::import(&mut dataset).unwrap();
+        let lines = importer.finish(vec![
+            vec![],
+            vec![],
+            vec![],
+            vec![routes::tram_12::oranienburger_tor_am_kupfergraben()],
+        ]);
+        assert!(lines[&"1".into()].contains(&lines::s1()));
+        let s42 = lines[&"1".into()]
+            .iter()
+            .find(|line| line.name() == "S42")
+            .unwrap();
+        assert_eq!(s42.color(), &Kind::SuburbanRailway.color());
+        assert!(lines[&"2".into()].contains(&lines::u4()));
+        assert!(lines[&"2".into()].contains(&lines::tram_12_with_route()));
+    }
 }
@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-use super::Line;
+use super::{Line, LineId};
 use crate::agency::AgencyId;
 use crate::trip::Route;
 use simulation::line::Kind;
@@ -11,32 +11,51 @@ pub(super) struct IncompleteLine {
     agency_id: AgencyId,
     name: String,
     color: Option<Color>,
+    text_color: Option<Color>,
     kind: Kind,
 }
 
 impl IncompleteLine {
-    pub(super) fn new(agency_id: AgencyId, name: String, kind: Kind) -> Self {
+    pub(super) fn new(
+        agency_id: AgencyId,
+        name: String,
+        kind: Kind,
+        color: Option<Color>,
+        text_color: Option<Color>,
+    ) -> Self {
         Self {
             agency_id,
             name,
-            color: None,
+            color,
+            text_color,
             kind,
         }
     }
 
+    /// Overrides the color of rail lines with the one listed in `colors.txt`, if any.
     pub(super) fn add_color_when_applicable(&mut self, colors: &HashMap<String, Color>) {
         match self.kind {
-            Kind::Railway | Kind::SuburbanRailway | Kind::UrbanRailway => {
-                self.color = colors.get(&self.name).cloned();
+            Kind::Railway | Kind::RegionalRailway | Kind::SuburbanRailway | Kind::UrbanRailway => {
+                if let Some(color) = colors.get(&self.name) {
+                    self.color = Some(color.clone());
+                }
             }
             _ => {}
         }
     }
 
-    pub(super) fn finish(self, routes: Vec<Route>, lines: &mut HashMap<AgencyId, Vec<Line>>) {
+    /// Completes the line, falling back to the color of its kind and, as GTFS specifies, to black
+    /// text if the feed gives none.
+    pub(super) fn finish(
+        self,
+        ids: Vec<LineId>,
+        routes: Vec<Route>,
+        lines: &mut HashMap<AgencyId, Vec<Line>>,
+    ) {
         #[allow(clippy::or_fun_call)]
         let color = self.color.unwrap_or(self.kind.color());
-        let line = Line::new(self.name, color, self.kind, routes);
+        let text_color = self.text_color.unwrap_or_else(|| Color::new(0, 0, 0));
+        let line = Line::new(ids, self.name, color, text_color, self.kind, routes);
         lines
             .entry(self.agency_id)
             .or_insert_with(Vec::new)
@@ -52,7 +71,13 @@ pub(crate) mod fixtures {
         ($($line:ident: $agency:ident, $name:expr, $kind:ident);* $(;)?) => (
             $(
                 pub(in crate::line) fn $line() -> IncompleteLine {
-                    IncompleteLine::new(stringify!($agency).into(), $name.to_string(), Kind::$kind)
+                    IncompleteLine::new(
+                        stringify!($agency).into(),
+                        $name.to_string(),
+                        Kind::$kind,
+                        None,
+                        None,
+                    )
                 }
             )*
         )
@@ -89,4 +114,34 @@ mod tests {
         line.add_color_when_applicable(&colors());
         assert_eq!(line.color, None);
     }
+
+    #[test]
+    fn test_keep_route_color_without_override() {
+        let mut line = incomplete_lines::u4();
+        line.color = Some(Color::new(0, 102, 173));
+        line.add_color_when_applicable(&HashMap::new());
+        assert_eq!(line.color, Some(Color::new(0, 102, 173)));
+    }
+
+    #[test]
+    fn test_finish_with_route_colors() {
+        let mut line = incomplete_lines::u4_replacement();
+        line.color = Some(Color::new(255, 217, 0));
+        line.text_color = Some(Color::new(0, 0, 0));
+        let mut lines = HashMap::new();
+        line.finish(vec!["u4".into()], Vec::new(), &mut lines);
+        let line = &lines[&"pubtransport".into()][0];
+        assert_eq!(line.color(), &Color::new(255, 217, 0));
+        assert_eq!(line.text_color(), &Color::new(0, 0, 0));
+    }
+
+    #[test]
+    fn test_finish_defaults_to_black_text() {
+        let line = incomplete_lines::u4_replacement();
+        let mut lines = HashMap::new();
+        line.finish(vec!["u4".into()], Vec::new(), &mut lines);
+        let line = &lines[&"pubtransport".into()][0];
+        assert_eq!(line.color(), &Kind::Bus.color());
+        assert_eq!(line.text_color(), &Color::new(0, 0, 0));
+    }
 }
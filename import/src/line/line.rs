@@ -1,8 +1,9 @@
 use chrono::NaiveDate;
 
 use crate::create_id_type;
-use crate::location::Linearizer;
-use crate::trip::{Route, Scheduler};
+use crate::location::{Linearizer, LocationId};
+use crate::realtime::Realtime;
+use crate::trip::{Implausibility, Route, Scheduler};
 use simulation::line::Kind;
 use simulation::Color;
 
@@ -10,26 +11,49 @@ create_id_type!(LineId);
 
 #[derive(Debug, PartialEq)]
 pub struct Line {
+    ids: Vec<LineId>,
     name: String,
     color: Color,
+    text_color: Color,
     kind: Kind,
     routes: Vec<Route>,
 }
 
 impl Line {
-    pub(crate) fn new(name: String, color: Color, kind: Kind, routes: Vec<Route>) -> Line {
+    pub(crate) fn new(
+        ids: Vec<LineId>,
+        name: String,
+        color: Color,
+        text_color: Color,
+        kind: Kind,
+        routes: Vec<Route>,
+    ) -> Line {
         Line {
+            ids,
             name,
             color,
+            text_color,
             kind,
             routes,
         }
     }
 
+    pub(crate) fn ids(&self) -> &[LineId] {
+        &self.ids
+    }
+
     pub fn name(&self) -> &str {
         &self.name
     }
 
+    pub fn color(&self) -> &Color {
+        &self.color
+    }
+
+    pub fn text_color(&self) -> &Color {
+        &self.text_color
+    }
+
     pub(crate) fn kind(&self) -> Kind {
         self.kind
     }
@@ -38,21 +62,43 @@ impl Line {
         self.routes.iter()
     }
 
+    /// Whether one of the routes stops at a location the predicate accepts.
+    pub(crate) fn stops_at(&self, accept: impl Fn(&LocationId) -> bool) -> bool {
+        self.routes.iter().any(|route| route.stops_at(&accept))
+    }
+
+    pub fn implausibilities(&self) -> Vec<Implausibility> {
+        self.routes
+            .iter()
+            .flat_map(|route| route.implausibilities(self.kind))
+            .collect()
+    }
+
+    /// Stores the route with the most trips at the dates.
+    ///
+    /// With realtime data, the trains are stored as they actually ran.
     pub(crate) fn store(
         &self,
-        date: NaiveDate,
+        dates: &[NaiveDate],
+        realtime: Option<&Realtime>,
         linearizer: &mut Linearizer,
         scheduler: &mut Scheduler,
     ) -> storage::Line {
         let route = self
             .routes()
-            .max_by_key(|route| route.num_trips_at(date))
+            .max_by_key(|route| {
+                dates
+                    .iter()
+                    .map(|&date| route.num_trips_at(date))
+                    .sum::<usize>()
+            })
             .unwrap();
         let nodes = route.store_nodes(linearizer);
-        let trains = route.store_trains(date, scheduler);
+        let trains = route.store_trains(&self.ids, dates, realtime, linearizer, scheduler);
         storage::Line::new(
             self.name.clone(),
             self.color.clone(),
+            self.text_color.clone(),
             self.kind,
             nodes,
             trains,
@@ -66,12 +112,14 @@ pub(crate) mod fixtures {
     use crate::fixtures::routes;
 
     macro_rules! lines {
-        ($($line:ident: $name:expr, $kind:ident, $color:expr);* $(;)?) => (
+        ($($line:ident: $id:expr, $name:expr, $kind:ident, $color:expr);* $(;)?) => (
             $(
                 pub(crate) fn $line() -> Line {
                     Line {
+                        ids: vec![$id.into()],
                         name: $name.to_string(),
                         color: Color::new($color.0, $color.1, $color.2),
+                        text_color: Color::new(0, 0, 0),
                         kind: Kind::$kind,
                         routes: Vec::new(),
                     }
@@ -81,10 +129,10 @@ pub(crate) mod fixtures {
     }
 
     lines! {
-        s1:                 "S1",           SuburbanRailway,    (220, 107, 166);
-        s42:                "S42",          SuburbanRailway,    (204,  97,  18);
-        u4:                 "U4",           UrbanRailway,       (255, 217,   0);
-        tram_12:            "12",           Tram,               (136, 112, 171);
+        s1:                 "1",    "S1",           SuburbanRailway,    (220, 107, 166);
+        s42:                "2",    "S42",          SuburbanRailway,    (204,  97,  18);
+        u4:                 "3",    "U4",           UrbanRailway,       (255, 217,   0);
+        tram_12:            "4",    "12",           Tram,               (136, 112, 171);
     }
 
     pub(crate) fn tram_12_with_route() -> Line {
@@ -122,7 +170,7 @@ mod tests {
             "am_kupfergraben_oranienburger_tor" => 1,
         };
         assert_eq!(
-            line.store(date, &mut linearizer, &mut scheduler),
+            line.store(&[date], None, &mut linearizer, &mut scheduler),
             storage::fixtures::lines::tram_12(&linearizer.location_ids(), &schedule_ids)
         );
     }
@@ -18,6 +18,10 @@ pub(super) struct LineRecord {
     route_short_name: String,
     #[serde(rename = "route_type", deserialize_with = "deserialize::line_kind")]
     line_kind: Kind,
+    #[serde(default, deserialize_with = "deserialize::gtfs_color")]
+    route_color: Option<Color>,
+    #[serde(default, deserialize_with = "deserialize::gtfs_color")]
+    route_text_color: Option<Color>,
 }
 
 impl LineRecord {
@@ -26,8 +30,13 @@ impl LineRecord {
         id_mapping: &mut HashMap<LineId, usize>,
         incomplete_lines: &mut Vec<IncompleteLine>,
     ) {
-        let incomplete_line =
-            IncompleteLine::new(self.agency_id, self.route_short_name, self.line_kind);
+        let incomplete_line = IncompleteLine::new(
+            self.agency_id,
+            self.route_short_name,
+            self.line_kind,
+            self.route_color,
+            self.route_text_color,
+        );
         let position = match incomplete_lines
             .iter()
             .find_position(|other| &&incomplete_line == other)
@@ -67,6 +76,8 @@ mod tests {
             agency_id: "pubtransport".into(),
             route_short_name: "U4".to_string(),
             line_kind: Kind::UrbanRailway,
+            route_color: None,
+            route_text_color: None,
         }
     }
 
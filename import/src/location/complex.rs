@@ -0,0 +1,217 @@
+use super::{LocationId, LocationKind};
+use crate::coord::{transform, Point};
+use simulation::complex::PathwayMode;
+
+/// Assumed walking speed in m/s for pathways without an explicit traversal time.
+const WALKING_SPEED: f64 = 1.2;
+
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct Complex {
+    places: Vec<Place>,
+    pathways: Vec<Pathway>,
+}
+
+impl Complex {
+    pub(super) fn add_place(&mut self, place: Place) {
+        self.places.push(place);
+    }
+
+    fn find(&self, id: &LocationId) -> Option<usize> {
+        self.places.iter().position(|place| &place.id == id)
+    }
+
+    /// Index of the place with the id, if it is a platform.
+    pub(super) fn platform(&self, id: &LocationId) -> Option<usize> {
+        self.find(id)
+            .filter(|&index| self.places[index].kind == LocationKind::Stop)
+    }
+
+    /// Connects two places of this complex.
+    ///
+    /// Without a traversal time the pathway is walked at a constant speed, either along its
+    /// length or, lacking that too, along the direct line between its places.
+    pub(super) fn add_pathway(
+        &mut self,
+        from: &LocationId,
+        to: &LocationId,
+        mode: PathwayMode,
+        bidirectional: bool,
+        traversal_time: Option<u32>,
+        length: Option<f64>,
+    ) -> Option<()> {
+        let from = self.find(from)?;
+        let to = self.find(to)?;
+        let traversal_time = traversal_time.unwrap_or_else(|| {
+            let length = length.unwrap_or_else(|| {
+                na::distance(&self.places[from].position, &self.places[to].position)
+            });
+            (length / WALKING_SPEED).ceil() as u32
+        });
+        self.pathways.push(Pathway {
+            from,
+            to,
+            mode,
+            bidirectional,
+            traversal_time,
+        });
+        Some(())
+    }
+
+    pub(super) fn store(&self) -> storage::Complex {
+        let places = self.places.iter().map(Place::store).collect();
+        let pathways = self.pathways.iter().map(Pathway::store).collect();
+        storage::Complex::new(places, pathways)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub(super) struct Place {
+    id: LocationId,
+    kind: LocationKind,
+    name: String,
+    position: Point,
+}
+
+impl Place {
+    pub(super) fn new(id: LocationId, kind: LocationKind, name: String, position: Point) -> Self {
+        Self {
+            id,
+            kind,
+            name,
+            position,
+        }
+    }
+
+    fn store(&self) -> storage::Place {
+        storage::Place::new(
+            self.kind.place_kind().unwrap(),
+            self.name.clone(),
+            transform(self.position),
+        )
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Pathway {
+    from: usize,
+    to: usize,
+    mode: PathwayMode,
+    bidirectional: bool,
+    traversal_time: u32,
+}
+
+impl Pathway {
+    fn store(&self) -> storage::Pathway {
+        storage::Pathway::new(
+            self.from,
+            self.to,
+            self.mode,
+            self.bidirectional,
+            self.traversal_time,
+        )
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod fixtures {
+    use super::*;
+    use crate::coord::project;
+
+    pub(crate) fn hauptbahnhof() -> Complex {
+        let mut complex = Complex::default();
+        complex.add_place(Place::new(
+            "hauptbahnhof_nord".into(),
+            LocationKind::Entrance,
+            "Hauptbahnhof Nord".to_string(),
+            project(52.5267, 13.3692),
+        ));
+        complex.add_place(Place::new(
+            "hauptbahnhof_1".into(),
+            LocationKind::Stop,
+            "Hauptbahnhof Gleis 1".to_string(),
+            project(52.5252, 13.3694),
+        ));
+        complex
+            .add_pathway(
+                &"hauptbahnhof_nord".into(),
+                &"hauptbahnhof_1".into(),
+                PathwayMode::Escalator,
+                true,
+                Some(120),
+                None,
+            )
+            .unwrap();
+        complex
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::complexes;
+    use simulation::complex::PlaceKind;
+
+    #[test]
+    fn test_add_pathway_with_traversal_time() {
+        let complex = complexes::hauptbahnhof();
+        assert_eq!(complex.pathways[0].traversal_time, 120);
+    }
+
+    #[test]
+    fn test_add_pathway_with_length() {
+        let mut complex = complexes::hauptbahnhof();
+        complex
+            .add_pathway(
+                &"hauptbahnhof_1".into(),
+                &"hauptbahnhof_nord".into(),
+                PathwayMode::Stairs,
+                false,
+                None,
+                Some(60.0),
+            )
+            .unwrap();
+        assert_eq!(complex.pathways[1].traversal_time, 50);
+    }
+
+    #[test]
+    fn test_add_pathway_along_direct_line() {
+        let mut complex = complexes::hauptbahnhof();
+        complex
+            .add_pathway(
+                &"hauptbahnhof_1".into(),
+                &"hauptbahnhof_nord".into(),
+                PathwayMode::Walkway,
+                false,
+                None,
+                None,
+            )
+            .unwrap();
+        assert_eq!(complex.pathways[1].traversal_time, 140);
+    }
+
+    #[test]
+    fn test_add_pathway_to_unknown_place() {
+        let mut complex = complexes::hauptbahnhof();
+        assert_eq!(
+            complex.add_pathway(
+                &"hauptbahnhof_1".into(),
+                &"friedrichstr_1".into(),
+                PathwayMode::Walkway,
+                true,
+                None,
+                None,
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_store() {
+        let complex = complexes::hauptbahnhof().store().load();
+        assert_eq!(complex.places()[0].kind(), PlaceKind::Entrance);
+        assert_eq!(complex.places()[1].kind(), PlaceKind::Platform);
+        assert_eq!(complex.places()[1].name(), "Hauptbahnhof Gleis 1");
+        assert_eq!(complex.walking_time(0, 1), Some(120));
+        assert_eq!(complex.walking_time(1, 0), Some(120));
+    }
+}
@@ -1,41 +1,101 @@
 use std::collections::HashMap;
-use std::error::Error;
 use std::rc::Rc;
 
-use super::{Location, LocationId, LocationImportError, LocationRecord};
+use super::{Complex, Location, LocationId, LocationRecord, PathwayRecord, Place};
 use crate::utils::{Action, Dataset};
+use crate::ImportError;
 
 pub(crate) struct Importer;
 
 impl Importer {
     pub(crate) fn import(
         dataset: &mut impl Dataset,
-    ) -> Result<HashMap<LocationId, Rc<Location>>, Box<dyn Error>> {
+    ) -> Result<HashMap<LocationId, Rc<Location>>, ImportError> {
         let mut queues = (Vec::new(), Vec::new());
         let mut locations = HashMap::new();
+        let mut places = Vec::new();
 
         let action = Action::start("Importing locations");
         for result in action.read_csv(dataset, "stops.txt")? {
-            let record: LocationRecord = result?;
-            record.import_or_enqueue(&mut locations, &mut queues)?;
+            let (line, record): (_, LocationRecord) = result?;
+            places.extend(record.place());
+            record.import_or_enqueue(line, &mut locations, &mut queues)?;
         }
 
-        for record in queues.0.into_iter().chain(queues.1) {
+        for (line, record) in queues.0.into_iter().chain(queues.1) {
             if let Err(record) = record.try_import(&mut locations) {
-                return Err(LocationImportError::ParentNotFound(record).into());
+                return Err(record.parent_not_found(line));
             }
         }
 
         action.complete(&format!("Imported {} locations", locations.len()));
+
+        let mut complexes = Self::group_into_complexes(&locations, places);
+        Self::import_pathways(dataset, &locations, &mut complexes)?;
+        Self::attach_complexes(&mut locations, complexes);
         Ok(locations)
     }
+
+    fn group_into_complexes(
+        locations: &HashMap<LocationId, Rc<Location>>,
+        places: Vec<(LocationId, Place)>,
+    ) -> HashMap<LocationId, Complex> {
+        let mut complexes = HashMap::new();
+        for (parent_id, place) in places {
+            complexes
+                .entry(locations[&parent_id].id())
+                .or_insert_with(Complex::default)
+                .add_place(place);
+        }
+        complexes
+    }
+
+    fn import_pathways(
+        dataset: &mut impl Dataset,
+        locations: &HashMap<LocationId, Rc<Location>>,
+        complexes: &mut HashMap<LocationId, Complex>,
+    ) -> Result<(), ImportError> {
+        if !dataset.contains("pathways.txt") {
+            return Ok(());
+        }
+
+        let action = Action::start("Importing pathways");
+        let mut count = 0;
+        for result in action.read_csv(dataset, "pathways.txt")? {
+            let (line, record): (_, PathwayRecord) = result?;
+            record.import(line, locations, complexes)?;
+            count += 1;
+        }
+        action.complete(&format!("Imported {} pathways", count));
+        Ok(())
+    }
+
+    fn attach_complexes(
+        locations: &mut HashMap<LocationId, Rc<Location>>,
+        complexes: HashMap<LocationId, Complex>,
+    ) {
+        let stations = complexes
+            .into_iter()
+            .map(|(id, complex)| {
+                let station = Rc::new(locations[&id].with_complex(complex));
+                (id, station)
+            })
+            .collect::<HashMap<_, _>>();
+
+        for location in locations.values_mut() {
+            if let Some(station) = stations.get(&location.id()) {
+                *location = Rc::clone(station);
+            }
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::dataset;
-    use crate::fixtures::locations;
+    use crate::fixtures::{complexes, locations};
+    use crate::utils::Namespaced;
     use test_utils::map;
 
     #[test]
@@ -48,7 +108,7 @@ mod tests {
 
         assert_eq!(
             Importer::import(&mut dataset).unwrap_err().to_string(),
-            "forbidden parent bahnhof for station hauptbahnhof"
+            "stops.txt, line 2: stop_id hauptbahnhof is a station and must not have a parent station"
         );
     }
 
@@ -62,7 +122,7 @@ mod tests {
 
         assert_eq!(
             Importer::import(&mut dataset).unwrap_err().to_string(),
-            "parent hauptbahnhof for location hauptbahnhof_1 not found"
+            "stops.txt, line 2: parent_station hauptbahnhof not found"
         );
     }
 
@@ -83,4 +143,46 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn test_station_complex() {
+        let mut dataset = dataset!(
+            stops:
+                stop_id,             stop_name,              stop_lat, stop_lon, location_type, parent_station;
+                "hauptbahnhof",      "Hauptbahnhof",         52.526,   13.369,   1,             "";
+                "hauptbahnhof_nord", "Hauptbahnhof Nord",    52.5267,  13.3692,  2,             "hauptbahnhof";
+                "hauptbahnhof_1",    "Hauptbahnhof Gleis 1", 52.5252,  13.3694,  0,             "hauptbahnhof"
+            pathways:
+                pathway_id, from_stop_id,        to_stop_id,       pathway_mode, is_bidirectional, length, traversal_time;
+                1,          "hauptbahnhof_nord", "hauptbahnhof_1", 4,            1,                "",     120
+        );
+
+        let hauptbahnhof =
+            Rc::new(locations::hauptbahnhof().with_complex(complexes::hauptbahnhof()));
+        assert_eq!(
+            Importer::import(&mut dataset).unwrap(),
+            map! {
+                "hauptbahnhof" => Rc::clone(&hauptbahnhof),
+                "hauptbahnhof_nord" => Rc::clone(&hauptbahnhof),
+                "hauptbahnhof_1" => hauptbahnhof,
+            }
+        );
+    }
+
+    #[test]
+    fn test_namespaced() {
+        let dataset = dataset!(
+            stops:
+                stop_id,          stop_name,              stop_lat, stop_lon, location_type, parent_station;
+                "hauptbahnhof",   "Hauptbahnhof",         52.526,   13.369,   1,             "";
+                "hauptbahnhof_1", "Hauptbahnhof Gleis 1", 52.5252,  13.3694,  0,             "hauptbahnhof"
+        );
+
+        let locations = Importer::import(&mut Namespaced::new(dataset, "vbb".into())).unwrap();
+        assert_eq!(locations.len(), 2);
+        assert_eq!(
+            locations[&"vbb:hauptbahnhof_1".into()].id(),
+            "vbb:hauptbahnhof".into()
+        );
+    }
 }
@@ -2,6 +2,8 @@ use std::fmt;
 
 use serde::de::{Deserialize, Deserializer, Error as DeserializeError, Visitor};
 
+use simulation::complex::PlaceKind;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub(super) enum LocationKind {
     Stop,
@@ -11,6 +13,18 @@ pub(super) enum LocationKind {
     BoardingArea,
 }
 
+impl LocationKind {
+    pub(super) fn place_kind(self) -> Option<PlaceKind> {
+        match self {
+            LocationKind::Stop => Some(PlaceKind::Platform),
+            LocationKind::Station => None,
+            LocationKind::Entrance => Some(PlaceKind::Entrance),
+            LocationKind::GenericNode => Some(PlaceKind::GenericNode),
+            LocationKind::BoardingArea => Some(PlaceKind::BoardingArea),
+        }
+    }
+}
+
 impl<'de> Deserialize<'de> for LocationKind {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -53,6 +67,16 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_place_kind() {
+        assert_eq!(LocationKind::Stop.place_kind(), Some(PlaceKind::Platform));
+        assert_eq!(LocationKind::Station.place_kind(), None);
+        assert_eq!(
+            LocationKind::BoardingArea.place_kind(),
+            Some(PlaceKind::BoardingArea)
+        );
+    }
+
     #[test]
     fn test_deserialize_location_kind() {
         assert_de_tokens(&LocationKind::Stop, &[Token::U16(0)]);
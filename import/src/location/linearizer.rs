@@ -7,6 +7,7 @@ use crate::location::{Location, LocationId};
 pub(crate) struct Linearizer {
     ids: HashMap<LocationId, usize>,
     locations: Vec<Rc<Location>>,
+    merged_stations: HashMap<LocationId, LocationId>,
 }
 
 impl Linearizer {
@@ -14,11 +15,27 @@ impl Linearizer {
         Self {
             ids: HashMap::new(),
             locations: Vec::new(),
+            merged_stations: HashMap::new(),
         }
     }
 
+    /// Creates a linearizer which stores merged stations only once.
+    ///
+    /// The first location of a merged group to be retrieved represents the whole group.
+    pub(crate) fn with_merged_stations(merged_stations: HashMap<LocationId, LocationId>) -> Self {
+        Self {
+            merged_stations,
+            ..Self::new()
+        }
+    }
+
+    fn key(&self, location: &Location) -> LocationId {
+        let id = location.id();
+        self.merged_stations.get(&id).cloned().unwrap_or(id)
+    }
+
     pub(crate) fn retrieve(&mut self, location: &Rc<Location>) -> usize {
-        match self.ids.entry(location.id()) {
+        match self.ids.entry(self.key(location)) {
             Entry::Occupied(entry) => *entry.get(),
             Entry::Vacant(entry) => {
                 self.locations.push(location.clone());
@@ -27,6 +44,18 @@ impl Linearizer {
         }
     }
 
+    pub(crate) fn find(&self, location: &Location) -> Option<usize> {
+        self.ids.get(&self.key(location)).copied()
+    }
+
+    /// Whether the location is stored as itself instead of being represented by another station
+    /// it is merged with.
+    pub(crate) fn represents(&self, location: &Location) -> bool {
+        self.find(location)
+            .iter()
+            .any(|&index| self.locations[index].id() == location.id())
+    }
+
     #[cfg(test)]
     pub(crate) fn location_ids(&self) -> HashMap<String, usize> {
         self.ids
@@ -51,6 +80,7 @@ mod tests {
 
     use super::*;
     use crate::fixtures::locations;
+    use test_utils::map;
 
     #[test]
     fn test_retrieve() {
@@ -60,6 +90,24 @@ mod tests {
         assert_eq!(linearizer.retrieve(&Rc::new(locations::hauptbahnhof())), 0);
     }
 
+    #[test]
+    fn test_find() {
+        let mut linearizer = Linearizer::new();
+        linearizer.retrieve(&Rc::new(locations::hauptbahnhof()));
+        assert_eq!(linearizer.find(&locations::hauptbahnhof()), Some(0));
+        assert_eq!(linearizer.find(&locations::friedrichstr()), None);
+    }
+
+    #[test]
+    fn test_merged_stations() {
+        let merged_stations = map! { "friedrichstr" => "hauptbahnhof".into() };
+        let mut linearizer = Linearizer::with_merged_stations(merged_stations);
+        assert_eq!(linearizer.retrieve(&Rc::new(locations::friedrichstr())), 0);
+        assert_eq!(linearizer.retrieve(&Rc::new(locations::hauptbahnhof())), 0);
+        assert_eq!(linearizer.find(&locations::hauptbahnhof()), Some(0));
+        assert_equal(linearizer, vec![Rc::new(locations::friedrichstr())]);
+    }
+
     #[test]
     fn test_into_vec() {
         let mut linearizer = Linearizer::new();
@@ -1,5 +1,6 @@
 use std::fmt;
 
+use super::Complex;
 use crate::coord::{transform, Point, PointDebug};
 use crate::create_id_type;
 
@@ -10,11 +11,26 @@ pub struct Location {
     id: LocationId,
     name: String,
     position: Point,
+    complex: Complex,
 }
 
 impl Location {
     pub(crate) fn new(id: LocationId, name: String, position: Point) -> Location {
-        Location { id, name, position }
+        Location {
+            id,
+            name,
+            position,
+            complex: Complex::default(),
+        }
+    }
+
+    pub(super) fn with_complex(&self, complex: Complex) -> Location {
+        Location {
+            id: self.id.clone(),
+            name: self.name.clone(),
+            position: self.position,
+            complex,
+        }
     }
 
     pub(crate) fn id(&self) -> LocationId {
@@ -29,9 +45,14 @@ impl Location {
         self.position
     }
 
+    /// Index of the platform with the id among the places of the complex of this station.
+    pub(crate) fn platform(&self, id: &LocationId) -> Option<usize> {
+        self.complex.platform(id)
+    }
+
     pub(crate) fn store(&self) -> storage::Station {
         let position = transform(self.position());
-        storage::Station::new(position, self.name.clone())
+        storage::Station::new(position, self.name.clone(), self.complex.store())
     }
 }
 
@@ -44,6 +65,7 @@ impl fmt::Debug for Location {
             .field("id", &self.id)
             .field("name", &self.name)
             .field("position", &position)
+            .field("complex", &self.complex)
             .finish()
     }
 }
@@ -132,12 +154,16 @@ pub(crate) mod fixtures {
         strasse_zum_loewen:                  52.427, 13.160, "Straße zum Löwen";
         conradstr:                           52.420, 13.162, "Conradstr.";
     }
+
+    pub(crate) fn hauptbahnhof_with_complex() -> Location {
+        hauptbahnhof().with_complex(crate::fixtures::complexes::hauptbahnhof())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::coord::project;
-    use crate::fixtures::locations;
+    use crate::fixtures::{complexes, locations};
 
     #[test]
     fn test_getters() {
@@ -154,4 +180,21 @@ mod tests {
             storage::fixtures::stations::hauptbahnhof()
         );
     }
+
+    #[test]
+    fn test_platform() {
+        let location = locations::hauptbahnhof_with_complex();
+        assert_eq!(location.platform(&"hauptbahnhof_1".into()), Some(1));
+        assert_eq!(location.platform(&"hauptbahnhof_nord".into()), None);
+        assert_eq!(location.platform(&"hauptbahnhof".into()), None);
+    }
+
+    #[test]
+    fn test_store_with_complex() {
+        let location = locations::hauptbahnhof().with_complex(complexes::hauptbahnhof());
+        let station = location
+            .store()
+            .load(simulation::station::Kind::Interchange);
+        assert_eq!(station.complex(), &complexes::hauptbahnhof().store().load());
+    }
 }
@@ -0,0 +1,164 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use ordered_float::NotNan;
+
+use super::{Location, LocationId};
+
+/// Lowercase words of a name which are long enough to tell stations apart.
+fn significant_words(name: &str) -> Vec<String> {
+    name.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| word.chars().count() >= 3)
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Whether every significant word of the shorter name also appears in the longer one.
+fn similar_names(a: &str, b: &str) -> bool {
+    let (a, b) = (significant_words(a), significant_words(b));
+    let (shorter, longer) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    !shorter.is_empty() && shorter.iter().all(|word| longer.contains(word))
+}
+
+fn representative(merged: &HashMap<LocationId, LocationId>, mut id: LocationId) -> LocationId {
+    while let Some(target) = merged.get(&id) {
+        id = target.clone();
+    }
+    id
+}
+
+/// Finds stations of different feeds which are close to each other and have similar names.
+///
+/// Returns the ids of merged stations, mapped to the id of the station representing them.
+pub(crate) fn merge_stations(
+    feeds: &[Vec<Rc<Location>>],
+    max_distance: f64,
+) -> HashMap<LocationId, LocationId> {
+    let mut stations = feeds
+        .iter()
+        .enumerate()
+        .flat_map(|(feed, stations)| stations.iter().map(move |station| (feed, station)))
+        .collect::<Vec<_>>();
+    stations.sort_by_key(|(_, station)| NotNan::new(station.position().x).unwrap());
+
+    let mut merged = HashMap::new();
+    for (i, &(feed, station)) in stations.iter().enumerate() {
+        for &(other_feed, other) in &stations[i + 1..] {
+            if other.position().x - station.position().x > max_distance {
+                break;
+            }
+            if feed == other_feed
+                || na::distance(&station.position(), &other.position()) > max_distance
+                || !similar_names(station.name(), other.name())
+            {
+                continue;
+            }
+
+            let target = representative(&merged, station.id());
+            let source = representative(&merged, other.id());
+            if source != target {
+                merged.insert(source, target);
+            }
+        }
+    }
+
+    merged
+        .keys()
+        .map(|id| (id.clone(), representative(&merged, id.clone())))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coord::project;
+    use crate::fixtures::locations;
+    use test_utils::map;
+
+    fn location(id: &str, name: &str, lat: f64, lon: f64) -> Rc<Location> {
+        Rc::new(Location::new(
+            id.into(),
+            name.to_string(),
+            project(lat, lon),
+        ))
+    }
+
+    #[test]
+    fn test_similar_names() {
+        assert!(similar_names(
+            "S+U Friedrichstr. Bhf (Berlin)",
+            "Friedrichstr."
+        ));
+        assert!(similar_names(
+            "Berlin Hauptbahnhof",
+            "S+U Berlin Hauptbahnhof"
+        ));
+        assert!(!similar_names("Berlin Hauptbahnhof", "Berlin Ostbahnhof"));
+        assert!(!similar_names("S", "S"));
+    }
+
+    #[test]
+    fn test_merge_close_stations() {
+        let feeds = vec![
+            vec![Rc::new(locations::friedrichstr())],
+            vec![location(
+                "db:friedrichstr",
+                "Berlin Friedrichstr.",
+                52.5201,
+                13.3871,
+            )],
+        ];
+        assert_eq!(
+            merge_stations(&feeds, 100.0),
+            map! { "db:friedrichstr" => "friedrichstr".into() }
+        );
+    }
+
+    #[test]
+    fn test_keep_distant_stations() {
+        let feeds = vec![
+            vec![Rc::new(locations::friedrichstr())],
+            vec![location(
+                "db:friedrichstr",
+                "Berlin Friedrichstr.",
+                52.530,
+                13.387,
+            )],
+        ];
+        assert_eq!(merge_stations(&feeds, 100.0), HashMap::new());
+    }
+
+    #[test]
+    fn test_keep_stations_of_same_feed() {
+        let feeds = vec![vec![
+            Rc::new(locations::friedrichstr()),
+            location("friedrichstr_2", "Friedrichstr.", 52.5201, 13.3871),
+        ]];
+        assert_eq!(merge_stations(&feeds, 100.0), HashMap::new());
+    }
+
+    #[test]
+    fn test_merge_transitively() {
+        let feeds = vec![
+            vec![Rc::new(locations::friedrichstr())],
+            vec![location(
+                "db:friedrichstr",
+                "Friedrichstr.",
+                52.5201,
+                13.3871,
+            )],
+            vec![location(
+                "bvg:friedrichstr",
+                "Friedrichstr.",
+                52.5202,
+                13.3872,
+            )],
+        ];
+        let merged = merge_stations(&feeds, 100.0);
+        assert_eq!(merged.len(), 2);
+        assert_eq!(
+            merged[&"db:friedrichstr".into()],
+            merged[&"bvg:friedrichstr".into()]
+        );
+    }
+}
@@ -1,19 +1,22 @@
-mod errors;
+mod complex;
 mod importer;
 mod kind;
 mod linearizer;
 mod location;
+mod merger;
 mod record;
 
 #[cfg(test)]
 pub(crate) mod fixtures {
+    pub(crate) use super::complex::fixtures as complexes;
     pub(crate) use super::location::fixtures as locations;
 }
 
-use errors::LocationImportError;
+use complex::{Complex, Place};
 use kind::LocationKind;
-use record::LocationRecord;
+use record::{LocationRecord, PathwayRecord};
 
 pub(crate) use importer::Importer;
 pub(crate) use linearizer::Linearizer;
 pub(crate) use location::{Location, LocationId};
+pub(crate) use merger::merge_stations;
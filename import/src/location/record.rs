@@ -3,8 +3,14 @@ use std::rc::Rc;
 
 use serde_derive::Deserialize;
 
-use super::{Location, LocationId, LocationImportError, LocationKind};
+use super::{Complex, Location, LocationId, LocationKind, Place};
 use crate::coord::project;
+use crate::deserialize;
+use crate::ImportError;
+use simulation::complex::PathwayMode;
+
+/// Locations waiting for their parent station, together with their line.
+pub(super) type Queue = Vec<(u64, LocationRecord)>;
 
 #[derive(Debug, PartialEq, Deserialize)]
 pub(super) struct LocationRecord {
@@ -18,12 +24,18 @@ pub(super) struct LocationRecord {
 }
 
 impl LocationRecord {
-    pub(super) fn stop_id(&self) -> &LocationId {
-        &self.stop_id
-    }
-
-    pub(super) fn parent_station(&self) -> Option<&LocationId> {
-        self.parent_station.as_ref()
+    /// Creates the place this location takes within the complex of its parent station.
+    pub(super) fn place(&self) -> Option<(LocationId, Place)> {
+        let parent = self.parent_station.clone()?;
+        self.location_kind.place_kind()?;
+        let position = project(self.stop_lat, self.stop_lon);
+        let place = Place::new(
+            self.stop_id.clone(),
+            self.location_kind,
+            self.stop_name.clone(),
+            position,
+        );
+        Some((parent, place))
     }
 
     pub(super) fn try_import(
@@ -48,24 +60,117 @@ impl LocationRecord {
 
     pub(super) fn import_or_enqueue(
         self,
+        line: u64,
         locations: &mut HashMap<LocationId, Rc<Location>>,
-        queues: &mut (Vec<Self>, Vec<Self>),
-    ) -> Result<(), LocationImportError> {
+        queues: &mut (Queue, Queue),
+    ) -> Result<(), ImportError> {
         if let Err(record) = self.try_import(locations) {
             match record.location_kind {
                 LocationKind::Station => {
-                    return Err(LocationImportError::StationHasParent(record));
+                    return Err(ImportError::InvalidRecord {
+                        file: "stops.txt",
+                        line,
+                        field: "stop_id",
+                        id: record.stop_id.to_string(),
+                        reason: "is a station and must not have a parent station",
+                    });
                 }
                 LocationKind::Stop | LocationKind::Entrance | LocationKind::GenericNode => {
-                    queues.0.push(record);
+                    queues.0.push((line, record));
                 }
                 LocationKind::BoardingArea => {
-                    queues.1.push(record);
+                    queues.1.push((line, record));
                 }
             }
         }
         Ok(())
     }
+
+    /// Creates the error for a location whose parent station does not exist.
+    pub(super) fn parent_not_found(self, line: u64) -> ImportError {
+        ImportError::DanglingReference {
+            file: "stops.txt",
+            line,
+            field: "parent_station",
+            id: self
+                .parent_station
+                .map(|id| id.to_string())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub(super) struct PathwayRecord {
+    pathway_id: String,
+    from_stop_id: LocationId,
+    to_stop_id: LocationId,
+    #[serde(deserialize_with = "deserialize::pathway_mode")]
+    pathway_mode: PathwayMode,
+    #[serde(deserialize_with = "deserialize::numeric_bool")]
+    is_bidirectional: bool,
+    length: Option<f64>,
+    traversal_time: Option<u32>,
+}
+
+impl PathwayRecord {
+    fn invalid(self, line: u64) -> ImportError {
+        ImportError::InvalidRecord {
+            file: "pathways.txt",
+            line,
+            field: "pathway_id",
+            id: self.pathway_id,
+            reason: "does not connect two places of the same station",
+        }
+    }
+
+    pub(super) fn import(
+        self,
+        line: u64,
+        locations: &HashMap<LocationId, Rc<Location>>,
+        complexes: &mut HashMap<LocationId, Complex>,
+    ) -> Result<(), ImportError> {
+        let (from, to) = match (
+            locations.get(&self.from_stop_id),
+            locations.get(&self.to_stop_id),
+        ) {
+            (Some(from), Some(to)) => (from, to),
+            (None, _) => {
+                return Err(ImportError::DanglingReference {
+                    file: "pathways.txt",
+                    line,
+                    field: "from_stop_id",
+                    id: self.from_stop_id.to_string(),
+                })
+            }
+            (_, None) => {
+                return Err(ImportError::DanglingReference {
+                    file: "pathways.txt",
+                    line,
+                    field: "to_stop_id",
+                    id: self.to_stop_id.to_string(),
+                })
+            }
+        };
+        if !Rc::ptr_eq(from, to) {
+            return Err(self.invalid(line));
+        }
+        let station = from.id();
+        let added = complexes.get_mut(&station).and_then(|complex| {
+            complex.add_pathway(
+                &self.from_stop_id,
+                &self.to_stop_id,
+                self.pathway_mode,
+                self.is_bidirectional,
+                self.traversal_time,
+                self.length,
+            )
+        });
+        match added {
+            Some(()) => Ok(()),
+            None => Err(self.invalid(line)),
+        }
+    }
 }
 
 impl Into<Location> for LocationRecord {
@@ -78,7 +183,7 @@ impl Into<Location> for LocationRecord {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::fixtures::locations;
+    use crate::fixtures::{complexes, locations};
     use test_utils::map;
 
     fn main_station_record() -> LocationRecord {
@@ -103,6 +208,93 @@ mod tests {
         }
     }
 
+    fn pathway_record(from: &str, to: &str) -> PathwayRecord {
+        PathwayRecord {
+            pathway_id: "1".to_string(),
+            from_stop_id: from.into(),
+            to_stop_id: to.into(),
+            pathway_mode: PathwayMode::Escalator,
+            is_bidirectional: true,
+            length: None,
+            traversal_time: Some(120),
+        }
+    }
+
+    #[test]
+    fn test_place_of_station() {
+        assert_eq!(main_station_record().place(), None);
+    }
+
+    #[test]
+    fn test_place_of_platform() {
+        assert_eq!(
+            main_station_platform_record().place(),
+            Some((
+                "hauptbahnhof".into(),
+                Place::new(
+                    "hauptbahnhof_1".into(),
+                    LocationKind::Stop,
+                    "Hauptbahnhof Gleis 1".to_string(),
+                    project(52.526, 13.369),
+                )
+            ))
+        );
+    }
+
+    #[test]
+    fn test_import_pathway() {
+        let hauptbahnhof = Rc::new(locations::hauptbahnhof());
+        let locations = map! {
+            "hauptbahnhof" => Rc::clone(&hauptbahnhof),
+            "hauptbahnhof_nord" => Rc::clone(&hauptbahnhof),
+            "hauptbahnhof_1" => Rc::clone(&hauptbahnhof),
+        };
+        let mut expected = complexes::hauptbahnhof();
+        expected
+            .add_pathway(
+                &"hauptbahnhof_nord".into(),
+                &"hauptbahnhof_1".into(),
+                PathwayMode::Escalator,
+                true,
+                Some(120),
+                None,
+            )
+            .unwrap();
+        let mut complexes = map! {
+            "hauptbahnhof" => complexes::hauptbahnhof(),
+        };
+        pathway_record("hauptbahnhof_nord", "hauptbahnhof_1")
+            .import(2, &locations, &mut complexes)
+            .unwrap();
+        assert_eq!(complexes, map! { "hauptbahnhof" => expected });
+    }
+
+    #[test]
+    fn test_import_pathway_between_stations() {
+        let locations = locations::by_id();
+        let mut complexes = HashMap::new();
+        let error = pathway_record("hauptbahnhof", "friedrichstr")
+            .import(2, &locations, &mut complexes)
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "pathways.txt, line 2: pathway_id 1 does not connect two places of the same station"
+        );
+    }
+
+    #[test]
+    fn test_import_pathway_to_unknown_location() {
+        let locations = locations::by_id();
+        let mut complexes = HashMap::new();
+        let error = pathway_record("hauptbahnhof", "unknown")
+            .import(2, &locations, &mut complexes)
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "pathways.txt, line 2: to_stop_id unknown not found"
+        );
+    }
+
     #[test]
     fn test_into_location() {
         let location: Location = main_station_record().into();
@@ -21,15 +21,18 @@ pub enum Profile {
 }
 
 impl Profile {
-    pub(crate) fn filter<'a>(self, agencies: impl Iterator<Item = &'a Agency>) -> Vec<&'a Line> {
+    pub(crate) fn filter<'a>(
+        self,
+        agencies: impl Iterator<Item = &'a Agency>,
+    ) -> Vec<(&'a Agency, &'a Line)> {
         let matching_agencies = agencies
             .filter(|agency| self.matches_agency(agency))
             .collect::<Vec<_>>();
 
         matching_agencies
             .into_iter()
-            .flat_map(|agency| agency.lines())
-            .filter(|line| self.matches_line(line))
+            .flat_map(|agency| agency.lines().iter().map(move |line| (agency, line)))
+            .filter(|(_, line)| self.matches_line(line))
             .collect()
     }
 
@@ -55,7 +58,7 @@ impl Profile {
             Self::BerlinRapidTransit => is_rapid,
             Self::BerlinMetro => is_rapid || line.name().starts_with('M'),
             Self::BerlinWithoutRailway | Self::BerlinBrandenburgWithoutRailway => {
-                line.kind() != Kind::Railway
+                !matches!(line.kind(), Kind::Railway | Kind::RegionalRailway)
             }
             Self::Berlin | Self::BerlinBrandenburg => true,
         }
@@ -75,6 +78,22 @@ pub const PROFILE_NAMES: &[&str] = &[
 
 pub const DEFAULT_PROFILE_NAME: &str = "berlin-no-r";
 
+impl fmt::Display for Profile {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::BerlinSuburbanRailway => "berlin-s",
+            Self::BerlinUrbanRailway => "berlin-u",
+            Self::BerlinRapidTransit => "berlin-s+u",
+            Self::BerlinMetro => "berlin-s+u+metro",
+            Self::BerlinWithoutRailway => "berlin-no-r",
+            Self::Berlin => "berlin",
+            Self::BerlinBrandenburgWithoutRailway => "berlin-brandenburg-no-r",
+            Self::BerlinBrandenburg => "berlin-brandenburg",
+        };
+        formatter.write_str(name)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct InvalidProfileError(String);
 
@@ -103,3 +122,15 @@ impl TryFrom<&str> for Profile {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_round_trip() {
+        for &name in PROFILE_NAMES {
+            assert_eq!(Profile::try_from(name).unwrap().to_string(), name);
+        }
+    }
+}
@@ -0,0 +1,369 @@
+use chrono::{NaiveDate, NaiveDateTime};
+
+use crate::agency::{Agency, AgencyId};
+use crate::line::{Line, LineId};
+use crate::location::LocationId;
+use crate::trip::TripId;
+use simulation::line::Kind;
+use simulation::Severity;
+
+const DAY: i64 = 24 * 60 * 60;
+
+/// Part of the network an alert refers to, all given fields must match.
+///
+/// A selector with a stop refers to the station of the stop only, and only if one of the
+/// selected lines serves it. Otherwise it refers to lines. Selectors of single trips are ignored,
+/// since the simulation has no alerts for single trains.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Selector {
+    agency: Option<AgencyId>,
+    line: Option<LineId>,
+    kind: Option<Kind>,
+    trip: Option<TripId>,
+    stop: Option<LocationId>,
+}
+
+impl Selector {
+    pub(super) fn new(
+        agency: Option<AgencyId>,
+        line: Option<LineId>,
+        kind: Option<Kind>,
+        trip: Option<TripId>,
+        stop: Option<LocationId>,
+    ) -> Self {
+        Self {
+            agency,
+            line,
+            kind,
+            trip,
+            stop,
+        }
+    }
+
+    /// Whether the selector narrows down the lines, instead of referring to all of them.
+    fn selects_lines(&self) -> bool {
+        self.agency.is_some() || self.line.is_some() || self.kind.is_some()
+    }
+
+    fn matches_line(&self, agency: &Agency, line: &Line) -> bool {
+        self.agency.iter().all(|id| id == agency.id())
+            && self.line.iter().all(|id| line.ids().contains(id))
+            && self.kind.iter().all(|&kind| kind == line.kind())
+    }
+
+    fn refers_to_line(&self, agency: &Agency, line: &Line) -> bool {
+        self.trip.is_none()
+            && self.stop.is_none()
+            && self.selects_lines()
+            && self.matches_line(agency, line)
+    }
+
+    fn station(
+        &self,
+        lines: &[(&Agency, &Line)],
+        find_station: &impl Fn(&LocationId) -> Option<usize>,
+    ) -> Option<usize> {
+        if self.trip.is_some() {
+            return None;
+        }
+        let station = find_station(self.stop.as_ref()?)?;
+        let served = !self.selects_lines()
+            || lines.iter().any(|(agency, line)| {
+                self.matches_line(agency, line)
+                    && line.stops_at(|stop| find_station(stop) == Some(station))
+            });
+        Some(station).filter(|_| served)
+    }
+}
+
+/// A service alert with its active periods in the local time of the timetable.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Alert {
+    header: String,
+    severity: Severity,
+    periods: Vec<(Option<NaiveDateTime>, Option<NaiveDateTime>)>,
+    selectors: Vec<Selector>,
+}
+
+impl Alert {
+    pub(super) fn new(
+        header: String,
+        severity: Severity,
+        periods: Vec<(Option<NaiveDateTime>, Option<NaiveDateTime>)>,
+        selectors: Vec<Selector>,
+    ) -> Self {
+        Self {
+            header,
+            severity,
+            periods,
+            selectors,
+        }
+    }
+
+    /// The active periods during the days starting at the date, in seconds since its midnight.
+    fn periods_at(&self, date: NaiveDate, day_count: usize) -> Vec<(u32, u32)> {
+        if self.periods.is_empty() {
+            return vec![(0, u32::MAX)];
+        }
+
+        let midnight = date.and_hms(0, 0, 0);
+        let end_of_days = day_count as i64 * DAY;
+        let seconds = |time: NaiveDateTime| (time - midnight).num_seconds();
+        self.periods
+            .iter()
+            .filter_map(|&(start, end)| {
+                let start = start.map_or(0, seconds).max(0);
+                let end = end
+                    .map_or(i64::from(u32::MAX), seconds)
+                    .min(u32::MAX.into());
+                if start < end_of_days && start < end {
+                    Some((start as u32, end as u32))
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Resolves the alert against the stored lines and stations, if it is active at one of the
+    /// consecutive dates.
+    pub(crate) fn store(
+        &self,
+        dates: &[NaiveDate],
+        lines: &[(&Agency, &Line)],
+        find_station: impl Fn(&LocationId) -> Option<usize>,
+    ) -> Option<storage::Alert> {
+        let periods = self.periods_at(dates[0], dates.len());
+        let mut stations = self
+            .selectors
+            .iter()
+            .filter_map(|selector| selector.station(lines, &find_station))
+            .collect::<Vec<_>>();
+        stations.sort_unstable();
+        stations.dedup();
+        let lines = lines
+            .iter()
+            .enumerate()
+            .filter(|(_, (agency, line))| {
+                self.selectors
+                    .iter()
+                    .any(|selector| selector.refers_to_line(agency, line))
+            })
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>();
+
+        if periods.is_empty() || (lines.is_empty() && stations.is_empty()) {
+            return None;
+        }
+        Some(storage::Alert::new(
+            self.header.clone(),
+            self.severity,
+            periods,
+            lines,
+            stations,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{agencies, lines};
+    use test_utils::time;
+
+    fn date() -> NaiveDate {
+        NaiveDate::from_ymd(2019, 1, 7)
+    }
+
+    fn alert(
+        periods: Vec<(Option<NaiveDateTime>, Option<NaiveDateTime>)>,
+        selectors: Vec<Selector>,
+    ) -> Alert {
+        Alert::new(
+            "Construction works".to_string(),
+            Severity::Warning,
+            periods,
+            selectors,
+        )
+    }
+
+    fn line_selector(line: &str) -> Selector {
+        Selector {
+            line: Some(line.into()),
+            ..Selector::default()
+        }
+    }
+
+    #[test]
+    fn test_periods_at() {
+        let periods = vec![
+            (None, Some(date().and_hms(1, 0, 0))),
+            (
+                Some(date().and_hms(9, 0, 0)),
+                Some(date().and_hms(12, 0, 0)),
+            ),
+            (Some(date().succ().and_hms(9, 0, 0)), None),
+        ];
+        let limited = alert(periods, vec![]);
+        assert_eq!(
+            limited.periods_at(date(), 1),
+            [
+                (time!(0:00:00), time!(1:00:00)),
+                (time!(9:00:00), time!(12:00:00)),
+            ]
+        );
+        assert_eq!(
+            limited.periods_at(date(), 2),
+            [
+                (time!(0:00:00), time!(1:00:00)),
+                (time!(9:00:00), time!(12:00:00)),
+                (time!(33:00:00), u32::MAX),
+            ]
+        );
+        assert_eq!(alert(vec![], vec![]).periods_at(date(), 1), [(0, u32::MAX)]);
+    }
+
+    #[test]
+    fn test_store_line() {
+        let agency = agencies::pubtrans(vec![lines::u4(), lines::tram_12()]);
+        let lines = agency
+            .lines()
+            .iter()
+            .map(|line| (&agency, line))
+            .collect::<Vec<_>>();
+        let alert = alert(vec![], vec![line_selector("4")]);
+        assert_eq!(
+            alert.store(&[date()], &lines, |_| None),
+            Some(storage::Alert::new(
+                "Construction works".to_string(),
+                Severity::Warning,
+                vec![(0, u32::MAX)],
+                vec![1],
+                vec![],
+            ))
+        );
+    }
+
+    #[test]
+    fn test_store_agency_and_kind() {
+        let agency = agencies::pubtrans(vec![lines::u4(), lines::tram_12()]);
+        let lines = agency
+            .lines()
+            .iter()
+            .map(|line| (&agency, line))
+            .collect::<Vec<_>>();
+        let selector = Selector {
+            agency: Some("1".into()),
+            kind: Some(Kind::Tram),
+            ..Selector::default()
+        };
+        let stored = alert(vec![], vec![selector]).store(&[date()], &lines, |_| None);
+        assert_eq!(
+            stored,
+            Some(storage::Alert::new(
+                "Construction works".to_string(),
+                Severity::Warning,
+                vec![(0, u32::MAX)],
+                vec![1],
+                vec![],
+            ))
+        );
+        let selector = Selector {
+            agency: Some("2".into()),
+            ..Selector::default()
+        };
+        assert_eq!(
+            alert(vec![], vec![selector]).store(&[date()], &lines, |_| None),
+            None
+        );
+    }
+
+    #[test]
+    fn test_store_stop() {
+        let selector = Selector {
+            stop: Some("friedrichstr".into()),
+            ..Selector::default()
+        };
+        let alert = alert(vec![], vec![selector]);
+        let find_station = |stop: &LocationId| match stop.to_string().as_str() {
+            "friedrichstr" => Some(1),
+            _ => None,
+        };
+        assert_eq!(
+            alert.store(&[date()], &[], find_station),
+            Some(storage::Alert::new(
+                "Construction works".to_string(),
+                Severity::Warning,
+                vec![(0, u32::MAX)],
+                vec![],
+                vec![1],
+            ))
+        );
+    }
+
+    #[test]
+    fn test_store_stop_of_line() {
+        let agency = agencies::pubtrans(vec![lines::u4(), lines::tram_12_with_route()]);
+        let lines = agency
+            .lines()
+            .iter()
+            .map(|line| (&agency, line))
+            .collect::<Vec<_>>();
+        let find_station = |stop: &LocationId| match stop.to_string().as_str() {
+            "friedrichstr" => Some(1),
+            _ => None,
+        };
+        let stop_of_line = |line: &str| Selector {
+            line: Some(line.into()),
+            stop: Some("friedrichstr".into()),
+            ..Selector::default()
+        };
+        assert_eq!(
+            alert(vec![], vec![stop_of_line("4")]).store(&[date()], &lines, find_station),
+            Some(storage::Alert::new(
+                "Construction works".to_string(),
+                Severity::Warning,
+                vec![(0, u32::MAX)],
+                vec![],
+                vec![1],
+            ))
+        );
+        assert_eq!(
+            alert(vec![], vec![stop_of_line("3")]).store(&[date()], &lines, find_station),
+            None
+        );
+    }
+
+    #[test]
+    fn test_store_ignores_trips() {
+        let agency = agencies::pubtrans(vec![lines::u4(), lines::tram_12_with_route()]);
+        let lines = agency
+            .lines()
+            .iter()
+            .map(|line| (&agency, line))
+            .collect::<Vec<_>>();
+        let selector = Selector {
+            line: Some("4".into()),
+            trip: Some("1".into()),
+            ..Selector::default()
+        };
+        assert_eq!(
+            alert(vec![], vec![selector]).store(&[date()], &lines, |_| Some(1)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_store_inactive() {
+        let periods = vec![(
+            Some(date().pred().and_hms(9, 0, 0)),
+            Some(date().pred().and_hms(12, 0, 0)),
+        )];
+        let selector = Selector {
+            stop: Some("friedrichstr".into()),
+            ..Selector::default()
+        };
+        let alert = alert(periods, vec![selector]);
+        assert_eq!(alert.store(&[date()], &[], |_| Some(0)), None);
+    }
+}
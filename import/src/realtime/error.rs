@@ -0,0 +1,69 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+/// Reasons why a GTFS Realtime feed could not be read.
+#[derive(Debug)]
+pub enum RealtimeError {
+    /// The feed file could not be read.
+    Unreadable { path: PathBuf, source: io::Error },
+    /// The feed is no valid `FeedMessage`.
+    Decode { source: prost::DecodeError },
+    /// An entity of the feed contains a malformed value.
+    InvalidEntity {
+        entity: String,
+        field: &'static str,
+        value: String,
+    },
+}
+
+impl fmt::Display for RealtimeError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RealtimeError::Unreadable { path, source } => {
+                write!(formatter, "could not read {}: {}", path.display(), source)
+            }
+            RealtimeError::Decode { source } => {
+                write!(formatter, "could not decode realtime feed: {}", source)
+            }
+            RealtimeError::InvalidEntity {
+                entity,
+                field,
+                value,
+            } => write!(
+                formatter,
+                "entity {}: invalid {} {:?}",
+                entity, field, value
+            ),
+        }
+    }
+}
+
+impl Error for RealtimeError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            RealtimeError::Unreadable { source, .. } => Some(source),
+            RealtimeError::Decode { source } => Some(source),
+            RealtimeError::InvalidEntity { .. } => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_invalid_entity() {
+        let error = RealtimeError::InvalidEntity {
+            entity: "1".to_string(),
+            field: "start_date",
+            value: "2019-08-26".to_string(),
+        };
+        assert_eq!(
+            error.to_string(),
+            "entity 1: invalid start_date \"2019-08-26\""
+        );
+    }
+}
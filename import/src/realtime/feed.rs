@@ -0,0 +1,211 @@
+//! The subset of the GTFS Realtime protocol buffers which is understood by the importer.
+//!
+//! Field numbers follow `gtfs-realtime.proto`, unknown fields are skipped while decoding.
+
+use prost::{Enumeration, Message};
+
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct FeedMessage {
+    #[prost(message, required, tag = "1")]
+    pub(crate) header: FeedHeader,
+    #[prost(message, repeated, tag = "2")]
+    pub(crate) entity: Vec<FeedEntity>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct FeedHeader {
+    #[prost(string, required, tag = "1")]
+    pub(crate) gtfs_realtime_version: String,
+    #[prost(uint64, optional, tag = "3")]
+    pub(crate) timestamp: Option<u64>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct FeedEntity {
+    #[prost(string, required, tag = "1")]
+    pub(crate) id: String,
+    #[prost(bool, optional, tag = "2")]
+    pub(crate) is_deleted: Option<bool>,
+    #[prost(message, optional, tag = "3")]
+    pub(crate) trip_update: Option<TripUpdate>,
+    #[prost(message, optional, tag = "4")]
+    pub(crate) vehicle: Option<VehiclePosition>,
+    #[prost(message, optional, tag = "5")]
+    pub(crate) alert: Option<Alert>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct TripUpdate {
+    #[prost(message, required, tag = "1")]
+    pub(crate) trip: TripDescriptor,
+    #[prost(message, repeated, tag = "2")]
+    pub(crate) stop_time_update: Vec<StopTimeUpdate>,
+    #[prost(int32, optional, tag = "5")]
+    pub(crate) delay: Option<i32>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct StopTimeEvent {
+    #[prost(int32, optional, tag = "1")]
+    pub(crate) delay: Option<i32>,
+    #[prost(int64, optional, tag = "2")]
+    pub(crate) time: Option<i64>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct StopTimeUpdate {
+    #[prost(uint32, optional, tag = "1")]
+    pub(crate) stop_sequence: Option<u32>,
+    #[prost(string, optional, tag = "4")]
+    pub(crate) stop_id: Option<String>,
+    #[prost(message, optional, tag = "2")]
+    pub(crate) arrival: Option<StopTimeEvent>,
+    #[prost(message, optional, tag = "3")]
+    pub(crate) departure: Option<StopTimeEvent>,
+    #[prost(enumeration = "StopRelationship", optional, tag = "5")]
+    pub(crate) schedule_relationship: Option<i32>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Enumeration)]
+pub(crate) enum StopRelationship {
+    Scheduled = 0,
+    Skipped = 1,
+    NoData = 2,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct TripDescriptor {
+    #[prost(string, optional, tag = "1")]
+    pub(crate) trip_id: Option<String>,
+    #[prost(string, optional, tag = "5")]
+    pub(crate) route_id: Option<String>,
+    #[prost(uint32, optional, tag = "6")]
+    pub(crate) direction_id: Option<u32>,
+    #[prost(string, optional, tag = "2")]
+    pub(crate) start_time: Option<String>,
+    #[prost(string, optional, tag = "3")]
+    pub(crate) start_date: Option<String>,
+    #[prost(enumeration = "TripRelationship", optional, tag = "4")]
+    pub(crate) schedule_relationship: Option<i32>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Enumeration)]
+pub(crate) enum TripRelationship {
+    Scheduled = 0,
+    Added = 1,
+    Unscheduled = 2,
+    Canceled = 3,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct VehiclePosition {
+    #[prost(message, optional, tag = "1")]
+    pub(crate) trip: Option<TripDescriptor>,
+    #[prost(message, optional, tag = "8")]
+    pub(crate) vehicle: Option<VehicleDescriptor>,
+    #[prost(message, optional, tag = "2")]
+    pub(crate) position: Option<Position>,
+    #[prost(uint32, optional, tag = "3")]
+    pub(crate) current_stop_sequence: Option<u32>,
+    #[prost(string, optional, tag = "7")]
+    pub(crate) stop_id: Option<String>,
+    #[prost(enumeration = "VehicleStopStatus", optional, tag = "4")]
+    pub(crate) current_status: Option<i32>,
+    #[prost(uint64, optional, tag = "5")]
+    pub(crate) timestamp: Option<u64>,
+    #[prost(enumeration = "OccupancyStatus", optional, tag = "9")]
+    pub(crate) occupancy_status: Option<i32>,
+    #[prost(uint32, optional, tag = "10")]
+    pub(crate) occupancy_percentage: Option<u32>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Enumeration)]
+pub(crate) enum VehicleStopStatus {
+    IncomingAt = 0,
+    StoppedAt = 1,
+    InTransitTo = 2,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Enumeration)]
+pub(crate) enum OccupancyStatus {
+    Empty = 0,
+    ManySeatsAvailable = 1,
+    FewSeatsAvailable = 2,
+    StandingRoomOnly = 3,
+    CrushedStandingRoomOnly = 4,
+    Full = 5,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct VehicleDescriptor {
+    #[prost(string, optional, tag = "1")]
+    pub(crate) id: Option<String>,
+    #[prost(string, optional, tag = "2")]
+    pub(crate) label: Option<String>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct Position {
+    #[prost(float, required, tag = "1")]
+    pub(crate) latitude: f32,
+    #[prost(float, required, tag = "2")]
+    pub(crate) longitude: f32,
+    #[prost(float, optional, tag = "3")]
+    pub(crate) bearing: Option<f32>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct Alert {
+    #[prost(message, repeated, tag = "1")]
+    pub(crate) active_period: Vec<TimeRange>,
+    #[prost(message, repeated, tag = "5")]
+    pub(crate) informed_entity: Vec<EntitySelector>,
+    #[prost(message, optional, tag = "10")]
+    pub(crate) header_text: Option<TranslatedString>,
+    #[prost(enumeration = "SeverityLevel", optional, tag = "14")]
+    pub(crate) severity_level: Option<i32>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct TimeRange {
+    #[prost(uint64, optional, tag = "1")]
+    pub(crate) start: Option<u64>,
+    #[prost(uint64, optional, tag = "2")]
+    pub(crate) end: Option<u64>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct EntitySelector {
+    #[prost(string, optional, tag = "1")]
+    pub(crate) agency_id: Option<String>,
+    #[prost(string, optional, tag = "2")]
+    pub(crate) route_id: Option<String>,
+    #[prost(int32, optional, tag = "3")]
+    pub(crate) route_type: Option<i32>,
+    #[prost(message, optional, tag = "4")]
+    pub(crate) trip: Option<TripDescriptor>,
+    #[prost(string, optional, tag = "5")]
+    pub(crate) stop_id: Option<String>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct TranslatedString {
+    #[prost(message, repeated, tag = "1")]
+    pub(crate) translation: Vec<Translation>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct Translation {
+    #[prost(string, required, tag = "1")]
+    pub(crate) text: String,
+    #[prost(string, optional, tag = "2")]
+    pub(crate) language: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Enumeration)]
+pub(crate) enum SeverityLevel {
+    UnknownSeverity = 1,
+    Info = 2,
+    Warning = 3,
+    Severe = 4,
+}
@@ -0,0 +1,10 @@
+mod alert;
+mod error;
+pub(crate) mod feed;
+mod realtime;
+mod trip_update;
+mod vehicle_positions;
+
+pub use error::RealtimeError;
+pub use realtime::Realtime;
+pub use vehicle_positions::VehiclePositions;
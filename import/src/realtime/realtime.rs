@@ -0,0 +1,646 @@
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fs;
+use std::path::Path;
+
+use chrono::{Duration, FixedOffset, NaiveDate, NaiveDateTime};
+use prost::Message;
+use serde::de::value::Error as ValueError;
+use serde::de::IntoDeserializer;
+
+use super::alert::{Alert, Selector};
+use super::feed::{self, FeedMessage, SeverityLevel, StopRelationship, TripRelationship};
+use super::trip_update::{Change, Event, StopUpdate, TripUpdate};
+use super::RealtimeError;
+use crate::deserialize;
+use crate::line::LineId;
+use crate::location::LocationId;
+use crate::trip::TripId;
+use simulation::{Direction, Severity};
+
+/// Deviations from the static timetable, as read from GTFS Realtime `TripUpdates` and
+/// `ServiceAlerts`.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Realtime {
+    updates: HashMap<TripId, Vec<TripUpdate>>,
+    added: Vec<TripUpdate>,
+    alerts: Vec<Alert>,
+}
+
+/// Converts the fields of a single feed entity.
+struct EntityDecoder<'a> {
+    entity: &'a str,
+    utc_offset: FixedOffset,
+    namespace: Option<&'a str>,
+}
+
+impl EntityDecoder<'_> {
+    /// The id prefixed with the namespace of the static feed, like the ids imported from it.
+    fn id<T: for<'s> From<&'s str>>(&self, id: &str) -> T {
+        match self.namespace {
+            Some(namespace) => T::from(format!("{}:{}", namespace, id).as_str()),
+            None => T::from(id),
+        }
+    }
+
+    fn invalid(&self, field: &'static str, value: &str) -> RealtimeError {
+        RealtimeError::InvalidEntity {
+            entity: self.entity.to_string(),
+            field,
+            value: value.to_string(),
+        }
+    }
+
+    fn date(&self, value: &str) -> Result<NaiveDate, RealtimeError> {
+        NaiveDate::parse_from_str(value, "%Y%m%d").map_err(|_| self.invalid("start_date", value))
+    }
+
+    fn time(&self, value: &str) -> Result<Duration, RealtimeError> {
+        deserialize::duration::<_>(IntoDeserializer::<ValueError>::into_deserializer(value))
+            .map_err(|_| self.invalid("start_time", value))
+    }
+
+    fn timestamp(
+        &self,
+        field: &'static str,
+        timestamp: i64,
+    ) -> Result<NaiveDateTime, RealtimeError> {
+        timestamp
+            .checked_add(self.utc_offset.local_minus_utc().into())
+            .and_then(|local| NaiveDateTime::from_timestamp_opt(local, 0))
+            .ok_or_else(|| self.invalid(field, &timestamp.to_string()))
+    }
+
+    fn event(
+        &self,
+        event: &feed::StopTimeEvent,
+        date: Option<NaiveDate>,
+    ) -> Result<Option<Event>, RealtimeError> {
+        if let Some(delay) = event.delay {
+            return Ok(Some(Event::Delay(Duration::seconds(delay.into()))));
+        }
+        let time = match event.time {
+            Some(time) => time,
+            None => return Ok(None),
+        };
+        let local = self.timestamp("time", time)?;
+        let midnight = date.unwrap_or_else(|| local.date()).and_hms(0, 0, 0);
+        Ok(Some(Event::Time(local - midnight)))
+    }
+
+    fn stop_update(
+        &self,
+        update: &feed::StopTimeUpdate,
+        date: Option<NaiveDate>,
+    ) -> Result<StopUpdate, RealtimeError> {
+        let relationship = update
+            .schedule_relationship
+            .and_then(StopRelationship::from_i32)
+            .unwrap_or(StopRelationship::Scheduled);
+        let event = |event: &Option<feed::StopTimeEvent>| match event {
+            Some(event) => self.event(event, date),
+            None => Ok(None),
+        };
+        Ok(StopUpdate::new(
+            update.stop_sequence,
+            update.stop_id.as_deref().map(|id| self.id(id)),
+            event(&update.arrival)?,
+            event(&update.departure)?,
+            relationship == StopRelationship::Skipped,
+            relationship == StopRelationship::NoData,
+        ))
+    }
+
+    fn trip_update(&self, update: &feed::TripUpdate) -> Result<TripUpdate, RealtimeError> {
+        let descriptor = &update.trip;
+        let start_date = descriptor
+            .start_date
+            .as_deref()
+            .map(|date| self.date(date))
+            .transpose()?;
+        let start_time = descriptor
+            .start_time
+            .as_deref()
+            .map(|time| self.time(time))
+            .transpose()?;
+        let stops = update
+            .stop_time_update
+            .iter()
+            .map(|stop| self.stop_update(stop, start_date))
+            .collect::<Result<_, _>>()?;
+
+        let relationship = descriptor
+            .schedule_relationship
+            .and_then(TripRelationship::from_i32)
+            .unwrap_or(TripRelationship::Scheduled);
+        let change = match relationship {
+            TripRelationship::Canceled => Change::Canceled,
+            TripRelationship::Added => Change::Added {
+                line: descriptor.route_id.as_deref().map(|id| self.id(id)),
+                direction: descriptor.direction_id.map(|direction| match direction {
+                    0 => Direction::Upstream,
+                    _ => Direction::Downstream,
+                }),
+                stops,
+            },
+            TripRelationship::Scheduled | TripRelationship::Unscheduled => Change::Delayed {
+                delay: update.delay.map(|delay| Duration::seconds(delay.into())),
+                stops,
+            },
+        };
+        Ok(TripUpdate::new(
+            descriptor.trip_id.as_deref().map(|id| self.id(id)),
+            start_date,
+            start_time,
+            change,
+        ))
+    }
+
+    fn selector(&self, selector: &feed::EntitySelector) -> Result<Selector, RealtimeError> {
+        let kind = selector
+            .route_type
+            .map(|route_type| {
+                deserialize::line_kind(IntoDeserializer::<ValueError>::into_deserializer(
+                    route_type as u64,
+                ))
+                .map_err(|_| self.invalid("route_type", &route_type.to_string()))
+            })
+            .transpose()?;
+        let trip = selector.trip.as_ref();
+        let line = selector
+            .route_id
+            .as_deref()
+            .or_else(|| trip?.route_id.as_deref());
+        Ok(Selector::new(
+            selector.agency_id.as_deref().map(|id| self.id(id)),
+            line.map(|id| self.id(id)),
+            kind,
+            trip.and_then(|trip| trip.trip_id.as_deref())
+                .map(|id| self.id(id)),
+            selector
+                .stop_id
+                .as_deref()
+                .map(|id| self.id::<LocationId>(id)),
+        ))
+    }
+
+    fn alert(&self, alert: &feed::Alert) -> Result<Alert, RealtimeError> {
+        let header = alert
+            .header_text
+            .as_ref()
+            .and_then(|text| text.translation.first())
+            .map_or_else(String::new, |translation| translation.text.clone());
+        let severity = match alert.severity_level.and_then(SeverityLevel::from_i32) {
+            Some(SeverityLevel::Info) => Severity::Info,
+            Some(SeverityLevel::Warning) => Severity::Warning,
+            Some(SeverityLevel::Severe) => Severity::Severe,
+            Some(SeverityLevel::UnknownSeverity) | None => Severity::Unknown,
+        };
+        let timestamp = |timestamp: Option<u64>| {
+            timestamp
+                .map(|timestamp| {
+                    let timestamp = i64::try_from(timestamp)
+                        .map_err(|_| self.invalid("active_period", &timestamp.to_string()))?;
+                    self.timestamp("active_period", timestamp)
+                })
+                .transpose()
+        };
+        let periods = alert
+            .active_period
+            .iter()
+            .map(|period| Ok((timestamp(period.start)?, timestamp(period.end)?)))
+            .collect::<Result<_, _>>()?;
+        let selectors = alert
+            .informed_entity
+            .iter()
+            .map(|selector| self.selector(selector))
+            .collect::<Result<_, _>>()?;
+        Ok(Alert::new(header, severity, periods, selectors))
+    }
+}
+
+impl Realtime {
+    /// Decodes a `FeedMessage` with trip updates and service alerts.
+    ///
+    /// Absolute times in the feed are converted to times of the service day using the UTC offset
+    /// of the static timetable. If several static feeds were imported, the namespace of the one
+    /// the updates refer to is prefixed to their ids.
+    pub fn decode(
+        bytes: &[u8],
+        utc_offset: FixedOffset,
+        namespace: Option<&str>,
+    ) -> Result<Self, RealtimeError> {
+        let message =
+            FeedMessage::decode(bytes).map_err(|source| RealtimeError::Decode { source })?;
+        let mut realtime = Self::default();
+        for entity in &message.entity {
+            if entity.is_deleted == Some(true) {
+                continue;
+            }
+            let decoder = EntityDecoder {
+                entity: &entity.id,
+                utc_offset,
+                namespace,
+            };
+            if let Some(alert) = &entity.alert {
+                realtime.alerts.push(decoder.alert(alert)?);
+            }
+            let update = match &entity.trip_update {
+                Some(update) => decoder.trip_update(update)?,
+                None => continue,
+            };
+            if update.is_added() {
+                realtime.added.push(update);
+            } else if let Some(trip) = update.trip().cloned() {
+                realtime
+                    .updates
+                    .entry(trip)
+                    .or_insert_with(Vec::new)
+                    .push(update);
+            }
+        }
+        Ok(realtime)
+    }
+
+    /// Adds the updates and alerts of another feed.
+    pub fn extend(&mut self, other: Self) {
+        for (trip, updates) in other.updates {
+            self.updates.entry(trip).or_default().extend(updates);
+        }
+        self.added.extend(other.added);
+        self.alerts.extend(other.alerts);
+    }
+
+    pub fn read(
+        path: impl AsRef<Path>,
+        utc_offset: FixedOffset,
+        namespace: Option<&str>,
+    ) -> Result<Self, RealtimeError> {
+        let path = path.as_ref();
+        let bytes = fs::read(path).map_err(|source| RealtimeError::Unreadable {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Self::decode(&bytes, utc_offset, namespace)
+    }
+
+    /// The updates with their stop ids resolved, for example from platforms to their stations.
+    ///
+    /// Feeds usually refer to platforms, while the stops of the trips are identified by their
+    /// stations. Stop ids which do not resolve are kept.
+    pub(crate) fn resolve_stops(
+        &self,
+        resolve: impl Fn(&LocationId) -> Option<LocationId>,
+    ) -> Self {
+        let mut realtime = self.clone();
+        for update in realtime.updates.values_mut().flatten() {
+            update.resolve_stops(&resolve);
+        }
+        for update in &mut realtime.added {
+            update.resolve_stops(&resolve);
+        }
+        realtime
+    }
+
+    /// The update for the trip starting at the given date and time, if any.
+    pub(crate) fn update(
+        &self,
+        trip: &TripId,
+        date: NaiveDate,
+        start: Duration,
+    ) -> Option<&TripUpdate> {
+        self.updates
+            .get(trip)?
+            .iter()
+            .find(|update| update.applies_to(date, Some(start)))
+    }
+
+    pub(crate) fn alerts(&self) -> impl Iterator<Item = &Alert> {
+        self.alerts.iter()
+    }
+
+    /// Trips added to one of the lines at the given date and running in the direction.
+    pub(crate) fn added_trips<'a>(
+        &'a self,
+        lines: &'a [LineId],
+        date: NaiveDate,
+        direction: Direction,
+    ) -> impl Iterator<Item = &'a TripUpdate> {
+        self.added.iter().filter(move |update| {
+            update.applies_to(date, None) && update.is_added_to(lines, direction)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::realtime::feed::{FeedEntity, FeedHeader, TripDescriptor};
+    use test_utils::{time, times};
+
+    fn utc() -> FixedOffset {
+        FixedOffset::east(0)
+    }
+
+    fn date() -> NaiveDate {
+        NaiveDate::from_ymd(2019, 1, 7)
+    }
+
+    fn encode(trip: TripDescriptor, stop_time_update: Vec<feed::StopTimeUpdate>) -> Vec<u8> {
+        let message = FeedMessage {
+            header: FeedHeader {
+                gtfs_realtime_version: "2.0".to_string(),
+                timestamp: None,
+            },
+            entity: vec![FeedEntity {
+                id: "1".to_string(),
+                is_deleted: None,
+                trip_update: Some(feed::TripUpdate {
+                    trip,
+                    stop_time_update,
+                    delay: None,
+                }),
+                vehicle: None,
+                alert: None,
+            }],
+        };
+        let mut bytes = Vec::new();
+        message.encode(&mut bytes).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_read() {
+        let realtime = Realtime::read("tests/data/realtime/trip_updates.pb", utc(), None).unwrap();
+        assert!(realtime
+            .update(&"1".into(), date(), time!(Duration; 9:02:00))
+            .is_some());
+        assert!(realtime
+            .update(&"1".into(), date(), time!(Duration; 9:12:00))
+            .is_none());
+        assert!(realtime
+            .update(&"2".into(), date(), time!(Duration; 8:34:00))
+            .is_some());
+        assert!(realtime
+            .update(&"2".into(), date().succ(), time!(Duration; 8:34:00))
+            .is_none());
+        let lines = ["4".into()];
+        assert_eq!(
+            realtime
+                .added_trips(&lines, date(), Direction::Upstream)
+                .count(),
+            1
+        );
+        assert_eq!(
+            realtime
+                .added_trips(&lines, date(), Direction::Downstream)
+                .count(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_read_alerts() {
+        let realtime = Realtime::read("tests/data/realtime/alerts.pb", utc(), None).unwrap();
+        assert!(realtime.updates.is_empty());
+        assert_eq!(
+            realtime.alerts().collect::<Vec<_>>(),
+            [
+                &Alert::new(
+                    "Construction works".to_string(),
+                    Severity::Warning,
+                    vec![(
+                        Some(date().and_hms(6, 0, 0)),
+                        Some(date().and_hms(10, 0, 0))
+                    )],
+                    vec![Selector::new(None, Some("4".into()), None, None, None)],
+                ),
+                &Alert::new(
+                    "Friedrichstr. closed".to_string(),
+                    Severity::Severe,
+                    vec![(
+                        Some(date().and_hms(9, 0, 0)),
+                        Some(date().and_hms(12, 0, 0))
+                    )],
+                    vec![Selector::new(
+                        None,
+                        None,
+                        None,
+                        None,
+                        Some("friedrichstr".into())
+                    )],
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extend() {
+        let mut realtime =
+            Realtime::read("tests/data/realtime/trip_updates.pb", utc(), None).unwrap();
+        realtime.extend(Realtime::read("tests/data/realtime/alerts.pb", utc(), None).unwrap());
+        assert!(realtime
+            .update(&"1".into(), date(), time!(Duration; 9:02:00))
+            .is_some());
+        assert_eq!(realtime.alerts().count(), 2);
+    }
+
+    #[test]
+    fn test_read_namespaced() {
+        let realtime =
+            Realtime::read("tests/data/realtime/trip_updates.pb", utc(), Some("vbb")).unwrap();
+        assert!(realtime
+            .update(&"vbb:1".into(), date(), time!(Duration; 9:02:00))
+            .is_some());
+        assert!(realtime
+            .update(&"1".into(), date(), time!(Duration; 9:02:00))
+            .is_none());
+        let lines = ["vbb:4".into()];
+        assert_eq!(
+            realtime
+                .added_trips(&lines, date(), Direction::Upstream)
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_read_missing_file() {
+        let error = Realtime::read("tests/data/realtime/missing.pb", utc(), None).unwrap_err();
+        assert!(matches!(error, RealtimeError::Unreadable { .. }));
+    }
+
+    #[test]
+    fn test_decode_truncated() {
+        let error = Realtime::read("tests/data/realtime/truncated.pb", utc(), None).unwrap_err();
+        assert!(matches!(error, RealtimeError::Decode { .. }));
+    }
+
+    #[test]
+    fn test_decode_invalid_start_date() {
+        let trip = TripDescriptor {
+            trip_id: Some("1".to_string()),
+            start_date: Some("2019-01-07".to_string()),
+            ..TripDescriptor::default()
+        };
+        let error = Realtime::decode(&encode(trip, Vec::new()), utc(), None).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "entity 1: invalid start_date \"2019-01-07\""
+        );
+    }
+
+    #[test]
+    fn test_decode_invalid_route_type() {
+        let alert = feed::Alert {
+            active_period: Vec::new(),
+            informed_entity: vec![feed::EntitySelector {
+                route_type: Some(42),
+                ..feed::EntitySelector::default()
+            }],
+            header_text: None,
+            severity_level: None,
+        };
+        let message = FeedMessage {
+            header: FeedHeader {
+                gtfs_realtime_version: "2.0".to_string(),
+                timestamp: None,
+            },
+            entity: vec![FeedEntity {
+                id: "1".to_string(),
+                is_deleted: None,
+                trip_update: None,
+                vehicle: None,
+                alert: Some(alert),
+            }],
+        };
+        let mut bytes = Vec::new();
+        message.encode(&mut bytes).unwrap();
+        let error = Realtime::decode(&bytes, utc(), None).unwrap_err();
+        assert_eq!(error.to_string(), "entity 1: invalid route_type \"42\"");
+    }
+
+    #[test]
+    fn test_decode_invalid_active_period() {
+        let alert = feed::Alert {
+            active_period: vec![feed::TimeRange {
+                start: Some(u64::MAX),
+                end: None,
+            }],
+            informed_entity: vec![feed::EntitySelector {
+                stop_id: Some("friedrichstr".to_string()),
+                ..feed::EntitySelector::default()
+            }],
+            header_text: None,
+            severity_level: None,
+        };
+        let message = FeedMessage {
+            header: FeedHeader {
+                gtfs_realtime_version: "2.0".to_string(),
+                timestamp: None,
+            },
+            entity: vec![FeedEntity {
+                id: "1".to_string(),
+                is_deleted: None,
+                trip_update: None,
+                vehicle: None,
+                alert: Some(alert),
+            }],
+        };
+        let mut bytes = Vec::new();
+        message.encode(&mut bytes).unwrap();
+        let error = Realtime::decode(&bytes, utc(), None).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            format!("entity 1: invalid active_period \"{}\"", u64::MAX)
+        );
+    }
+
+    #[test]
+    fn test_decode_invalid_time() {
+        let trip = TripDescriptor {
+            trip_id: Some("1".to_string()),
+            ..TripDescriptor::default()
+        };
+        let stop = feed::StopTimeUpdate {
+            stop_id: Some("friedrichstr".to_string()),
+            arrival: Some(feed::StopTimeEvent {
+                delay: None,
+                time: Some(i64::MAX),
+            }),
+            ..feed::StopTimeUpdate::default()
+        };
+        let error = Realtime::decode(&encode(trip, vec![stop]), utc(), None).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            format!("entity 1: invalid time \"{}\"", i64::MAX)
+        );
+    }
+
+    #[test]
+    fn test_resolve_platform_stops() {
+        let trip = TripDescriptor {
+            trip_id: Some("1".to_string()),
+            ..TripDescriptor::default()
+        };
+        let stop = feed::StopTimeUpdate {
+            stop_id: Some("friedrichstr_2".to_string()),
+            arrival: Some(feed::StopTimeEvent {
+                delay: Some(60),
+                time: None,
+            }),
+            ..feed::StopTimeUpdate::default()
+        };
+        let realtime = Realtime::decode(&encode(trip, vec![stop]), utc(), None).unwrap();
+        let locations = ["oranienburger_tor".into(), "friedrichstr".into()];
+        let durations = times!(Duration; 9:02:00, 0:00, 2:00, 0:00);
+        let apply = |realtime: &Realtime| {
+            realtime
+                .update(&"1".into(), date(), time!(Duration; 9:02:00))
+                .unwrap()
+                .apply(&locations, None, &durations)
+                .unwrap()
+        };
+        assert_eq!(apply(&realtime), durations);
+
+        let resolved = realtime.resolve_stops(|stop| match stop.to_string().as_str() {
+            "friedrichstr_2" => Some("friedrichstr".into()),
+            _ => None,
+        });
+        assert_eq!(
+            apply(&resolved),
+            times!(Duration; 9:02:00, 0:00, 3:00, 0:00)
+        );
+    }
+
+    #[test]
+    fn test_decode_absolute_time() {
+        let trip = TripDescriptor {
+            trip_id: Some("1".to_string()),
+            start_date: Some("20190107".to_string()),
+            ..TripDescriptor::default()
+        };
+        let stop = feed::StopTimeUpdate {
+            stop_id: Some("friedrichstr".to_string()),
+            arrival: Some(feed::StopTimeEvent {
+                delay: None,
+                // 2019-01-07 08:05:00 UTC
+                time: Some(1_546_848_300),
+            }),
+            ..feed::StopTimeUpdate::default()
+        };
+        let realtime =
+            Realtime::decode(&encode(trip, vec![stop]), FixedOffset::east(3600), None).unwrap();
+        let update = realtime
+            .update(&"1".into(), date(), time!(Duration; 9:02:00))
+            .unwrap();
+        let durations = update
+            .apply(
+                &["oranienburger_tor".into(), "friedrichstr".into()],
+                None,
+                &times!(Duration; 9:02:00, 0:00, 2:00, 0:00),
+            )
+            .unwrap();
+        assert_eq!(durations, times!(Duration; 9:02:00, 0:00, 3:00, 0:00));
+    }
+}
@@ -0,0 +1,420 @@
+use chrono::{Duration, NaiveDate};
+
+use crate::line::LineId;
+use crate::location::LocationId;
+use crate::trip::TripId;
+use simulation::Direction;
+
+/// Arrival or departure of a vehicle at a stop as reported by a realtime feed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Event {
+    /// Deviation from the scheduled time.
+    Delay(Duration),
+    /// Time since midnight of the service day.
+    Time(Duration),
+}
+
+impl Event {
+    fn delay(self, scheduled: Duration) -> Duration {
+        match self {
+            Event::Delay(delay) => delay,
+            Event::Time(time) => time - scheduled,
+        }
+    }
+
+    fn time(self) -> Option<Duration> {
+        match self {
+            Event::Delay(_) => None,
+            Event::Time(time) => Some(time),
+        }
+    }
+}
+
+/// Realtime information about one stop of a trip.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct StopUpdate {
+    sequence: Option<u32>,
+    location: Option<LocationId>,
+    arrival: Option<Event>,
+    departure: Option<Event>,
+    skipped: bool,
+    no_data: bool,
+}
+
+impl StopUpdate {
+    pub(super) fn new(
+        sequence: Option<u32>,
+        location: Option<LocationId>,
+        arrival: Option<Event>,
+        departure: Option<Event>,
+        skipped: bool,
+        no_data: bool,
+    ) -> Self {
+        Self {
+            sequence,
+            location,
+            arrival,
+            departure,
+            skipped,
+            no_data,
+        }
+    }
+
+    /// Whether this update refers to the stop, preferring the sequence over the location.
+    fn refers_to(&self, sequence: Option<u32>, location: &LocationId) -> bool {
+        match (self.sequence, sequence) {
+            (Some(own), Some(sequence)) => own == sequence,
+            _ => self.location.as_ref() == Some(location),
+        }
+    }
+}
+
+/// How a trip deviates from the static timetable.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Change {
+    /// The trip runs with the given delays, which apply to all following stops until the next
+    /// update.
+    Delayed {
+        delay: Option<Duration>,
+        stops: Vec<StopUpdate>,
+    },
+    Canceled,
+    /// The trip is not part of the static timetable, its stops carry absolute times.
+    Added {
+        line: Option<LineId>,
+        direction: Option<Direction>,
+        stops: Vec<StopUpdate>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct TripUpdate {
+    trip: Option<TripId>,
+    start_date: Option<NaiveDate>,
+    start_time: Option<Duration>,
+    change: Change,
+}
+
+/// Arrival and departure time of each stop, given the durations of a trip.
+fn to_stop_times(durations: &[Duration]) -> Vec<(Duration, Duration)> {
+    let mut times = Vec::new();
+    let mut time = Duration::zero();
+    for pair in durations.chunks(2) {
+        let arrival = time + pair[0];
+        time = arrival + pair[1];
+        times.push((arrival, time));
+    }
+    times
+}
+
+/// Durations of a trip, given the arrival and departure time of each stop.
+fn to_durations(stop_times: &[(Duration, Duration)]) -> Vec<Duration> {
+    let mut durations = Vec::new();
+    let mut time = Duration::zero();
+    for &(arrival, departure) in stop_times {
+        durations.push(arrival - time);
+        durations.push(departure - arrival);
+        time = departure;
+    }
+    durations
+}
+
+impl TripUpdate {
+    pub(super) fn new(
+        trip: Option<TripId>,
+        start_date: Option<NaiveDate>,
+        start_time: Option<Duration>,
+        change: Change,
+    ) -> Self {
+        Self {
+            trip,
+            start_date,
+            start_time,
+            change,
+        }
+    }
+
+    pub(super) fn trip(&self) -> Option<&TripId> {
+        self.trip.as_ref()
+    }
+
+    /// Replaces the stop ids of the stop updates by the ids they resolve to.
+    pub(super) fn resolve_stops(&mut self, resolve: &impl Fn(&LocationId) -> Option<LocationId>) {
+        let stops = match &mut self.change {
+            Change::Delayed { stops, .. } | Change::Added { stops, .. } => stops,
+            Change::Canceled => return,
+        };
+        for stop in stops {
+            if let Some(location) = stop.location.as_ref().and_then(resolve) {
+                stop.location = Some(location);
+            }
+        }
+    }
+
+    pub(super) fn is_added(&self) -> bool {
+        matches!(self.change, Change::Added { .. })
+    }
+
+    /// Whether the update refers to a trip starting at the given date and time.
+    pub(super) fn applies_to(&self, date: NaiveDate, start: Option<Duration>) -> bool {
+        self.start_date.unwrap_or(date) == date
+            && match (self.start_time, start) {
+                (Some(start_time), Some(start)) => start_time == start,
+                _ => true,
+            }
+    }
+
+    /// Whether this added trip belongs to one of the lines and runs in the direction.
+    pub(crate) fn is_added_to(&self, lines: &[LineId], direction: Direction) -> bool {
+        match &self.change {
+            Change::Added {
+                line,
+                direction: added_direction,
+                ..
+            } => {
+                line.iter().all(|line| lines.contains(line))
+                    && added_direction.unwrap_or(direction) == direction
+            }
+            _ => false,
+        }
+    }
+
+    /// Adjusts the durations of a scheduled trip, returning `None` if it is canceled.
+    ///
+    /// The stops of the trip are identified by their locations and, if known, their sequence
+    /// numbers. Stops without an update keep the delay of the previous stop.
+    pub(crate) fn apply(
+        &self,
+        locations: &[LocationId],
+        sequences: Option<&[u32]>,
+        durations: &[Duration],
+    ) -> Option<Vec<Duration>> {
+        let (delay, stops) = match &self.change {
+            Change::Delayed { delay, stops } => (*delay, stops),
+            Change::Canceled => return None,
+            Change::Added { .. } => return Some(durations.to_vec()),
+        };
+
+        let mut current = delay.unwrap_or_else(Duration::zero);
+        let mut earliest = Duration::min_value();
+        let mut times = Vec::new();
+        for (i, (arrival, departure)) in to_stop_times(durations).into_iter().enumerate() {
+            let sequence = sequences.map(|sequences| sequences[i]);
+            let update = locations
+                .get(i)
+                .and_then(|location| stops.iter().find(|stop| stop.refers_to(sequence, location)));
+            let (arrival_delay, departure_delay) = match update {
+                Some(update) if update.no_data => (Duration::zero(), Duration::zero()),
+                Some(update) => {
+                    let arrival_delay =
+                        update.arrival.map_or(current, |event| event.delay(arrival));
+                    let departure_delay = if update.skipped {
+                        arrival_delay - (departure - arrival)
+                    } else {
+                        update
+                            .departure
+                            .map_or(arrival_delay, |event| event.delay(departure))
+                    };
+                    (arrival_delay, departure_delay)
+                }
+                None => (current, current),
+            };
+
+            // delays must not let the vehicle leave a stop before arriving there
+            let actual_arrival = (arrival + arrival_delay).max(earliest);
+            let actual_departure = (departure + departure_delay).max(actual_arrival);
+            times.push((actual_arrival, actual_departure));
+            current = actual_departure - departure;
+            earliest = actual_departure;
+        }
+        Some(to_durations(&times))
+    }
+
+    /// Durations of an added trip which calls at exactly the given locations.
+    pub(crate) fn added_durations(&self, locations: &[LocationId]) -> Option<Vec<Duration>> {
+        let stops = match &self.change {
+            Change::Added { stops, .. } => stops,
+            _ => return None,
+        };
+        if stops.len() != locations.len() {
+            return None;
+        }
+
+        let mut times = Vec::new();
+        for (stop, location) in stops.iter().zip(locations) {
+            if stop.location.as_ref() != Some(location) {
+                return None;
+            }
+            let arrival = stop.arrival.or(stop.departure)?.time()?;
+            let departure = stop.departure.or(stop.arrival)?.time()?;
+            times.push((arrival, departure.max(arrival)));
+        }
+        Some(to_durations(&times))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_utils::{time, times};
+
+    fn locations() -> Vec<LocationId> {
+        vec![
+            "oranienburger_tor".into(),
+            "friedrichstr".into(),
+            "universitaetsstr".into(),
+            "am_kupfergraben".into(),
+        ]
+    }
+
+    fn durations() -> Vec<Duration> {
+        times!(Duration; 9:02:00, 0:00, 2:00, 0:30, 2:00, 0:00, 1:00, 0:00)
+    }
+
+    fn stop(location: &str, arrival: Option<Event>, departure: Option<Event>) -> StopUpdate {
+        StopUpdate::new(
+            None,
+            Some(location.into()),
+            arrival,
+            departure,
+            false,
+            false,
+        )
+    }
+
+    fn delayed(delay: Option<Duration>, stops: Vec<StopUpdate>) -> TripUpdate {
+        TripUpdate::new(
+            Some("1".into()),
+            None,
+            None,
+            Change::Delayed { delay, stops },
+        )
+    }
+
+    #[test]
+    fn test_propagate_delay() {
+        let update = delayed(
+            None,
+            vec![stop(
+                "friedrichstr",
+                Some(Event::Delay(Duration::seconds(60))),
+                None,
+            )],
+        );
+        assert_eq!(
+            update.apply(&locations(), None, &durations()),
+            Some(times!(Duration; 9:02:00, 0:00, 3:00, 0:30, 2:00, 0:00, 1:00, 0:00))
+        );
+    }
+
+    #[test]
+    fn test_trip_delay() {
+        let update = delayed(Some(Duration::seconds(120)), Vec::new());
+        assert_eq!(
+            update.apply(&locations(), None, &durations()),
+            Some(times!(Duration; 9:04:00, 0:00, 2:00, 0:30, 2:00, 0:00, 1:00, 0:00))
+        );
+    }
+
+    #[test]
+    fn test_match_stop_sequence() {
+        let update = delayed(
+            None,
+            vec![StopUpdate::new(
+                Some(20),
+                Some("universitaetsstr".into()),
+                Some(Event::Delay(Duration::seconds(60))),
+                None,
+                false,
+                false,
+            )],
+        );
+        assert_eq!(
+            update.apply(&locations(), Some(&[10, 20, 30, 40]), &durations()),
+            Some(times!(Duration; 9:02:00, 0:00, 3:00, 0:30, 2:00, 0:00, 1:00, 0:00))
+        );
+    }
+
+    #[test]
+    fn test_skipped_stop() {
+        let update = delayed(
+            None,
+            vec![StopUpdate::new(
+                None,
+                Some("friedrichstr".into()),
+                None,
+                None,
+                true,
+                false,
+            )],
+        );
+        assert_eq!(
+            update.apply(&locations(), None, &durations()),
+            Some(times!(Duration; 9:02:00, 0:00, 2:00, 0:00, 2:00, 0:00, 1:00, 0:00))
+        );
+    }
+
+    #[test]
+    fn test_early_departure_waits_for_arrival() {
+        let update = delayed(
+            None,
+            vec![stop(
+                "friedrichstr",
+                Some(Event::Delay(Duration::seconds(60))),
+                Some(Event::Delay(Duration::seconds(-60))),
+            )],
+        );
+        assert_eq!(
+            update.apply(&locations(), None, &durations()),
+            Some(times!(Duration; 9:02:00, 0:00, 3:00, 0:00, 2:00, 0:00, 1:00, 0:00))
+        );
+    }
+
+    #[test]
+    fn test_canceled() {
+        let update = TripUpdate::new(Some("1".into()), None, None, Change::Canceled);
+        assert_eq!(update.apply(&locations(), None, &durations()), None);
+    }
+
+    #[test]
+    fn test_applies_to() {
+        let date = NaiveDate::from_ymd(2019, 1, 7);
+        let update = TripUpdate::new(
+            Some("1".into()),
+            Some(date),
+            Some(time!(Duration; 9:02:00)),
+            Change::Canceled,
+        );
+        assert!(update.applies_to(date, Some(time!(Duration; 9:02:00))));
+        assert!(!update.applies_to(date, Some(time!(Duration; 9:12:00))));
+        assert!(!update.applies_to(date.succ(), Some(time!(Duration; 9:02:00))));
+    }
+
+    #[test]
+    fn test_added_durations() {
+        let time = |time| Some(Event::Time(time));
+        let update = TripUpdate::new(
+            None,
+            None,
+            None,
+            Change::Added {
+                line: Some("4".into()),
+                direction: Some(Direction::Upstream),
+                stops: vec![
+                    stop("oranienburger_tor", None, time(time!(Duration; 10:02:00))),
+                    stop("friedrichstr", time(time!(Duration; 10:04:00)), None),
+                    stop("universitaetsstr", time(time!(Duration; 10:06:00)), None),
+                    stop("am_kupfergraben", time(time!(Duration; 10:07:00)), None),
+                ],
+            },
+        );
+        assert!(update.is_added_to(&["4".into()], Direction::Upstream));
+        assert!(!update.is_added_to(&["4".into()], Direction::Downstream));
+        assert!(!update.is_added_to(&["3".into()], Direction::Upstream));
+        assert_eq!(
+            update.added_durations(&locations()),
+            Some(times!(Duration; 10:02:00, 0:00, 2:00, 0:00, 2:00, 0:00, 1:00, 0:00))
+        );
+        assert_eq!(update.added_durations(&locations()[..3]), None);
+    }
+}
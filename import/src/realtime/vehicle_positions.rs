@@ -0,0 +1,305 @@
+use chrono::{Duration, FixedOffset, NaiveDate};
+use prost::Message;
+
+use super::feed::{
+    self, FeedEntity, FeedHeader, FeedMessage, OccupancyStatus, TripDescriptor, VehicleDescriptor,
+    VehicleStopStatus,
+};
+use crate::coord::{project_back, transform_back};
+use simulation::{Dataset, Direction, Line, Node, StopStatus, Train};
+
+/// Writes the active trains of a simulation as GTFS Realtime `VehiclePositions`.
+///
+/// The simulation does not keep the GTFS ids of routes and stops, so `route_id` and `stop_id`
+/// are left unset and the vehicle label names the line and the current stop instead.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VehiclePositions {
+    date: NaiveDate,
+    utc_offset: FixedOffset,
+}
+
+/// Number of stops up to and including the node, in the direction of travel.
+fn stop_sequence(nodes: &[Node], direction: Direction, at: usize) -> u32 {
+    let passed = match direction {
+        Direction::Upstream => &nodes[..=at],
+        Direction::Downstream => &nodes[at..],
+    };
+    passed
+        .iter()
+        .filter(|node| node.is_stop() && node.allows(direction))
+        .count() as u32
+}
+
+/// Occupancy of a vehicle with the passengers on board relative to its capacity.
+fn occupancy_status(load: f32) -> OccupancyStatus {
+    if load <= 0.0 {
+        OccupancyStatus::Empty
+    } else if load < 0.3 {
+        OccupancyStatus::ManySeatsAvailable
+    } else if load < 0.5 {
+        OccupancyStatus::FewSeatsAvailable
+    } else if load < 0.8 {
+        OccupancyStatus::StandingRoomOnly
+    } else if load < 1.0 {
+        OccupancyStatus::CrushedStandingRoomOnly
+    } else {
+        OccupancyStatus::Full
+    }
+}
+
+fn format_time(time: u32) -> String {
+    format!("{:02}:{:02}:{:02}", time / 3600, time / 60 % 60, time % 60)
+}
+
+impl VehiclePositions {
+    /// Snapshots of a simulation whose first stored day is at the given date, with timestamps in
+    /// the UTC offset of the timetable.
+    pub fn new(date: NaiveDate, utc_offset: FixedOffset) -> Self {
+        Self { date, utc_offset }
+    }
+
+    fn service_date(&self, day: usize) -> NaiveDate {
+        self.date + Duration::days(day as i64)
+    }
+
+    fn timestamp(&self, dataset: &Dataset) -> u64 {
+        let midnight = self.service_date(dataset.day()).and_hms(0, 0, 0)
+            - Duration::seconds(self.utc_offset.local_minus_utc().into());
+        (midnight + Duration::seconds(dataset.time().into())).timestamp() as u64
+    }
+
+    fn vehicle_position(
+        &self,
+        dataset: &Dataset,
+        line: &Line,
+        train: &Train,
+        load: Option<u32>,
+        id: String,
+    ) -> Option<feed::VehiclePosition> {
+        let (position, orientation) = train.position(line.nodes())?;
+        let (latitude, longitude) = project_back(transform_back(position));
+        let bearing = orientation.x.atan2(-orientation.y).to_degrees();
+
+        let (status, at) = match train.stop_status(line.nodes()) {
+            Some(StopStatus::StoppedAt(at)) => (VehicleStopStatus::StoppedAt, Some(at)),
+            Some(StopStatus::InTransitTo(at)) => (VehicleStopStatus::InTransitTo, Some(at)),
+            None => (VehicleStopStatus::InTransitTo, None),
+        };
+        let stop = at.and_then(|at| line.nodes()[at].station());
+        let label = match (status, stop) {
+            (VehicleStopStatus::StoppedAt, Some(stop)) => {
+                format!("{} at {}", line.name(), stop.name())
+            }
+            (_, Some(stop)) => format!("{} to {}", line.name(), stop.name()),
+            (_, None) => line.name().to_string(),
+        };
+        let start_date = if train.is_from_previous_day() {
+            self.service_date(dataset.day()).pred()
+        } else {
+            self.service_date(dataset.day())
+        };
+
+        Some(feed::VehiclePosition {
+            trip: Some(TripDescriptor {
+                direction_id: Some(match train.direction() {
+                    Direction::Upstream => 0,
+                    Direction::Downstream => 1,
+                }),
+                start_time: Some(format_time(train.start_time())),
+                start_date: Some(start_date.format("%Y%m%d").to_string()),
+                ..TripDescriptor::default()
+            }),
+            vehicle: Some(VehicleDescriptor {
+                id: Some(id),
+                label: Some(label),
+            }),
+            position: Some(feed::Position {
+                latitude: latitude as f32,
+                longitude: longitude as f32,
+                bearing: Some((bearing + 360.0) % 360.0),
+            }),
+            current_stop_sequence: at.map(|at| stop_sequence(line.nodes(), train.direction(), at)),
+            stop_id: None,
+            current_status: at.map(|_| status as i32),
+            timestamp: Some(self.timestamp(dataset)),
+            occupancy_status: load
+                .map(|load| occupancy_status(load as f32 / line.kind().capacity() as f32) as i32),
+            occupancy_percentage: load.map(|load| load * 100 / line.kind().capacity()),
+        })
+    }
+
+    /// Encodes a `FeedMessage` with the position of every active train at the current time of
+    /// the simulation.
+    ///
+    /// Vehicles are identified by the index of their line and their index on the line, so ids
+    /// stay the same between snapshots of one service day. Their occupancy is included if the
+    /// simulation has passengers.
+    pub fn encode(&self, dataset: &Dataset) -> Vec<u8> {
+        let mut entity = Vec::new();
+        for (line_index, line) in dataset.lines().iter().enumerate() {
+            for (train_index, train) in line.trains().iter().enumerate() {
+                let id = format!("{}-{}", line_index, train_index);
+                let load = dataset
+                    .passengers()
+                    .map(|passengers| passengers.load(line_index, train_index));
+                if let Some(vehicle) = self.vehicle_position(dataset, line, train, load, id.clone())
+                {
+                    entity.push(FeedEntity {
+                        id,
+                        is_deleted: None,
+                        trip_update: None,
+                        vehicle: Some(vehicle),
+                        alert: None,
+                    });
+                }
+            }
+        }
+
+        let message = FeedMessage {
+            header: FeedHeader {
+                gtfs_realtime_version: "2.0".to_string(),
+                timestamp: Some(self.timestamp(dataset)),
+            },
+            entity,
+        };
+        let mut bytes = Vec::new();
+        message.encode(&mut bytes).unwrap();
+        bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+
+    use super::*;
+    use simulation::fixtures::datasets;
+    use simulation::line::Kind;
+    use simulation::passengers::Flow;
+    use simulation::{Color, Demand};
+    use test_utils::{time, times};
+
+    fn vehicle_positions() -> VehiclePositions {
+        VehiclePositions::new(NaiveDate::from_ymd(2019, 1, 7), FixedOffset::east(3600))
+    }
+
+    fn decode(bytes: &[u8]) -> FeedMessage {
+        FeedMessage::decode(bytes).unwrap()
+    }
+
+    #[test]
+    fn test_format_time() {
+        assert_eq!(format_time(time!(9:02:05)), "09:02:05");
+        assert_eq!(format_time(time!(25:30:00)), "25:30:00");
+    }
+
+    #[test]
+    fn test_no_active_trains() {
+        let dataset = datasets::tram_12();
+        let message = decode(&vehicle_positions().encode(&dataset));
+        // 2019-01-07 00:00:00 +01:00
+        assert_eq!(message.header.timestamp, Some(1_546_815_600));
+        assert!(message.entity.is_empty());
+    }
+
+    #[test]
+    fn test_vehicle_positions() {
+        let mut dataset = datasets::tram_12();
+        dataset.update(time!(9:02:47));
+        let message = decode(&vehicle_positions().encode(&dataset));
+        assert_eq!(message.entity.len(), 1);
+
+        let entity = &message.entity[0];
+        assert_eq!(entity.id, "0-0");
+        let vehicle = entity.vehicle.as_ref().unwrap();
+        let trip = vehicle.trip.as_ref().unwrap();
+        assert_eq!(trip.route_id, None);
+        assert_eq!(trip.direction_id, Some(0));
+        assert_eq!(trip.start_time.as_deref(), Some("09:01:40"));
+        assert_eq!(trip.start_date.as_deref(), Some("20190107"));
+        assert_eq!(vehicle.stop_id, None);
+        assert_eq!(
+            vehicle.vehicle.as_ref().unwrap().label.as_deref(),
+            Some("12 to Friedrichstr.")
+        );
+        assert_eq!(vehicle.current_stop_sequence, Some(2));
+        assert_eq!(
+            vehicle.current_status,
+            Some(VehicleStopStatus::InTransitTo as i32)
+        );
+        assert_eq!(vehicle.timestamp, Some(1_546_848_167));
+        assert_eq!(vehicle.occupancy_status, None);
+
+        let position = vehicle.position.as_ref().unwrap();
+        assert_relative_eq!(position.latitude, 52.523, epsilon = 0.001);
+        assert_relative_eq!(position.longitude, 13.388, epsilon = 0.001);
+        // southwards towards Friedrichstr.
+        assert_relative_eq!(position.bearing.unwrap(), 181.2, epsilon = 0.1);
+    }
+
+    #[test]
+    fn test_occupancy() {
+        let mut dataset = datasets::tram_12();
+        dataset.set_demand(&Demand::new(vec![Flow::new(
+            "Oranienburger Tor".to_string(),
+            "Am Kupfergraben".to_string(),
+            6.0,
+        )]));
+        dataset.update(time!(9:01:45));
+        dataset.update(time!(0:01:02));
+        let message = decode(&vehicle_positions().encode(&dataset));
+        let vehicle = message.entity[0].vehicle.as_ref().unwrap();
+        assert_eq!(
+            vehicle.occupancy_status,
+            Some(OccupancyStatus::FewSeatsAvailable as i32)
+        );
+        assert_eq!(vehicle.occupancy_percentage, Some(30));
+    }
+
+    #[test]
+    fn test_occupancy_status() {
+        assert_eq!(occupancy_status(0.0), OccupancyStatus::Empty);
+        assert_eq!(occupancy_status(0.1), OccupancyStatus::ManySeatsAvailable);
+        assert_eq!(occupancy_status(0.6), OccupancyStatus::StandingRoomOnly);
+        assert_eq!(occupancy_status(1.2), OccupancyStatus::Full);
+    }
+
+    #[test]
+    fn test_train_from_previous_day() {
+        let train = Train::new(
+            Kind::Tram,
+            Direction::Upstream,
+            0b1,
+            times![23:58:00, 0:20, 0:27, 1:21, 0:27, 0:20, 0:19, 0:11, 0:12, 0:12, 0:12,
+                0:20, 1:00, 0:20],
+        );
+        let line = Line::new(
+            "12".to_string(),
+            Kind::Tram.color(),
+            Color::new(0, 0, 0),
+            Kind::Tram,
+            simulation::fixtures::nodes::tram_12(),
+            vec![train],
+        );
+        let mut dataset = Dataset::new(Vec::new(), vec![line], Vec::new(), Vec::new(), 1);
+        dataset.update(time!(0:01:00));
+        let message = decode(&vehicle_positions().encode(&dataset));
+        assert_eq!(message.entity.len(), 1);
+
+        let vehicle = message.entity[0].vehicle.as_ref().unwrap();
+        let trip = vehicle.trip.as_ref().unwrap();
+        assert_eq!(trip.start_time.as_deref(), Some("23:58:00"));
+        assert_eq!(trip.start_date.as_deref(), Some("20190106"));
+        // 2019-01-07 00:01:00 +01:00
+        assert_eq!(vehicle.timestamp, Some(1_546_815_660));
+    }
+
+    #[test]
+    fn test_stop_sequence_downstream() {
+        let nodes = simulation::fixtures::nodes::tram_12();
+        assert_eq!(stop_sequence(&nodes, Direction::Downstream, 16), 1);
+        assert_eq!(stop_sequence(&nodes, Direction::Downstream, 12), 2);
+        assert_eq!(stop_sequence(&nodes, Direction::Downstream, 0), 4);
+        assert_eq!(stop_sequence(&nodes, Direction::Upstream, 9), 3);
+    }
+}
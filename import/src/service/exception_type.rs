@@ -3,7 +3,7 @@ use std::fmt;
 use serde::de::{Deserialize, Deserializer, Error as DeserializeError, Visitor};
 
 #[derive(Debug, PartialEq, Eq)]
-pub(super) enum ExceptionType {
+pub(crate) enum ExceptionType {
     Added,
     Removed,
 }
@@ -1,16 +1,16 @@
 use std::collections::HashMap;
-use std::error::Error;
 use std::rc::Rc;
 
 use super::{Service, ServiceExceptionRecord, ServiceId, ServiceRecord};
 use crate::utils::{Action, Dataset};
+use crate::ImportError;
 
 pub(crate) struct Importer;
 
 impl Importer {
     pub(crate) fn import(
         dataset: &mut impl Dataset,
-    ) -> Result<HashMap<ServiceId, Rc<Service>>, Box<dyn Error>> {
+    ) -> Result<HashMap<ServiceId, Rc<Service>>, ImportError> {
         let mut services = Self::import_services(dataset)?;
         Self::add_service_exceptions(dataset, &mut services)?;
 
@@ -24,12 +24,12 @@ impl Importer {
 
     fn import_services(
         dataset: &mut impl Dataset,
-    ) -> Result<HashMap<ServiceId, Service>, Box<dyn Error>> {
+    ) -> Result<HashMap<ServiceId, Service>, ImportError> {
         let mut services = HashMap::new();
 
         let action = Action::start("Importing services");
         for result in action.read_csv(dataset, "calendar.txt")? {
-            let record: ServiceRecord = result?;
+            let (_, record): (_, ServiceRecord) = result?;
             record.import(&mut services);
         }
         action.complete(&format!("Imported {} services", services.len()));
@@ -39,11 +39,11 @@ impl Importer {
     fn add_service_exceptions(
         dataset: &mut impl Dataset,
         services: &mut HashMap<ServiceId, Service>,
-    ) -> Result<(), Box<dyn Error>> {
+    ) -> Result<(), ImportError> {
         let action = Action::start("Importing service exceptions");
         for result in action.read_csv(dataset, "calendar_dates.txt")? {
-            let record: ServiceExceptionRecord = result?;
-            record.apply_to(services);
+            let (line, record): (_, ServiceExceptionRecord) = result?;
+            record.apply_to(line, services)?;
         }
         action.complete("Imported service exceptions");
         Ok(())
@@ -82,4 +82,22 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn test_exception_for_unknown_service() {
+        let mut dataset = dataset!(
+            calendar:
+                service_id, monday, tuesday, wednesday, thursday, friday, saturday, sunday, start_date, end_date;
+                1,          1,      1,       1,         1,        1,      0,        0,      20190101,   20191231
+            calendar_dates:
+                service_id, date,     exception_type;
+                1,          20190105, 1;
+                2,          20190107, 2
+        );
+
+        assert_eq!(
+            Importer::import(&mut dataset).unwrap_err().to_string(),
+            "calendar_dates.txt, line 3: service_id 2 not found"
+        );
+    }
 }
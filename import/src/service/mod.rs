@@ -8,8 +8,8 @@ pub(crate) mod fixtures {
     pub(crate) use super::service::fixtures as services;
 }
 
-use exception_type::ExceptionType;
 use record::{ServiceExceptionRecord, ServiceRecord};
 
+pub(crate) use exception_type::ExceptionType;
 pub(crate) use importer::Importer;
 pub(crate) use service::{Service, ServiceId};
@@ -6,6 +6,7 @@ use chrono::NaiveDate;
 
 use super::{ExceptionType, Service, ServiceId};
 use crate::deserialize;
+use crate::ImportError;
 
 #[derive(Debug, Deserialize)]
 pub(super) struct ServiceRecord {
@@ -61,12 +62,27 @@ pub(super) struct ServiceExceptionRecord {
 }
 
 impl ServiceExceptionRecord {
-    pub(super) fn apply_to(self, services: &mut HashMap<ServiceId, Service>) {
-        let service = services.get_mut(&self.service_id).unwrap();
+    pub(super) fn apply_to(
+        self,
+        line: u64,
+        services: &mut HashMap<ServiceId, Service>,
+    ) -> Result<(), ImportError> {
+        let service = match services.get_mut(&self.service_id) {
+            Some(service) => service,
+            None => {
+                return Err(ImportError::DanglingReference {
+                    file: "calendar_dates.txt",
+                    line,
+                    field: "service_id",
+                    id: self.service_id.to_string(),
+                })
+            }
+        };
         match self.exception_type {
             ExceptionType::Added => service.add_date(self.date),
             ExceptionType::Removed => service.remove_date(self.date),
         }
+        Ok(())
     }
 }
 
@@ -119,7 +135,7 @@ mod tests {
             date: NaiveDate::from_ymd(2019, 1, 5),
             exception_type: ExceptionType::Added,
         };
-        record.apply_to(&mut services);
+        record.apply_to(2, &mut services).unwrap();
         assert!(services[&"1".into()].available_at(NaiveDate::from_ymd(2019, 1, 5)));
     }
 
@@ -131,7 +147,7 @@ mod tests {
             date: NaiveDate::from_ymd(2019, 12, 24),
             exception_type: ExceptionType::Removed,
         };
-        record.apply_to(&mut services);
+        record.apply_to(2, &mut services).unwrap();
         assert!(!services[&"1".into()].available_at(NaiveDate::from_ymd(2019, 12, 24)));
     }
 }
@@ -0,0 +1,111 @@
+use std::error::Error;
+use std::fmt;
+use std::rc::Rc;
+use std::str::FromStr;
+
+use crate::location::Location;
+use crate::shape::Shape;
+
+/// Maximal distance in meters between two points of a densified shape.
+const DENSIFIED_SPACING: f64 = 50.0;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Mode {
+    Straight,
+    Densified,
+}
+
+impl Mode {
+    /// Synthesizes a shape running through the stop locations in order.
+    ///
+    /// This is used for trips whose shape is not part of the dataset.
+    pub(crate) fn synthesize(self, locations: &[Rc<Location>]) -> Shape {
+        let mut points = Vec::new();
+        let mut stops = Vec::with_capacity(locations.len());
+        for location in locations {
+            let position = location.position();
+            if let (Mode::Densified, Some(&last)) = (self, points.last()) {
+                let steps = (na::distance(&last, &position) / DENSIFIED_SPACING).ceil() as usize;
+                for step in 1..steps {
+                    points.push(last + (position - last) * (step as f64 / steps as f64));
+                }
+            }
+            stops.push(points.len());
+            points.push(position);
+        }
+        Shape::synthesized(points, stops)
+    }
+
+    pub fn variants() -> &'static [&'static str] {
+        &["straight", "densified"]
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct InvalidModeError(String);
+
+impl fmt::Display for InvalidModeError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "fallback mode '{}' not found", self.0)
+    }
+}
+
+impl Error for InvalidModeError {}
+
+impl FromStr for Mode {
+    type Err = InvalidModeError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "straight" => Ok(Self::Straight),
+            "densified" => Ok(Self::Densified),
+            _ => Err(InvalidModeError(value.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::stop_locations;
+
+    #[test]
+    fn test_straight() {
+        let locations = stop_locations::tram_12::oranienburger_tor_am_kupfergraben();
+        let shape = Mode::Straight.synthesize(&locations);
+        assert!(shape.is_synthesized());
+        assert_eq!(shape.synthesized_stops(), Some(&[0, 1, 2, 3][..]));
+        assert_eq!(
+            shape.into_iter().collect::<Vec<_>>(),
+            locations
+                .iter()
+                .map(|location| location.position())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_densified() {
+        let locations = stop_locations::tram_12::oranienburger_tor_am_kupfergraben();
+        let shape = Mode::Densified.synthesize(&locations);
+        let stops = shape.synthesized_stops().unwrap().to_vec();
+        let points = shape.into_iter().collect::<Vec<_>>();
+        for (location, &stop) in locations.iter().zip(&stops) {
+            assert_eq!(points[stop], location.position());
+        }
+        assert!(points.len() > locations.len());
+        for (a, b) in points.iter().zip(&points[1..]) {
+            assert!(na::distance(a, b) <= DENSIFIED_SPACING);
+        }
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!("straight".parse::<Mode>().unwrap(), Mode::Straight);
+        assert_eq!("densified".parse::<Mode>().unwrap(), Mode::Densified);
+        assert_eq!(
+            "curved".parse::<Mode>().unwrap_err().to_string(),
+            "fallback mode 'curved' not found"
+        );
+    }
+}
@@ -1,10 +1,10 @@
 use std::collections::HashMap;
-use std::error::Error;
 
 use super::smoother::Mode;
 use super::{Shape, ShapeId, ShapeRecord};
 use crate::utils::Action;
 use crate::utils::Dataset;
+use crate::ImportError;
 
 pub(crate) struct Importer;
 
@@ -12,12 +12,12 @@ impl Importer {
     pub(crate) fn import(
         dataset: &mut impl Dataset,
         mode: Mode,
-    ) -> Result<HashMap<ShapeId, Shape>, Box<dyn Error>> {
+    ) -> Result<HashMap<ShapeId, Shape>, ImportError> {
         let mut shapes = HashMap::new();
 
         let action = Action::start("Importing shapes");
         for result in action.read_csv(dataset, "shapes.txt")? {
-            let record: ShapeRecord = result?;
+            let (_, record): (_, ShapeRecord) = result?;
             record.import(&mut shapes);
         }
         action.complete(&format!("Imported {} shapes", shapes.len()));
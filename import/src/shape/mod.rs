@@ -1,3 +1,4 @@
+mod fallback;
 mod importer;
 mod record;
 mod shape;
@@ -10,6 +11,7 @@ pub(crate) mod fixtures {
 
 use record::ShapeRecord;
 
+pub use fallback::Mode as FallbackMode;
 pub(crate) use importer::Importer;
 pub(crate) use shape::{Shape, ShapeId};
 pub use smoother::Mode as SmoothMode;
@@ -10,6 +10,8 @@ pub(super) struct ShapeRecord {
     shape_id: ShapeId,
     shape_pt_lat: f64,
     shape_pt_lon: f64,
+    #[serde(default)]
+    shape_dist_traveled: Option<f64>,
 }
 
 impl ShapeRecord {
@@ -18,7 +20,7 @@ impl ShapeRecord {
         shapes
             .entry(self.shape_id)
             .or_insert_with(Shape::new)
-            .add(position)
+            .add(position, self.shape_dist_traveled)
     }
 }
 
@@ -34,6 +36,7 @@ mod tests {
             shape_id: "1".into(),
             shape_pt_lat: 52.526,
             shape_pt_lon: 13.369,
+            shape_dist_traveled: None,
         };
         record.import(&mut shapes);
         assert_eq!(
@@ -43,4 +46,28 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn test_import_distances() {
+        let mut shapes = HashMap::new();
+        for (lat, distance) in [(52.526, Some(0.0)), (52.527, Some(111.2))].iter() {
+            let record = ShapeRecord {
+                shape_id: "1".into(),
+                shape_pt_lat: *lat,
+                shape_pt_lon: 13.369,
+                shape_dist_traveled: *distance,
+            };
+            record.import(&mut shapes);
+        }
+        assert_eq!(shapes[&"1".into()].distances(), Some(&[0.0, 111.2][..]));
+
+        let record = ShapeRecord {
+            shape_id: "1".into(),
+            shape_pt_lat: 52.528,
+            shape_pt_lon: 13.369,
+            shape_dist_traveled: None,
+        };
+        record.import(&mut shapes);
+        assert_eq!(shapes[&"1".into()].distances(), None);
+    }
 }
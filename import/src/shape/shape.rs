@@ -9,15 +9,64 @@ create_id_type!(ShapeId);
 #[derive(PartialEq, Clone)]
 pub(crate) struct Shape {
     points: Vec<Point>,
+    /// Distances travelled along the shape up to each point, known if the dataset gives them for
+    /// every point.
+    distances: Option<Vec<f64>>,
+    /// Indices of the points the stops lie on, known if the shape was synthesized from them.
+    synthesized_stops: Option<Vec<usize>>,
 }
 
 impl Shape {
     pub(super) fn new() -> Self {
-        Self { points: Vec::new() }
+        Self {
+            points: Vec::new(),
+            distances: Some(Vec::new()),
+            synthesized_stops: None,
+        }
+    }
+
+    pub(super) fn measured(points: Vec<Point>, distances: Option<Vec<f64>>) -> Self {
+        Self {
+            points,
+            distances,
+            synthesized_stops: None,
+        }
+    }
+
+    pub(super) fn synthesized(points: Vec<Point>, stops: Vec<usize>) -> Self {
+        Self {
+            points,
+            distances: None,
+            synthesized_stops: Some(stops),
+        }
+    }
+
+    pub(crate) fn is_synthesized(&self) -> bool {
+        self.synthesized_stops.is_some()
     }
 
-    pub(super) fn add(&mut self, position: Point) {
+    pub(crate) fn synthesized_stops(&self) -> Option<&[usize]> {
+        self.synthesized_stops.as_deref()
+    }
+
+    pub(crate) fn points(&self) -> &[Point] {
+        &self.points
+    }
+
+    pub(crate) fn distances(&self) -> Option<&[f64]> {
+        self.distances.as_deref()
+    }
+
+    pub(super) fn add(&mut self, position: Point, distance: Option<f64>) {
         self.points.push(position);
+        match (&mut self.distances, distance) {
+            (Some(distances), Some(distance)) => distances.push(distance),
+            _ => self.distances = None,
+        }
+    }
+
+    pub(super) fn into_parts(self) -> (Vec<Point>, Option<Vec<f64>>) {
+        (self.points, self.distances)
     }
 
     pub(crate) fn iter_count(&self, count: usize) -> impl Iterator<Item = Point> + '_ {
@@ -30,6 +79,12 @@ impl Shape {
             .copied()
     }
 
+    #[cfg(test)]
+    pub(crate) fn with_distances(mut self, distances: Vec<f64>) -> Self {
+        self.distances = Some(distances);
+        self
+    }
+
     #[cfg(test)]
     pub(in crate::shape) fn reversed(mut self) -> Self {
         self.points.reverse();
@@ -39,7 +94,11 @@ impl Shape {
 
 impl From<Vec<Point>> for Shape {
     fn from(value: Vec<Point>) -> Self {
-        Self { points: value }
+        Self {
+            points: value,
+            distances: None,
+            synthesized_stops: None,
+        }
     }
 }
 
@@ -84,6 +143,8 @@ pub(crate) mod fixtures {
                         pub(crate) fn $shape() -> Shape {
                             Shape {
                                 points: vec![$( project($lat, $lon) ),*],
+                                distances: None,
+                                synthesized_stops: None,
                             }
                         }
                     )*
@@ -1,5 +1,6 @@
 use std::error::Error;
 use std::fmt;
+use std::ops::RangeInclusive;
 use std::str::FromStr;
 
 use crate::coord::Point;
@@ -14,9 +15,10 @@ pub enum Mode {
 
 impl Mode {
     pub(super) fn smooth(self, shape: Shape) -> Shape {
-        let mut smoother = Smoother::new(self);
-        for position in shape {
-            smoother.add(position);
+        let (points, distances) = shape.into_parts();
+        let mut smoother = Smoother::new(self, distances.is_some());
+        for (i, position) in points.into_iter().enumerate() {
+            smoother.add(position, distances.as_ref().map(|distances| distances[i]));
         }
         smoother.finish()
     }
@@ -26,6 +28,17 @@ impl Mode {
     }
 }
 
+impl fmt::Display for Mode {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Self::Off => "off",
+            Self::Deduplicate => "deduplicate",
+            Self::Full => "full",
+        };
+        formatter.write_str(name)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct InvalidModeError(String);
 
@@ -54,22 +67,32 @@ impl FromStr for Mode {
 struct Smoother {
     mode: Mode,
     points: Vec<Point>,
+    /// Distances along the shape, which stay in place when a reversed part is corrected.
+    distances: Option<Vec<f64>>,
     last_sharp_turn: Option<usize>,
 }
 
 impl Smoother {
-    fn new(mode: Mode) -> Self {
+    fn new(mode: Mode, measured: bool) -> Self {
         Self {
             mode,
             points: Vec::new(),
+            distances: if measured { Some(Vec::new()) } else { None },
             last_sharp_turn: None,
         }
     }
 
+    fn remove(&mut self, range: RangeInclusive<usize>) {
+        self.points.drain(range.clone());
+        if let Some(distances) = &mut self.distances {
+            distances.drain(range);
+        }
+    }
+
     fn dedup(&mut self) -> bool {
         let len = self.points.len();
         if len >= 2 && self.points[len - 2] == self.points[len - 1] {
-            self.points.pop();
+            self.remove(len - 1..=len - 1);
             return true;
         }
         false
@@ -98,9 +121,9 @@ impl Smoother {
                 && angles[1] > angles[2]
             {
                 if neighborhood[1] == neighborhood[3] {
-                    self.points.drain(at..=at + 1);
+                    self.remove(at..=at + 1);
                 } else {
-                    self.points.remove(at);
+                    self.remove(at..=at);
                 }
                 self.last_sharp_turn = None;
                 return true;
@@ -144,8 +167,11 @@ impl Smoother {
         false
     }
 
-    fn add(&mut self, point: Point) {
+    fn add(&mut self, point: Point, distance: Option<f64>) {
         self.points.push(point);
+        if let (Some(distances), Some(distance)) = (&mut self.distances, distance) {
+            distances.push(distance);
+        }
 
         match self.mode {
             Mode::Off => {}
@@ -165,7 +191,7 @@ impl Smoother {
     fn finish(mut self) -> Shape {
         self.correct_reversed_part(self.points.len().saturating_sub(3));
         self.correct_reversed_part(self.points.len().saturating_sub(2));
-        Shape::from(self.points)
+        Shape::measured(self.points, self.distances)
     }
 }
 
@@ -356,4 +382,15 @@ mod tests {
             ],
         },
     }
+
+    #[test]
+    fn test_keeps_distances() {
+        let shape =
+            fixtures::schoenholz::unprocessed().with_distances((0..8).map(f64::from).collect());
+        let smoothed = super::Mode::Deduplicate.smooth(shape);
+        assert_eq!(
+            smoothed.distances(),
+            Some(&[0.0, 1.0, 2.0, 3.0, 6.0, 7.0][..])
+        );
+    }
 }
@@ -0,0 +1,94 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use super::{Transfer, TransferRecord};
+use crate::location::{Location, LocationId};
+use crate::utils::{Action, Dataset};
+use crate::ImportError;
+
+pub(crate) struct Importer;
+
+impl Importer {
+    pub(crate) fn import(
+        dataset: &mut impl Dataset,
+        locations: &HashMap<LocationId, Rc<Location>>,
+    ) -> Result<Vec<Transfer>, ImportError> {
+        let mut transfers = Vec::new();
+        if !dataset.contains("transfers.txt") {
+            return Ok(transfers);
+        }
+
+        let action = Action::start("Importing transfers");
+        for result in action.read_csv(dataset, "transfers.txt")? {
+            let (line, record): (_, TransferRecord) = result?;
+            transfers.extend(record.import(line, locations)?);
+        }
+        action.complete(&format!("Imported {} transfers", transfers.len()));
+        Ok(transfers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dataset;
+    use crate::fixtures::locations;
+    use simulation::transfer::Kind;
+
+    #[test]
+    fn test_from_csv() {
+        let mut dataset = dataset!(
+            transfers:
+                from_stop_id,        to_stop_id,          transfer_type, min_transfer_time;
+                "friedrichstr",      "oranienburger_tor", 2,             240;
+                "oranienburger_tor", "friedrichstr",      3,             ""
+        );
+
+        assert_eq!(
+            Importer::import(&mut dataset, &locations::by_id()).unwrap(),
+            vec![
+                Transfer::new(
+                    Rc::new(locations::friedrichstr()),
+                    Rc::new(locations::oranienburger_tor()),
+                    Kind::MinimumTime,
+                    Some(240),
+                ),
+                Transfer::new(
+                    Rc::new(locations::oranienburger_tor()),
+                    Rc::new(locations::friedrichstr()),
+                    Kind::Forbidden,
+                    None,
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_in_seat_transfers() {
+        let mut dataset = dataset!(
+            transfers:
+                from_stop_id,        to_stop_id,          transfer_type, min_transfer_time;
+                "friedrichstr",      "oranienburger_tor", 4,             "";
+                "",                  "",                  5,             ""
+        );
+
+        assert_eq!(
+            Importer::import(&mut dataset, &locations::by_id()).unwrap(),
+            vec![Transfer::new(
+                Rc::new(locations::friedrichstr()),
+                Rc::new(locations::oranienburger_tor()),
+                Kind::Timed,
+                None,
+            )]
+        );
+    }
+
+    #[test]
+    fn test_missing_file() {
+        let mut dataset = dataset!();
+        assert_eq!(
+            Importer::import(&mut dataset, &locations::by_id()).unwrap(),
+            Vec::new()
+        );
+    }
+}
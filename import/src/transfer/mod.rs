@@ -0,0 +1,8 @@
+mod importer;
+mod record;
+mod transfer;
+
+use record::TransferRecord;
+
+pub(crate) use importer::Importer;
+pub(crate) use transfer::Transfer;
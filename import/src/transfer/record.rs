@@ -0,0 +1,122 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use serde_derive::Deserialize;
+
+use super::Transfer;
+use crate::deserialize;
+use crate::location::{Location, LocationId};
+use crate::ImportError;
+use simulation::transfer::Kind;
+
+#[derive(Debug, Deserialize)]
+pub(super) struct TransferRecord {
+    from_stop_id: Option<LocationId>,
+    to_stop_id: Option<LocationId>,
+    #[serde(
+        rename = "transfer_type",
+        deserialize_with = "deserialize::transfer_kind"
+    )]
+    transfer_kind: Kind,
+    min_transfer_time: Option<u32>,
+}
+
+impl TransferRecord {
+    fn location(
+        locations: &HashMap<LocationId, Rc<Location>>,
+        line: u64,
+        field: &'static str,
+        id: &LocationId,
+    ) -> Result<Rc<Location>, ImportError> {
+        match locations.get(id) {
+            Some(location) => Ok(Rc::clone(location)),
+            None => Err(ImportError::DanglingReference {
+                file: "transfers.txt",
+                line,
+                field,
+                id: id.to_string(),
+            }),
+        }
+    }
+
+    /// Imports the transfer between the stations of the stops.
+    ///
+    /// Returns `None` for transfers within a station and for in-seat transfers between trips
+    /// which leave out the stops, as neither can be stored.
+    pub(super) fn import(
+        self,
+        line: u64,
+        locations: &HashMap<LocationId, Rc<Location>>,
+    ) -> Result<Option<Transfer>, ImportError> {
+        let (from_stop_id, to_stop_id) = match (&self.from_stop_id, &self.to_stop_id) {
+            (Some(from_stop_id), Some(to_stop_id)) => (from_stop_id, to_stop_id),
+            _ => return Ok(None),
+        };
+        let from = Self::location(locations, line, "from_stop_id", from_stop_id)?;
+        let to = Self::location(locations, line, "to_stop_id", to_stop_id)?;
+        if from.id() == to.id() {
+            return Ok(None);
+        }
+        Ok(Some(Transfer::new(
+            from,
+            to,
+            self.transfer_kind,
+            self.min_transfer_time,
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::locations;
+
+    #[test]
+    fn test_import() {
+        let record = TransferRecord {
+            from_stop_id: Some("friedrichstr".into()),
+            to_stop_id: Some("oranienburger_tor".into()),
+            transfer_kind: Kind::MinimumTime,
+            min_transfer_time: Some(240),
+        };
+        assert_eq!(
+            record.import(2, &locations::by_id()).unwrap(),
+            Some(Transfer::new(
+                Rc::new(locations::friedrichstr()),
+                Rc::new(locations::oranienburger_tor()),
+                Kind::MinimumTime,
+                Some(240),
+            ))
+        );
+    }
+
+    #[test]
+    fn test_import_within_station() {
+        let mut locations = locations::by_id();
+        locations.insert("hauptbahnhof_1".into(), Rc::new(locations::hauptbahnhof()));
+        let record = TransferRecord {
+            from_stop_id: Some("hauptbahnhof_1".into()),
+            to_stop_id: Some("hauptbahnhof".into()),
+            transfer_kind: Kind::MinimumTime,
+            min_transfer_time: Some(180),
+        };
+        assert_eq!(record.import(2, &locations).unwrap(), None);
+    }
+
+    #[test]
+    fn test_import_unknown_location() {
+        let record = TransferRecord {
+            from_stop_id: Some("friedrichstr".into()),
+            to_stop_id: Some("unknown".into()),
+            transfer_kind: Kind::Recommended,
+            min_transfer_time: None,
+        };
+        assert_eq!(
+            record
+                .import(2, &locations::by_id())
+                .unwrap_err()
+                .to_string(),
+            "transfers.txt, line 2: to_stop_id unknown not found"
+        );
+    }
+}
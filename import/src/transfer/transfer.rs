@@ -0,0 +1,144 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::location::{Linearizer, Location};
+use simulation::transfer::Kind;
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct Transfer {
+    from: Rc<Location>,
+    to: Rc<Location>,
+    kind: Kind,
+    min_time: Option<u32>,
+}
+
+impl Transfer {
+    pub(crate) fn new(
+        from: Rc<Location>,
+        to: Rc<Location>,
+        kind: Kind,
+        min_time: Option<u32>,
+    ) -> Self {
+        Self {
+            from,
+            to,
+            kind,
+            min_time,
+        }
+    }
+
+    /// Indices of the stored stations, if both are part of the stored dataset and distinct.
+    fn stations(&self, linearizer: &Linearizer) -> Option<(usize, usize)> {
+        let from = linearizer.find(&self.from)?;
+        let to = linearizer.find(&self.to)?;
+        if from == to {
+            return None;
+        }
+        Some((from, to))
+    }
+
+    /// Stores the transfers between distinct stations of the stored dataset.
+    ///
+    /// Transfers of the same kind between the same stored stations, which happens when merged
+    /// stations are stored once, are kept once with the longest minimum time.
+    pub(crate) fn store_all(transfers: &[Self], linearizer: &Linearizer) -> Vec<storage::Transfer> {
+        let mut indices: HashMap<_, usize> = HashMap::new();
+        let mut stored: Vec<(usize, usize, Kind, Option<u32>)> = Vec::new();
+        for transfer in transfers {
+            let (from, to) = match transfer.stations(linearizer) {
+                Some(stations) => stations,
+                None => continue,
+            };
+            match indices.get(&(from, to, transfer.kind)) {
+                Some(&index) => {
+                    let min_time = &mut stored[index].3;
+                    *min_time = (*min_time).max(transfer.min_time);
+                }
+                None => {
+                    indices.insert((from, to, transfer.kind), stored.len());
+                    stored.push((from, to, transfer.kind, transfer.min_time));
+                }
+            }
+        }
+        stored
+            .into_iter()
+            .map(|(from, to, kind, min_time)| storage::Transfer::new(from, to, kind, min_time))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::locations;
+    use test_utils::map;
+
+    fn transfer() -> Transfer {
+        Transfer::new(
+            Rc::new(locations::friedrichstr()),
+            Rc::new(locations::oranienburger_tor()),
+            Kind::MinimumTime,
+            Some(240),
+        )
+    }
+
+    #[test]
+    fn test_store() {
+        let mut linearizer = Linearizer::new();
+        linearizer.retrieve(&Rc::new(locations::oranienburger_tor()));
+        linearizer.retrieve(&Rc::new(locations::friedrichstr()));
+        assert_eq!(
+            Transfer::store_all(&[transfer()], &linearizer),
+            vec![storage::Transfer::new(1, 0, Kind::MinimumTime, Some(240))]
+        );
+    }
+
+    #[test]
+    fn test_store_with_unused_station() {
+        let mut linearizer = Linearizer::new();
+        linearizer.retrieve(&Rc::new(locations::friedrichstr()));
+        assert_eq!(Transfer::store_all(&[transfer()], &linearizer), Vec::new());
+    }
+
+    #[test]
+    fn test_store_within_merged_station() {
+        let merged_stations = map! {
+            "oranienburger_tor" => "friedrichstr".into(),
+        };
+        let mut linearizer = Linearizer::with_merged_stations(merged_stations);
+        linearizer.retrieve(&Rc::new(locations::friedrichstr()));
+        assert_eq!(Transfer::store_all(&[transfer()], &linearizer), Vec::new());
+    }
+
+    #[test]
+    fn test_store_duplicates() {
+        let merged_stations = map! {
+            "hauptbahnhof" => "friedrichstr".into(),
+        };
+        let mut linearizer = Linearizer::with_merged_stations(merged_stations);
+        linearizer.retrieve(&Rc::new(locations::friedrichstr()));
+        linearizer.retrieve(&Rc::new(locations::oranienburger_tor()));
+        let transfers = [
+            transfer(),
+            Transfer::new(
+                Rc::new(locations::hauptbahnhof()),
+                Rc::new(locations::oranienburger_tor()),
+                Kind::MinimumTime,
+                Some(300),
+            ),
+            Transfer::new(
+                Rc::new(locations::hauptbahnhof()),
+                Rc::new(locations::oranienburger_tor()),
+                Kind::Forbidden,
+                None,
+            ),
+        ];
+        assert_eq!(
+            Transfer::store_all(&transfers, &linearizer),
+            vec![
+                storage::Transfer::new(0, 1, Kind::MinimumTime, Some(300)),
+                storage::Transfer::new(0, 1, Kind::Forbidden, None),
+            ]
+        );
+    }
+}
@@ -0,0 +1,60 @@
+use std::fmt;
+
+use serde::de::{Deserialize, Deserializer, Error as DeserializeError, Visitor};
+
+/// Whether passengers can get on or off at a stop, as given by `pickup_type` and `drop_off_type`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Boarding {
+    Regular,
+    Unavailable,
+    PhoneAgency,
+    CoordinateWithDriver,
+}
+
+impl<'de> Deserialize<'de> for Boarding {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct BoardingVisitor;
+
+        impl<'de> Visitor<'de> for BoardingVisitor {
+            type Value = Boarding;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("integer between 0 and 3")
+            }
+
+            fn visit_u64<E>(self, value: u64) -> Result<Boarding, E>
+            where
+                E: DeserializeError,
+            {
+                match value {
+                    0 => Ok(Boarding::Regular),
+                    1 => Ok(Boarding::Unavailable),
+                    2 => Ok(Boarding::PhoneAgency),
+                    3 => Ok(Boarding::CoordinateWithDriver),
+                    _ => Err(E::custom(format!("unknown boarding of value: {}", value))),
+                }
+            }
+        }
+
+        deserializer.deserialize_u64(BoardingVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_test::{assert_de_tokens, assert_de_tokens_error, Token};
+
+    use super::*;
+
+    #[test]
+    fn test_deserialize_boarding() {
+        assert_de_tokens(&Boarding::Regular, &[Token::U8(0)]);
+        assert_de_tokens(&Boarding::Unavailable, &[Token::U8(1)]);
+        assert_de_tokens(&Boarding::PhoneAgency, &[Token::U8(2)]);
+        assert_de_tokens(&Boarding::CoordinateWithDriver, &[Token::U8(3)]);
+        assert_de_tokens_error::<Boarding>(&[Token::U8(4)], "unknown boarding of value: 4");
+    }
+}
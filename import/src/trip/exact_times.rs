@@ -0,0 +1,60 @@
+use std::fmt;
+
+use serde::de::{Deserialize, Deserializer, Error as DeserializeError, Visitor};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum ExactTimes {
+    FrequencyBased,
+    ScheduleBased,
+}
+
+impl<'de> Deserialize<'de> for ExactTimes {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct ExactTimesVisitor;
+
+        impl<'de> Visitor<'de> for ExactTimesVisitor {
+            type Value = ExactTimes;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("either 0 or 1")
+            }
+
+            fn visit_u64<E>(self, value: u64) -> Result<ExactTimes, E>
+            where
+                E: DeserializeError,
+            {
+                match value {
+                    0 => Ok(ExactTimes::FrequencyBased),
+                    1 => Ok(ExactTimes::ScheduleBased),
+                    _ => Err(E::custom(format!(
+                        "unknown exact times of value: {}",
+                        value
+                    ))),
+                }
+            }
+        }
+
+        deserializer.deserialize_u64(ExactTimesVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_test::{assert_de_tokens, assert_de_tokens_error, Token};
+
+    use super::*;
+
+    #[test]
+    fn test_deserialize_exact_times() {
+        assert_de_tokens(&ExactTimes::FrequencyBased, &[Token::U8(0)]);
+        assert_de_tokens(&ExactTimes::ScheduleBased, &[Token::U8(1)]);
+        assert_de_tokens_error::<ExactTimes>(&[Token::U8(2)], "unknown exact times of value: 2");
+        assert_de_tokens_error::<ExactTimes>(
+            &[Token::Str("")],
+            "invalid type: string \"\", expected either 0 or 1",
+        );
+    }
+}
@@ -0,0 +1,82 @@
+use std::iter;
+
+use chrono::Duration;
+
+use super::ExactTimes;
+
+#[derive(Debug, PartialEq)]
+pub(super) struct Frequency {
+    start: Duration,
+    end: Duration,
+    headway: Duration,
+    exact_times: ExactTimes,
+}
+
+impl Frequency {
+    pub(super) fn new(
+        start: Duration,
+        end: Duration,
+        headway: Duration,
+        exact_times: ExactTimes,
+    ) -> Self {
+        Self {
+            start,
+            end,
+            headway,
+            exact_times,
+        }
+    }
+
+    /// Start times of the trips described by this frequency.
+    ///
+    /// Frequency-based service only promises the headway, so the trips are
+    /// spread evenly across the window by starting half a headway late.
+    pub(super) fn start_times(&self) -> impl Iterator<Item = Duration> + '_ {
+        let first = match self.exact_times {
+            ExactTimes::FrequencyBased => self.start + self.headway / 2,
+            ExactTimes::ScheduleBased => self.start,
+        };
+        iter::successors(Some(first), move |&start| Some(start + self.headway))
+            .take_while(move |&start| start < self.end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_utils::{time, times};
+
+    fn frequency(exact_times: ExactTimes) -> Frequency {
+        Frequency::new(
+            time!(Duration; 9:00:00),
+            time!(Duration; 9:30:00),
+            time!(Duration; 0:10:00),
+            exact_times,
+        )
+    }
+
+    #[test]
+    fn test_schedule_based_start_times() {
+        let frequency = frequency(ExactTimes::ScheduleBased);
+        assert_eq!(
+            frequency.start_times().collect::<Vec<_>>(),
+            times!(Duration; 9:00:00, 9:10:00, 9:20:00)
+        );
+    }
+
+    #[test]
+    fn test_frequency_based_start_times() {
+        let frequency = frequency(ExactTimes::FrequencyBased);
+        assert_eq!(
+            frequency.start_times().collect::<Vec<_>>(),
+            times!(Duration; 9:05:00, 9:15:00, 9:25:00)
+        );
+    }
+
+    #[test]
+    fn test_empty_window() {
+        let mut frequency = frequency(ExactTimes::ScheduleBased);
+        frequency.end = frequency.start;
+        assert_eq!(frequency.start_times().count(), 0);
+    }
+}
@@ -1,19 +1,22 @@
 use std::collections::HashMap;
-use std::error::Error;
 use std::iter;
 use std::rc::Rc;
 
-use super::{Route, RouteBuffer, StopRecord, TripBuffer, TripId, TripRecord};
+use super::{
+    Frequency, FrequencyRecord, Route, RouteBuffer, StopRecord, TripBuffer, TripId, TripRecord,
+};
 use crate::line::LineId;
 use crate::location::{Location, LocationId};
 use crate::service::{Service, ServiceId};
-use crate::shape::{Shape, ShapeId};
+use crate::shape::{FallbackMode, Shape, ShapeId};
 use crate::utils::{Action, Dataset};
+use crate::ImportError;
 
 pub(crate) struct Importer<'a> {
     services: &'a HashMap<ServiceId, Rc<Service>>,
     locations: &'a HashMap<LocationId, Rc<Location>>,
     shapes: &'a HashMap<ShapeId, Shape>,
+    shape_fallback: FallbackMode,
     id_mapping: &'a HashMap<LineId, usize>,
     line_count: usize,
 }
@@ -23,6 +26,7 @@ impl<'a> Importer<'a> {
         services: &'a HashMap<ServiceId, Rc<Service>>,
         locations: &'a HashMap<LocationId, Rc<Location>>,
         shapes: &'a HashMap<ShapeId, Shape>,
+        shape_fallback: FallbackMode,
         id_mapping: &'a HashMap<LineId, usize>,
         line_count: usize,
     ) -> Importer<'a> {
@@ -30,6 +34,7 @@ impl<'a> Importer<'a> {
             services,
             locations,
             shapes,
+            shape_fallback,
             id_mapping,
             line_count,
         }
@@ -38,13 +43,13 @@ impl<'a> Importer<'a> {
     fn import_trip_buffers(
         &self,
         dataset: &mut impl Dataset,
-    ) -> Result<HashMap<TripId, TripBuffer>, Box<dyn Error>> {
+    ) -> Result<HashMap<TripId, TripBuffer>, ImportError> {
         let mut buffers = HashMap::new();
 
         let action = Action::start("Importing trips");
         for result in action.read_csv(dataset, "trips.txt")? {
-            let record: TripRecord = result?;
-            record.import(self.id_mapping, self.services, &mut buffers);
+            let (line, record): (_, TripRecord) = result?;
+            record.import(line, self.id_mapping, self.services, &mut buffers)?;
         }
         action.complete(&format!("Imported {} trips", buffers.len()));
         Ok(buffers)
@@ -54,24 +59,66 @@ impl<'a> Importer<'a> {
         &self,
         dataset: &mut impl Dataset,
         buffers: &mut HashMap<TripId, TripBuffer>,
-    ) -> Result<(), Box<dyn Error>> {
+    ) -> Result<(), ImportError> {
         let action = Action::start("Importing trip stops");
         for result in action.read_csv(dataset, "stop_times.txt")? {
-            let record: StopRecord = result?;
-            record.import(self.locations, buffers);
+            let (line, record): (_, StopRecord) = result?;
+            record.import(line, self.locations, buffers)?;
+        }
+        for (id, buffer) in buffers.iter_mut() {
+            buffer.complete_stops(id, self.shapes)?;
         }
         action.complete("Imported trip stops");
         Ok(())
     }
 
-    fn combine_into_routes(&self, buffers: HashMap<TripId, TripBuffer>) -> Vec<Vec<Route>> {
+    fn import_frequencies(
+        &self,
+        dataset: &mut impl Dataset,
+    ) -> Result<HashMap<TripId, Vec<Frequency>>, ImportError> {
+        let mut frequencies = HashMap::new();
+        if !dataset.contains("frequencies.txt") {
+            return Ok(frequencies);
+        }
+
+        let action = Action::start("Importing trip frequencies");
+        for result in action.read_csv(dataset, "frequencies.txt")? {
+            let (line, record): (_, FrequencyRecord) = result?;
+            record.import(line, &mut frequencies)?;
+        }
+        action.complete(&format!(
+            "Imported frequencies of {} trips",
+            frequencies.len()
+        ));
+        Ok(frequencies)
+    }
+
+    fn expand_frequencies(
+        &self,
+        buffers: HashMap<TripId, TripBuffer>,
+        mut frequencies: HashMap<TripId, Vec<Frequency>>,
+    ) -> Vec<(TripId, TripBuffer)> {
+        buffers
+            .into_iter()
+            .flat_map(|(id, buffer)| match frequencies.remove(&id) {
+                Some(frequencies) => buffer
+                    .expand(&frequencies)
+                    .into_iter()
+                    .map(|buffer| (id.clone(), buffer))
+                    .collect(),
+                None => vec![(id, buffer)],
+            })
+            .collect()
+    }
+
+    fn combine_into_routes(&self, buffers: Vec<(TripId, TripBuffer)>) -> Vec<Vec<Route>> {
         let mut action = Action::start("Assigning trips to their lines");
         let mut route_buffers = iter::repeat_with(RouteBuffer::new)
             .take(self.line_count)
             .collect();
 
-        for (_, buffer) in action.wrap_iter(buffers) {
-            buffer.create_and_place_trip(&self.shapes, &mut route_buffers);
+        for (id, buffer) in action.wrap_iter(buffers) {
+            buffer.create_and_place_trip(id, &self.shapes, self.shape_fallback, &mut route_buffers);
         }
         action.complete("Assigned trips to their lines");
 
@@ -84,12 +131,11 @@ impl<'a> Importer<'a> {
         routes
     }
 
-    pub(crate) fn import(
-        self,
-        dataset: &mut impl Dataset,
-    ) -> Result<Vec<Vec<Route>>, Box<dyn Error>> {
+    pub(crate) fn import(self, dataset: &mut impl Dataset) -> Result<Vec<Vec<Route>>, ImportError> {
         let mut buffers = self.import_trip_buffers(dataset)?;
         self.add_trip_stops(dataset, &mut buffers)?;
+        let frequencies = self.import_frequencies(dataset)?;
+        let buffers = self.expand_frequencies(buffers, frequencies);
         Ok(self.combine_into_routes(buffers))
     }
 }
@@ -99,6 +145,7 @@ mod tests {
     use super::*;
     use crate::dataset;
     use crate::fixtures::{locations, routes, services, shapes};
+    use chrono::NaiveDate;
     use test_utils::{assert_eq_alternate, map};
 
     #[test]
@@ -126,7 +173,14 @@ mod tests {
         let services = services::by_id();
         let locations = locations::by_id();
         let shapes = shapes::by_id();
-        let importer = Importer::new(&services, &locations, &shapes, &id_mapping, 1);
+        let importer = Importer::new(
+            &services,
+            &locations,
+            &shapes,
+            FallbackMode::Straight,
+            &id_mapping,
+            1,
+        );
         let routes = importer.import(&mut dataset).unwrap();
         assert_eq!(routes.len(), 1);
         assert_eq_alternate!(
@@ -134,4 +188,136 @@ mod tests {
             vec![routes::tram_12::oranienburger_tor_am_kupfergraben()],
         );
     }
+
+    #[test]
+    fn test_import_with_frequencies() {
+        let mut dataset = dataset!(
+            trips:
+                trip_id, route_id, service_id, shape_id,                        direction_id;
+                1,       tram_12,  mon_fri,    tram_12::oranienburger_tor_am_kupfergraben, 0;
+                2,       tram_12,  mon_fri,    tram_12::am_kupfergraben_oranienburger_tor, 1
+            stop_times:
+                trip_id, stop_id,                    arrival_time, departure_time;
+                1,       oranienburger_tor,          "0:00:00",    "0:00:00";
+                1,       friedrichstr,               "0:02:00",    "0:02:00";
+                1,       universitaetsstr,           "0:04:00",    "0:04:00";
+                1,       am_kupfergraben,            "0:05:00",    "0:05:00";
+                2,       am_kupfergraben,            "8:34:00",    "8:34:00";
+                2,       georgenstr_am_kupfergraben, "8:35:00",    "8:35:00";
+                2,       friedrichstr,               "8:38:00",    "8:38:00";
+                2,       oranienburger_tor,          "8:40:00",    "8:40:00"
+            frequencies:
+                trip_id, start_time, end_time,  headway_secs, exact_times;
+                1,       "9:00:00",  "9:20:00", 600,          1
+        );
+        let id_mapping = map! {
+            "tram_12" => 0,
+        };
+
+        let services = services::by_id();
+        let locations = locations::by_id();
+        let shapes = shapes::by_id();
+        let importer = Importer::new(
+            &services,
+            &locations,
+            &shapes,
+            FallbackMode::Straight,
+            &id_mapping,
+            1,
+        );
+        let routes = importer.import(&mut dataset).unwrap();
+        assert_eq!(routes.len(), 1);
+        assert_eq!(routes[0].len(), 1);
+        assert_eq!(
+            routes[0][0].num_trips_at(NaiveDate::from_ymd(2019, 1, 7)),
+            3
+        );
+    }
+
+    #[test]
+    fn test_import_without_shapes() {
+        let mut dataset = dataset!(
+            trips:
+                trip_id, route_id, service_id, direction_id;
+                1,       tram_12,  mon_fri,    0
+            stop_times:
+                trip_id, stop_id,                    arrival_time, departure_time;
+                1,       oranienburger_tor,          "9:02:00",    "9:02:00";
+                1,       friedrichstr,               "9:04:00",    "9:04:00";
+                1,       universitaetsstr,           "9:06:00",    "9:06:00";
+                1,       am_kupfergraben,            "9:07:00",    "9:07:00"
+        );
+        let id_mapping = map! {
+            "tram_12" => 0,
+        };
+
+        let services = services::by_id();
+        let locations = locations::by_id();
+        let shapes = HashMap::new();
+        let importer = Importer::new(
+            &services,
+            &locations,
+            &shapes,
+            FallbackMode::Straight,
+            &id_mapping,
+            1,
+        );
+        let routes = importer.import(&mut dataset).unwrap();
+        let route = &routes[0][0];
+        assert!(route.has_synthesized_shape());
+        let stops = route
+            .nodes()
+            .map(|node| node.location().unwrap().name())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            stops,
+            [
+                "Oranienburger Tor",
+                "Friedrichstr.",
+                "Universitätsstr.",
+                "Am Kupfergraben"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_import_sparse_stop_times() {
+        let mut dataset = dataset!(
+            trips:
+                trip_id, route_id, service_id, direction_id;
+                1,       tram_12,  mon_fri,    0
+            stop_times:
+                trip_id, stop_id,           stop_sequence, arrival_time, departure_time, pickup_type, drop_off_type, timepoint;
+                1,       am_kupfergraben,   4,             "9:07:00",    "9:07:00",      "",          "",            "";
+                1,       universitaetsstr,  3,             "",           "",             1,           1,             0;
+                1,       friedrichstr,      2,             "",           "",             0,           0,             0;
+                1,       oranienburger_tor, 1,             "9:02:00",    "9:02:00",      "",          "",            1
+        );
+        let id_mapping = map! {
+            "tram_12" => 0,
+        };
+
+        let services = services::by_id();
+        let locations = locations::by_id();
+        let shapes = HashMap::new();
+        let importer = Importer::new(
+            &services,
+            &locations,
+            &shapes,
+            FallbackMode::Straight,
+            &id_mapping,
+            1,
+        );
+        let routes = importer.import(&mut dataset).unwrap();
+        let route = &routes[0][0];
+        let stops = route
+            .nodes()
+            .filter_map(|node| node.location())
+            .map(|location| location.name())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            stops,
+            ["Oranienburger Tor", "Friedrichstr.", "Am Kupfergraben"]
+        );
+    }
 }
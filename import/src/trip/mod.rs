@@ -1,11 +1,17 @@
+mod boarding;
+mod exact_times;
+mod frequency;
 mod importer;
 mod node;
+mod plausibility;
 mod record;
 mod route;
 mod route_buffer;
 mod route_variant;
 mod schedule;
 mod scheduler;
+mod stop_time;
+mod timepoint;
 mod trip;
 mod trip_buffer;
 
@@ -19,14 +25,21 @@ pub(crate) mod fixtures {
     pub(crate) use super::trip_buffer::fixtures as trip_buffers;
 }
 
+use boarding::Boarding;
+use exact_times::ExactTimes;
+use frequency::Frequency;
 use node::Node;
-use record::{StopRecord, TripRecord};
+use record::{FrequencyRecord, StopRecord, TripRecord};
 use route_buffer::RouteBuffer;
 use route_variant::RouteVariant;
 use schedule::Schedule;
+use stop_time::StopTime;
+use timepoint::Timepoint;
 use trip::Trip;
-use trip_buffer::{TripBuffer, TripId};
+use trip_buffer::TripBuffer;
 
 pub(crate) use importer::Importer;
+pub use plausibility::Implausibility;
 pub use route::Route;
 pub(crate) use scheduler::Scheduler;
+pub(crate) use trip_buffer::TripId;
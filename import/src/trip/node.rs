@@ -35,11 +35,19 @@ impl Node {
 
     pub fn location(&self) -> Option<&Rc<Location>> {
         match &self.kind {
-            Kind::Waypoint => None,
+            Kind::Waypoint | Kind::PassThrough { .. } => None,
             Kind::Stop { location } => Some(&location),
         }
     }
 
+    /// The location of a stop, including the ones passed through, where the trips are timed.
+    pub(super) fn timed_location(&self) -> Option<&Rc<Location>> {
+        match &self.kind {
+            Kind::Waypoint => None,
+            Kind::Stop { location } | Kind::PassThrough { location } => Some(location),
+        }
+    }
+
     pub fn in_directions(&self) -> Directions {
         self.in_directions
     }
@@ -48,7 +56,7 @@ impl Node {
         let mut weights = nodes
             .iter()
             .filter(|node| node.in_directions.allows(direction))
-            .filter(|node| node.location().is_some())
+            .filter(|node| node.timed_location().is_some())
             .tuple_windows()
             .map(|(before, after)| na::distance(&before.position, &after.position))
             .collect::<Vec<_>>();
@@ -58,10 +66,33 @@ impl Node {
         weights
     }
 
+    /// Whether the trips pass through each timed node in the direction, in the order of travel.
+    pub(super) fn passed_through(nodes: &[Self], direction: Direction) -> Vec<bool> {
+        let mut passed_through = nodes
+            .iter()
+            .filter(|node| node.in_directions.allows(direction))
+            .filter(|node| node.timed_location().is_some())
+            .map(|node| node.location().is_none())
+            .collect::<Vec<_>>();
+        if direction == Direction::Downstream {
+            passed_through.reverse();
+        }
+        passed_through
+    }
+
     pub(super) fn make_stop(&mut self, location: Rc<Location>) {
         self.kind = Kind::Stop { location };
     }
 
+    /// Turns the stop into one the vehicles pass through without stopping.
+    pub(super) fn make_pass_through(&mut self) {
+        if let Kind::Stop { location } = &self.kind {
+            self.kind = Kind::PassThrough {
+                location: Rc::clone(location),
+            };
+        }
+    }
+
     pub(super) fn can_be_merged(&self, other: &Self) -> bool {
         self.position == other.position
             && self.kind == other.kind
@@ -80,6 +111,7 @@ impl Node {
             Kind::Stop { ref location } => storage::NodeKind::Stop {
                 at: linerarizer.retrieve(location),
             },
+            Kind::PassThrough { .. } => storage::NodeKind::PassThrough,
         };
         let position = transform(self.position);
         storage::Node::new(position, kind, self.in_directions)
@@ -102,6 +134,12 @@ impl fmt::Debug for Node {
                 .field("location", &location)
                 .field("in_directions", &self.in_directions)
                 .finish(),
+            Kind::PassThrough { location } => formatter
+                .debug_struct("PassThrough")
+                .field("position", &position)
+                .field("location", &location)
+                .field("in_directions", &self.in_directions)
+                .finish(),
         }
     }
 }
@@ -110,6 +148,7 @@ impl fmt::Debug for Node {
 pub(crate) enum Kind {
     Waypoint,
     Stop { location: Rc<Location> },
+    PassThrough { location: Rc<Location> },
 }
 
 #[cfg(test)]
@@ -408,6 +447,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_make_pass_through() {
+        let mut nodes = nodes::tram_12::oranienburger_tor_am_kupfergraben(Directions::Both);
+        nodes[4].make_pass_through();
+        assert_eq!(nodes[4].location(), None);
+        assert_eq!(
+            nodes[4].timed_location(),
+            Some(&Rc::new(locations::friedrichstr()))
+        );
+        assert_eq!(
+            Node::passed_through(&nodes, Direction::Upstream),
+            [false, true, false, false]
+        );
+        assert_eq!(
+            Node::passed_through(&nodes, Direction::Downstream),
+            [false, false, true, false]
+        );
+
+        let mut linearizer = Linearizer::new();
+        assert!(nodes[4].store(&mut linearizer).load(&[]).is_timed());
+        assert!(linearizer.location_ids().is_empty());
+    }
+
     #[test]
     fn test_merge() {
         let nodes = nodes::tram_12::oranienburger_tor_am_kupfergraben;
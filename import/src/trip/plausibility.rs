@@ -0,0 +1,336 @@
+use std::fmt;
+
+use chrono::Duration;
+use itertools::Itertools;
+
+use super::{Node, Trip};
+use simulation::line::Kind;
+use simulation::Direction;
+
+/// Fastest speed in km/h vehicles of a kind are expected to reach.
+fn speed_limit(kind: Kind) -> f64 {
+    match kind {
+        Kind::Railway => 300.0,
+        Kind::RegionalRailway => 200.0,
+        Kind::SuburbanRailway | Kind::Coach | Kind::Taxi => 130.0,
+        Kind::UrbanRailway | Kind::Monorail | Kind::Bus => 100.0,
+        Kind::Trolleybus | Kind::Tram => 80.0,
+        Kind::WaterTransport => 60.0,
+        Kind::CableTram | Kind::AerialLift | Kind::Funicular => 40.0,
+        Kind::Air => 1000.0,
+    }
+}
+
+/// Suspicious timetable or shape data found on a route.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Implausibility {
+    /// Trips travel between two stops faster than vehicles of the line can.
+    ExcessiveSpeed {
+        from: String,
+        to: String,
+        /// Highest speed in km/h among the affected trips.
+        speed: f64,
+        trips: usize,
+    },
+    /// Trips need no time between two stops which are too far apart to be rounded away.
+    InstantHop {
+        from: String,
+        to: String,
+        /// Distance between the stops in meters.
+        distance: f64,
+        trips: usize,
+    },
+    /// Trips run in the same direction with the same service and times as another trip.
+    DuplicateTrips { trips: usize },
+    /// The distance travelled along the shape places a stop away from the shape point nearest to
+    /// it.
+    MisplacedStop {
+        stop: String,
+        /// Distance between both placements in meters.
+        distance: f64,
+    },
+}
+
+impl fmt::Display for Implausibility {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Implausibility::ExcessiveSpeed {
+                from,
+                to,
+                speed,
+                trips,
+            } => write!(
+                formatter,
+                "{} trips run up to {:.0} km/h between {} and {}",
+                trips, speed, from, to
+            ),
+            Implausibility::InstantHop {
+                from,
+                to,
+                distance,
+                trips,
+            } => write!(
+                formatter,
+                "{} trips need no time for {:.0} m between {} and {}",
+                trips, distance, from, to
+            ),
+            Implausibility::DuplicateTrips { trips } => {
+                write!(formatter, "{} trips duplicate other trips", trips)
+            }
+            Implausibility::MisplacedStop { stop, distance } => write!(
+                formatter,
+                "shape_dist_traveled places {} {:.0} m away from its nearest shape point",
+                stop, distance
+            ),
+        }
+    }
+}
+
+/// A part of the route between two consecutive stops, in travel direction.
+struct Segment<'a> {
+    from: &'a str,
+    to: &'a str,
+    distance: f64,
+}
+
+impl<'a> Segment<'a> {
+    fn along(nodes: &'a [Node], direction: Direction) -> Vec<Self> {
+        let mut nodes = nodes
+            .iter()
+            .filter(|node| node.in_directions().allows(direction))
+            .collect::<Vec<_>>();
+        if direction == Direction::Downstream {
+            nodes.reverse();
+        }
+
+        let mut segments = Vec::new();
+        let mut last_stop = None;
+        let mut distance = 0.0;
+        for (before, after) in nodes.iter().tuple_windows() {
+            if let Some(location) = before.timed_location() {
+                last_stop = Some(location.name());
+                distance = 0.0;
+            }
+            distance += na::distance(&before.position(), &after.position());
+            if let (Some(from), Some(location)) = (last_stop, after.timed_location()) {
+                segments.push(Segment {
+                    from,
+                    to: location.name(),
+                    distance,
+                });
+            }
+        }
+        segments
+    }
+
+    /// Speed in km/h needed to cover this segment in the given time.
+    fn speed(&self, duration: Duration) -> f64 {
+        self.distance / duration.num_seconds() as f64 * 3.6
+    }
+}
+
+/// Looks for speeds and hops the vehicles of the line cannot achieve.
+fn analyze_speeds(
+    nodes: &[Node],
+    trips: &[Trip],
+    kind: Kind,
+    direction: Direction,
+) -> Vec<Implausibility> {
+    let limit = speed_limit(kind);
+    let segments = Segment::along(nodes, direction);
+    // timetables are usually rounded to minutes
+    let rounding_distance = limit / 3.6 * 60.0;
+
+    let mut speeding = vec![(0.0, 0); segments.len()];
+    let mut instant = vec![0; segments.len()];
+    for trip in trips.iter().filter(|trip| trip.direction() == direction) {
+        for (i, duration) in trip.driving_durations().enumerate().take(segments.len()) {
+            let segment = &segments[i];
+            if duration <= Duration::zero() {
+                if segment.distance > rounding_distance {
+                    instant[i] += 1;
+                }
+            } else {
+                let speed = segment.speed(duration);
+                if speed > limit {
+                    let (max_speed, count) = &mut speeding[i];
+                    *max_speed = speed.max(*max_speed);
+                    *count += 1;
+                }
+            }
+        }
+    }
+
+    let mut implausibilities = Vec::new();
+    for (i, segment) in segments.iter().enumerate() {
+        if speeding[i].1 > 0 {
+            implausibilities.push(Implausibility::ExcessiveSpeed {
+                from: segment.from.to_string(),
+                to: segment.to.to_string(),
+                speed: speeding[i].0,
+                trips: speeding[i].1,
+            });
+        }
+        if instant[i] > 0 {
+            implausibilities.push(Implausibility::InstantHop {
+                from: segment.from.to_string(),
+                to: segment.to.to_string(),
+                distance: segment.distance,
+                trips: instant[i],
+            });
+        }
+    }
+    implausibilities
+}
+
+fn count_duplicates(trips: &[Trip]) -> usize {
+    trips
+        .iter()
+        .enumerate()
+        .filter(|(i, trip)| trips[..*i].iter().any(|other| trip.duplicates(other)))
+        .count()
+}
+
+pub(super) fn analyze(nodes: &[Node], trips: &[Trip], kind: Kind) -> Vec<Implausibility> {
+    let mut implausibilities = analyze_speeds(nodes, trips, kind, Direction::Upstream);
+    implausibilities.extend(analyze_speeds(nodes, trips, kind, Direction::Downstream));
+    let duplicates = count_duplicates(trips);
+    if duplicates > 0 {
+        implausibilities.push(Implausibility::DuplicateTrips { trips: duplicates });
+    }
+    implausibilities
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::fixtures::{nodes, services, trips};
+    use simulation::Directions;
+    use test_utils::{time, times};
+
+    fn trip(direction: Direction, times: Vec<Duration>) -> Trip {
+        Trip::new(
+            "1".into(),
+            direction,
+            Rc::new(services::mon_fri()),
+            None,
+            times,
+        )
+    }
+
+    #[test]
+    fn test_segments() {
+        let nodes = nodes::tram_12::oranienburger_tor_am_kupfergraben(Directions::Both);
+        let segments = Segment::along(&nodes, Direction::Downstream);
+        let names = segments
+            .iter()
+            .map(|segment| (segment.from, segment.to))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            [
+                ("Am Kupfergraben", "Georgenstr./Am Kupfergraben"),
+                ("Georgenstr./Am Kupfergraben", "Friedrichstr."),
+                ("Friedrichstr.", "Oranienburger Tor"),
+            ]
+        );
+        assert!(segments.iter().all(|segment| segment.distance > 0.0));
+    }
+
+    #[test]
+    fn test_plausible_trips() {
+        let nodes = nodes::tram_12::oranienburger_tor_am_kupfergraben(Directions::Both);
+        let trips = vec![
+            trips::tram_12::oranienburger_tor_am_kupfergraben(time!(9:02:00)),
+            trips::tram_12::am_kupfergraben_oranienburger_tor(time!(8:34:00)),
+        ];
+        assert_eq!(analyze(&nodes, &trips, Kind::Tram), Vec::new());
+    }
+
+    #[test]
+    fn test_excessive_speed() {
+        let nodes = nodes::tram_12::oranienburger_tor_am_kupfergraben(Directions::Both);
+        let trips = vec![trip(
+            Direction::Upstream,
+            times!(Duration; 0, [0:00, 0:10, 0:00, 2:00, 0:00, 1:00, 0:00]),
+        )];
+        let implausibilities = analyze(&nodes, &trips, Kind::Tram);
+        assert_eq!(implausibilities.len(), 1);
+        match &implausibilities[0] {
+            Implausibility::ExcessiveSpeed {
+                from, to, trips, ..
+            } => {
+                assert_eq!(from, "Oranienburger Tor");
+                assert_eq!(to, "Friedrichstr.");
+                assert_eq!(*trips, 1);
+            }
+            implausibility => panic!("unexpected {:?}", implausibility),
+        }
+    }
+
+    #[test]
+    fn test_instant_hop_between_close_stops() {
+        let nodes = nodes::tram_12::oranienburger_tor_am_kupfergraben(Directions::Both);
+        let trips = vec![trip(
+            Direction::Upstream,
+            times!(Duration; 0, [0:00, 0:00, 0:00, 2:00, 0:00, 1:00, 0:00]),
+        )];
+        assert_eq!(analyze(&nodes, &trips, Kind::Tram), Vec::new());
+    }
+
+    #[test]
+    fn test_instant_hop_between_distant_stops() {
+        let nodes = nodes::s41::circle(Directions::Both);
+        let trips = vec![trip(
+            Direction::Upstream,
+            times!(Duration; 0, [0:00, 0:00, 0:00, 10:00, 0:00, 10:00, 0:00, 10:00, 0:00]),
+        )];
+        let implausibilities = analyze(&nodes, &trips, Kind::SuburbanRailway);
+        assert_eq!(implausibilities.len(), 1);
+        match &implausibilities[0] {
+            Implausibility::InstantHop {
+                from,
+                to,
+                distance,
+                trips,
+            } => {
+                assert_eq!(from, "Gesundbrunnen");
+                assert_eq!(to, "Ostkreuz");
+                assert!(*distance > 5000.0);
+                assert_eq!(*trips, 1);
+            }
+            implausibility => panic!("unexpected {:?}", implausibility),
+        }
+    }
+
+    #[test]
+    fn test_duplicate_trips() {
+        let nodes = nodes::tram_12::oranienburger_tor_am_kupfergraben(Directions::Both);
+        let trips = vec![
+            trips::tram_12::oranienburger_tor_am_kupfergraben(time!(9:02:00)),
+            trips::tram_12::oranienburger_tor_am_kupfergraben(time!(9:02:00)),
+            trips::tram_12::oranienburger_tor_am_kupfergraben(time!(9:12:00)),
+            trips::tram_12::oranienburger_tor_am_kupfergraben(time!(9:02:00)),
+        ];
+        assert_eq!(
+            analyze(&nodes, &trips, Kind::Tram),
+            vec![Implausibility::DuplicateTrips { trips: 2 }]
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        let implausibility = Implausibility::ExcessiveSpeed {
+            from: "Oranienburger Tor".to_string(),
+            to: "Friedrichstr.".to_string(),
+            speed: 301.4,
+            trips: 3,
+        };
+        assert_eq!(
+            implausibility.to_string(),
+            "3 trips run up to 301 km/h between Oranienburger Tor and Friedrichstr."
+        );
+    }
+}
@@ -5,12 +5,13 @@ use serde_derive::Deserialize;
 
 use chrono::Duration;
 
-use super::{TripBuffer, TripId};
+use super::{Boarding, ExactTimes, Frequency, StopTime, Timepoint, TripBuffer, TripId};
 use crate::deserialize;
 use crate::line::LineId;
 use crate::location::{Location, LocationId};
 use crate::service::{Service, ServiceId};
 use crate::shape::ShapeId;
+use crate::ImportError;
 use simulation::Direction;
 
 #[derive(Debug, Deserialize)]
@@ -18,7 +19,8 @@ pub(super) struct TripRecord {
     trip_id: TripId,
     route_id: LineId,
     service_id: ServiceId,
-    shape_id: ShapeId,
+    #[serde(default)]
+    shape_id: Option<ShapeId>,
     #[serde(deserialize_with = "deserialize::direction")]
     direction_id: Direction,
 }
@@ -26,14 +28,37 @@ pub(super) struct TripRecord {
 impl TripRecord {
     pub(super) fn import(
         self,
+        line: u64,
         id_mapping: &HashMap<LineId, usize>,
         services: &HashMap<ServiceId, Rc<Service>>,
         buffers: &mut HashMap<TripId, TripBuffer>,
-    ) {
-        let line_id = id_mapping[&self.route_id];
-        let service = Rc::clone(&services[&self.service_id]);
-        let buffer = TripBuffer::new(line_id, service, self.shape_id, self.direction_id);
+    ) -> Result<(), ImportError> {
+        let line_id =
+            *id_mapping
+                .get(&self.route_id)
+                .ok_or_else(|| ImportError::DanglingReference {
+                    file: "trips.txt",
+                    line,
+                    field: "route_id",
+                    id: self.route_id.to_string(),
+                })?;
+        let service =
+            services
+                .get(&self.service_id)
+                .ok_or_else(|| ImportError::DanglingReference {
+                    file: "trips.txt",
+                    line,
+                    field: "service_id",
+                    id: self.service_id.to_string(),
+                })?;
+        let buffer = TripBuffer::new(
+            line_id,
+            Rc::clone(service),
+            self.shape_id,
+            self.direction_id,
+        );
         buffers.insert(self.trip_id, buffer);
+        Ok(())
     }
 }
 
@@ -41,23 +66,110 @@ impl TripRecord {
 pub(super) struct StopRecord {
     trip_id: TripId,
     stop_id: LocationId,
-    #[serde(deserialize_with = "deserialize::duration")]
-    arrival_time: Duration,
-    #[serde(deserialize_with = "deserialize::duration")]
-    departure_time: Duration,
+    #[serde(default)]
+    stop_sequence: Option<u32>,
+    #[serde(deserialize_with = "deserialize::optional_duration")]
+    arrival_time: Option<Duration>,
+    #[serde(deserialize_with = "deserialize::optional_duration")]
+    departure_time: Option<Duration>,
+    #[serde(default)]
+    shape_dist_traveled: Option<f64>,
+    #[serde(default)]
+    pickup_type: Option<Boarding>,
+    #[serde(default)]
+    drop_off_type: Option<Boarding>,
+    #[serde(default)]
+    timepoint: Option<Timepoint>,
 }
 
 impl StopRecord {
     pub(super) fn import(
         self,
+        line: u64,
         locations: &HashMap<LocationId, Rc<Location>>,
         buffers: &mut HashMap<TripId, TripBuffer>,
-    ) {
-        buffers.get_mut(&self.trip_id).unwrap().add_stop(
-            Rc::clone(&locations[&self.stop_id]),
+    ) -> Result<(), ImportError> {
+        let buffer =
+            buffers
+                .get_mut(&self.trip_id)
+                .ok_or_else(|| ImportError::DanglingReference {
+                    file: "stop_times.txt",
+                    line,
+                    field: "trip_id",
+                    id: self.trip_id.to_string(),
+                })?;
+        let location =
+            locations
+                .get(&self.stop_id)
+                .ok_or_else(|| ImportError::DanglingReference {
+                    file: "stop_times.txt",
+                    line,
+                    field: "stop_id",
+                    id: self.stop_id.to_string(),
+                })?;
+        let has_times = self.arrival_time.is_some() || self.departure_time.is_some();
+        if self.timepoint == Some(Timepoint::Exact) && !has_times {
+            return Err(ImportError::InvalidRecord {
+                file: "stop_times.txt",
+                line,
+                field: "trip_id",
+                id: self.trip_id.to_string(),
+                reason: "lacks times at an exact timepoint",
+            });
+        }
+
+        let pass_through = self.pickup_type == Some(Boarding::Unavailable)
+            && self.drop_off_type == Some(Boarding::Unavailable);
+        let stop_time = StopTime::new(
+            line,
+            self.stop_sequence,
+            Rc::clone(location),
             self.arrival_time,
             self.departure_time,
+            self.shape_dist_traveled,
+            pass_through,
         );
+        buffer.add_stop(stop_time.with_platform(location.platform(&self.stop_id)));
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct FrequencyRecord {
+    trip_id: TripId,
+    #[serde(deserialize_with = "deserialize::duration")]
+    start_time: Duration,
+    #[serde(deserialize_with = "deserialize::duration")]
+    end_time: Duration,
+    headway_secs: i64,
+    #[serde(default)]
+    exact_times: Option<ExactTimes>,
+}
+
+impl FrequencyRecord {
+    pub(super) fn import(
+        self,
+        line: u64,
+        frequencies: &mut HashMap<TripId, Vec<Frequency>>,
+    ) -> Result<(), ImportError> {
+        if self.headway_secs <= 0 {
+            return Err(ImportError::InvalidRecord {
+                file: "frequencies.txt",
+                line,
+                field: "trip_id",
+                id: self.trip_id.to_string(),
+                reason: "has a headway which is not positive",
+            });
+        }
+
+        let frequency = Frequency::new(
+            self.start_time,
+            self.end_time,
+            Duration::seconds(self.headway_secs),
+            self.exact_times.unwrap_or(ExactTimes::FrequencyBased),
+        );
+        frequencies.entry(self.trip_id).or_default().push(frequency);
+        Ok(())
     }
 }
 
@@ -72,7 +184,7 @@ mod tests {
             trip_id: "u4_0".into(),
             route_id: "u4".into(),
             service_id: "mon_fri".into(),
-            shape_id: "u4".into(),
+            shape_id: Some("u4".into()),
             direction_id: Direction::Upstream,
         }
     }
@@ -84,7 +196,9 @@ mod tests {
             "u4" => 0,
         };
         let mut buffers = HashMap::new();
-        record.import(&id_mapping, &services::by_id(), &mut buffers);
+        record
+            .import(2, &id_mapping, &services::by_id(), &mut buffers)
+            .unwrap();
         assert_eq!(
             buffers,
             map! {
@@ -104,8 +218,12 @@ mod tests {
             "u4_duplicate" => 0,
         };
         let mut buffers = HashMap::new();
-        first.import(&id_mapping, &services::by_id(), &mut buffers);
-        second.import(&id_mapping, &services::by_id(), &mut buffers);
+        first
+            .import(2, &id_mapping, &services::by_id(), &mut buffers)
+            .unwrap();
+        second
+            .import(3, &id_mapping, &services::by_id(), &mut buffers)
+            .unwrap();
         assert_eq!(
             buffers,
             map! {
@@ -115,47 +233,55 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_import_trip_of_unknown_line() {
+        let record = u4_trip_record();
+        let mut buffers = HashMap::new();
+        let error = record
+            .import(2, &HashMap::new(), &services::by_id(), &mut buffers)
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "trips.txt, line 2: route_id u4 not found"
+        );
+        assert!(buffers.is_empty());
+    }
+
+    fn stop_record(trip_id: &str, stop_id: &str, time: Option<i64>) -> StopRecord {
+        StopRecord {
+            trip_id: trip_id.into(),
+            stop_id: stop_id.into(),
+            stop_sequence: None,
+            arrival_time: time.map(Duration::seconds),
+            departure_time: time.map(Duration::seconds),
+            shape_dist_traveled: None,
+            pickup_type: None,
+            drop_off_type: None,
+            timepoint: None,
+        }
+    }
+
     #[test]
     fn test_import_stops() {
         let records = vec![
-            StopRecord {
-                trip_id: "u4_0".into(),
-                stop_id: "nollendorfplatz".into(),
-                arrival_time: Duration::seconds(time!(4:36:00)),
-                departure_time: Duration::seconds(time!(4:36:00)),
-            },
-            StopRecord {
-                trip_id: "u4_0".into(),
-                stop_id: "viktoria_luise_platz".into(),
-                arrival_time: Duration::seconds(time!(4:38:00)),
-                departure_time: Duration::seconds(time!(4:38:00)),
-            },
-            StopRecord {
-                trip_id: "u4_0".into(),
-                stop_id: "bayerischer_platz".into(),
-                arrival_time: Duration::seconds(time!(4:39:30)),
-                departure_time: Duration::seconds(time!(4:39:30)),
-            },
-            StopRecord {
-                trip_id: "u4_0".into(),
-                stop_id: "rathaus_schoeneberg".into(),
-                arrival_time: Duration::seconds(time!(4:41:00)),
-                departure_time: Duration::seconds(time!(4:41:00)),
-            },
-            StopRecord {
-                trip_id: "u4_0".into(),
-                stop_id: "innsbrucker_platz".into(),
-                arrival_time: Duration::seconds(time!(4:42:00)),
-                departure_time: Duration::seconds(time!(4:42:00)),
-            },
+            stop_record("u4_0", "nollendorfplatz", Some(time!(4:36:00))),
+            stop_record("u4_0", "viktoria_luise_platz", Some(time!(4:38:00))),
+            stop_record("u4_0", "bayerischer_platz", Some(time!(4:39:30))),
+            stop_record("u4_0", "rathaus_schoeneberg", Some(time!(4:41:00))),
+            stop_record("u4_0", "innsbrucker_platz", Some(time!(4:42:00))),
         ];
 
         let mut buffers = map! {
             "u4_0" => trip_buffers::u4::empty(time!(0:00)),
         };
 
-        for record in records {
-            record.import(&locations::by_id(), &mut buffers);
+        for (line, record) in (2..).zip(records) {
+            record
+                .import(line, &locations::by_id(), &mut buffers)
+                .unwrap();
+        }
+        for (id, buffer) in &mut buffers {
+            buffer.complete_stops(id, &HashMap::new()).unwrap();
         }
 
         assert_eq!(
@@ -165,4 +291,193 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn test_import_stops_at_platforms() {
+        let hauptbahnhof = Rc::new(locations::hauptbahnhof_with_complex());
+        let locations = map! {
+            "hauptbahnhof" => Rc::clone(&hauptbahnhof),
+            "hauptbahnhof_1" => hauptbahnhof,
+            "friedrichstr" => Rc::new(locations::friedrichstr()),
+        };
+        let records = vec![
+            stop_record("u4_0", "hauptbahnhof_1", Some(time!(4:36:00))),
+            stop_record("u4_0", "friedrichstr", Some(time!(4:40:00))),
+            stop_record("u4_0", "hauptbahnhof", Some(time!(4:44:00))),
+        ];
+
+        let mut buffers = map! {
+            "u4_0" => trip_buffers::u4::empty(time!(0:00)),
+        };
+        for (line, record) in (2..).zip(records) {
+            record.import(line, &locations, &mut buffers).unwrap();
+        }
+        let buffer = buffers.get_mut(&"u4_0".into()).unwrap();
+        buffer
+            .complete_stops(&"u4_0".into(), &HashMap::new())
+            .unwrap();
+        assert_eq!(buffer.platforms(), [Some(1), None, None]);
+    }
+
+    #[test]
+    fn test_import_stops_out_of_sequence() {
+        let mut records = vec![
+            stop_record("u4_0", "rathaus_schoeneberg", Some(time!(4:41:00))),
+            stop_record("u4_0", "nollendorfplatz", Some(time!(4:36:00))),
+            stop_record("u4_0", "innsbrucker_platz", Some(time!(4:42:00))),
+            stop_record("u4_0", "bayerischer_platz", Some(time!(4:39:30))),
+            stop_record("u4_0", "viktoria_luise_platz", Some(time!(4:38:00))),
+        ];
+        for (record, sequence) in records.iter_mut().zip(&[4, 1, 5, 3, 2]) {
+            record.stop_sequence = Some(*sequence);
+        }
+
+        let mut buffers = map! {
+            "u4_0" => trip_buffers::u4::empty(time!(0:00)),
+        };
+        for (line, record) in (2..).zip(records) {
+            record
+                .import(line, &locations::by_id(), &mut buffers)
+                .unwrap();
+        }
+        for (id, buffer) in &mut buffers {
+            buffer.complete_stops(id, &HashMap::new()).unwrap();
+        }
+
+        assert_eq!(
+            buffers,
+            map! {
+                "u4_0" => trip_buffers::u4::nollendorfplatz_innsbrucker_platz(time!(4:36:00))
+                    .with_stop_sequences(vec![1, 2, 3, 4, 5]),
+            }
+        );
+    }
+
+    #[test]
+    fn test_import_pass_through_stop() {
+        let mut pass_through = stop_record("u4_0", "hauptbahnhof", Some(time!(4:40:00)));
+        pass_through.pickup_type = Some(Boarding::Unavailable);
+        pass_through.drop_off_type = Some(Boarding::Unavailable);
+        let records = vec![
+            stop_record("u4_0", "nollendorfplatz", Some(time!(4:36:00))),
+            stop_record("u4_0", "viktoria_luise_platz", Some(time!(4:38:00))),
+            stop_record("u4_0", "bayerischer_platz", Some(time!(4:39:30))),
+            pass_through,
+            stop_record("u4_0", "rathaus_schoeneberg", Some(time!(4:41:00))),
+            stop_record("u4_0", "innsbrucker_platz", Some(time!(4:42:00))),
+        ];
+
+        let mut buffers = map! {
+            "u4_0" => trip_buffers::u4::empty(time!(0:00)),
+        };
+        for (line, record) in (2..).zip(records) {
+            record
+                .import(line, &locations::by_id(), &mut buffers)
+                .unwrap();
+        }
+        for (id, buffer) in &mut buffers {
+            buffer.complete_stops(id, &HashMap::new()).unwrap();
+        }
+
+        let buffer = &buffers[&"u4_0".into()];
+        assert_eq!(buffer.locations()[3], Rc::new(locations::hauptbahnhof()));
+        assert_eq!(
+            buffer.passed_through(),
+            [false, false, false, true, false, false]
+        );
+    }
+
+    #[test]
+    fn test_import_stop_without_exact_times() {
+        let mut record = stop_record("u4_0", "viktoria_luise_platz", None);
+        record.timepoint = Some(Timepoint::Exact);
+        let mut buffers = map! {
+            "u4_0" => trip_buffers::u4::empty(time!(0:00)),
+        };
+        let error = record
+            .import(3, &locations::by_id(), &mut buffers)
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "stop_times.txt, line 3: trip_id u4_0 lacks times at an exact timepoint"
+        );
+    }
+
+    #[test]
+    fn test_import_stop_of_unknown_trip() {
+        let record = stop_record("u4_1", "nollendorfplatz", Some(time!(4:36:00)));
+        let mut buffers = map! {
+            "u4_0" => trip_buffers::u4::empty(time!(0:00)),
+        };
+        let error = record
+            .import(7, &locations::by_id(), &mut buffers)
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "stop_times.txt, line 7: trip_id u4_1 not found"
+        );
+    }
+
+    #[test]
+    fn test_import_frequencies() {
+        let records = vec![
+            FrequencyRecord {
+                trip_id: "u4_0".into(),
+                start_time: Duration::seconds(time!(5:00:00)),
+                end_time: Duration::seconds(time!(9:00:00)),
+                headway_secs: 300,
+                exact_times: None,
+            },
+            FrequencyRecord {
+                trip_id: "u4_0".into(),
+                start_time: Duration::seconds(time!(9:00:00)),
+                end_time: Duration::seconds(time!(20:00:00)),
+                headway_secs: 600,
+                exact_times: Some(ExactTimes::ScheduleBased),
+            },
+        ];
+
+        let mut frequencies = HashMap::new();
+        for (line, record) in (2..).zip(records) {
+            record.import(line, &mut frequencies).unwrap();
+        }
+
+        assert_eq!(
+            frequencies,
+            map! {
+                "u4_0" => vec![
+                    Frequency::new(
+                        Duration::seconds(time!(5:00:00)),
+                        Duration::seconds(time!(9:00:00)),
+                        Duration::seconds(300),
+                        ExactTimes::FrequencyBased,
+                    ),
+                    Frequency::new(
+                        Duration::seconds(time!(9:00:00)),
+                        Duration::seconds(time!(20:00:00)),
+                        Duration::seconds(600),
+                        ExactTimes::ScheduleBased,
+                    ),
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn test_import_frequency_without_headway() {
+        let record = FrequencyRecord {
+            trip_id: "u4_0".into(),
+            start_time: Duration::seconds(time!(5:00:00)),
+            end_time: Duration::seconds(time!(9:00:00)),
+            headway_secs: 0,
+            exact_times: None,
+        };
+        let mut frequencies = HashMap::new();
+        let error = record.import(4, &mut frequencies).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "frequencies.txt, line 4: trip_id u4_0 has a headway which is not positive"
+        );
+        assert!(frequencies.is_empty());
+    }
 }
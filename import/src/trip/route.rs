@@ -1,23 +1,61 @@
+use std::collections::HashMap;
+use std::iter;
+use std::rc::Rc;
+
 use chrono::NaiveDate;
 
-use super::{Node, Scheduler, Trip};
-use crate::location::Linearizer;
+use super::trip::runs_past_midnight;
+use super::{plausibility, Implausibility, Node, Scheduler, Trip};
+use crate::line::LineId;
+use crate::location::{Linearizer, Location, LocationId};
+use crate::realtime::Realtime;
+use simulation::line::Kind;
+use simulation::Direction;
+
+/// Direction, start time, schedule and platforms of a stored train.
+type Run = (Direction, u32, usize, Vec<Option<usize>>);
 
 #[derive(Debug, PartialEq)]
 pub struct Route {
     nodes: Vec<Node>,
     trips: Vec<Trip>,
+    synthesized_shape: bool,
+    misplaced_stops: Vec<Implausibility>,
 }
 
 impl Route {
-    pub(super) fn new(nodes: Vec<Node>, trips: Vec<Trip>) -> Route {
-        Route { nodes, trips }
+    pub(super) fn new(
+        nodes: Vec<Node>,
+        trips: Vec<Trip>,
+        synthesized_shape: bool,
+        misplaced_stops: Vec<Implausibility>,
+    ) -> Route {
+        Route {
+            nodes,
+            trips,
+            synthesized_shape,
+            misplaced_stops,
+        }
     }
 
     pub fn nodes(&self) -> impl Iterator<Item = &Node> {
         self.nodes.iter()
     }
 
+    /// Whether the nodes follow a shape synthesized from the stops instead of one of the dataset.
+    pub fn has_synthesized_shape(&self) -> bool {
+        self.synthesized_shape
+    }
+
+    /// Checks the trips for speeds vehicles of the given kind cannot reach and for duplicates.
+    ///
+    /// Stops whose distance along the shape contradicts their position are reported as well.
+    pub fn implausibilities(&self, kind: Kind) -> Vec<Implausibility> {
+        let mut implausibilities = plausibility::analyze(&self.nodes, &self.trips, kind);
+        implausibilities.extend(self.misplaced_stops.iter().cloned());
+        implausibilities
+    }
+
     pub(crate) fn num_trips_at(&self, date: NaiveDate) -> usize {
         self.trips
             .iter()
@@ -32,16 +70,174 @@ impl Route {
             .collect()
     }
 
-    pub(crate) fn store_trains(
+    /// Whether the route stops at a location the predicate accepts.
+    pub(crate) fn stops_at(&self, accept: &impl Fn(&LocationId) -> bool) -> bool {
+        self.nodes
+            .iter()
+            .filter_map(Node::location)
+            .any(|location| accept(&location.id()))
+    }
+
+    /// Locations of the nodes accepted by the filter, in the order they are visited in the
+    /// direction.
+    fn locations<'a>(
+        &'a self,
+        direction: Direction,
+        location: impl Fn(&'a Node) -> Option<&'a Rc<Location>>,
+    ) -> Vec<&'a Rc<Location>> {
+        let mut locations = self
+            .nodes
+            .iter()
+            .filter(|node| node.in_directions().allows(direction))
+            .filter_map(location)
+            .collect::<Vec<_>>();
+        if direction == Direction::Downstream {
+            locations.reverse();
+        }
+        locations
+    }
+
+    /// Ids of the stops, including the ones passed through, in the order they are visited in the
+    /// direction.
+    fn stop_ids(&self, direction: Direction) -> Vec<LocationId> {
+        self.locations(direction, Node::timed_location)
+            .into_iter()
+            .map(|location| location.id())
+            .collect()
+    }
+
+    /// The platforms of the trip at the stops which are stored as stations of their own.
+    ///
+    /// At stations merged into another one, the platform would refer to the wrong complex.
+    fn platforms(trip: &Trip, stored: &[Vec<bool>; 2]) -> Vec<Option<usize>> {
+        let stored = match trip.direction() {
+            Direction::Upstream => &stored[0],
+            Direction::Downstream => &stored[1],
+        };
+        trip.platforms()
+            .iter()
+            .zip(stored)
+            .map(|(&platform, &stored)| platform.filter(|_| stored))
+            .collect()
+    }
+
+    /// Start times, schedules and platforms of the trains running at the date.
+    ///
+    /// If only overnight trains are requested, trains ending before midnight are left out.
+    fn runs_at(
         &self,
         date: NaiveDate,
+        overnight_only: bool,
+        stored: &[Vec<bool>; 2],
         scheduler: &mut Scheduler,
-    ) -> Vec<storage::Train> {
-        scheduler.update_weights(&self.nodes);
+    ) -> Vec<Run> {
         self.trips
             .iter()
             .filter(|trip| trip.available_at(date))
-            .map(|trip| trip.store(scheduler))
+            .filter(|trip| !overnight_only || trip.runs_past_midnight())
+            .map(|trip| {
+                let (start, schedule) = trip.store(scheduler);
+                let platforms = Self::platforms(trip, stored);
+                (trip.direction(), start, schedule, platforms)
+            })
+            .collect()
+    }
+
+    /// Start times, schedules and platforms of the trains as they actually ran at the date,
+    /// including trips added to one of the lines.
+    ///
+    /// Added trips are only taken into account if they call at the same stops as this route.
+    fn realtime_runs_at(
+        &self,
+        lines: &[LineId],
+        date: NaiveDate,
+        overnight_only: bool,
+        realtime: &Realtime,
+        stored: &[Vec<bool>; 2],
+        scheduler: &mut Scheduler,
+    ) -> Vec<Run> {
+        let upstream = self.stop_ids(Direction::Upstream);
+        let downstream = self.stop_ids(Direction::Downstream);
+        let stop_ids = |direction| match direction {
+            Direction::Upstream => &upstream,
+            Direction::Downstream => &downstream,
+        };
+
+        let mut runs = self
+            .trips
+            .iter()
+            .filter(|trip| trip.available_at(date))
+            .filter(|trip| !overnight_only || trip.runs_past_midnight())
+            .filter_map(|trip| {
+                let (start, schedule) =
+                    trip.store_realtime(stop_ids(trip.direction()), date, realtime, scheduler)?;
+                let platforms = Self::platforms(trip, stored);
+                Some((trip.direction(), start, schedule, platforms))
+            })
+            .collect::<Vec<_>>();
+        for &direction in &[Direction::Upstream, Direction::Downstream] {
+            for added in realtime.added_trips(lines, date, direction) {
+                if let Some(durations) = added.added_durations(stop_ids(direction)) {
+                    if !overnight_only || runs_past_midnight(&durations) {
+                        let (start, schedule) = scheduler.process(direction, &durations);
+                        runs.push((direction, start, schedule, Vec::new()));
+                    }
+                }
+            }
+        }
+        runs
+    }
+
+    /// Stores the trains running at one of the dates, each marked with the days it runs.
+    ///
+    /// Trains of the day before the first date are stored as well if they run past midnight.
+    /// With realtime data, the trains are stored as they actually ran. Trains running the same
+    /// schedule at the same time on several days are stored once.
+    ///
+    /// The linearizer tells which stations are stored as themselves, it must have retrieved the
+    /// stops of the route.
+    pub(crate) fn store_trains(
+        &self,
+        lines: &[LineId],
+        dates: &[NaiveDate],
+        realtime: Option<&Realtime>,
+        linearizer: &Linearizer,
+        scheduler: &mut Scheduler,
+    ) -> Vec<storage::Train> {
+        scheduler.update_weights(&self.nodes);
+        let stored = [Direction::Upstream, Direction::Downstream].map(|direction| {
+            self.locations(direction, Node::location)
+                .into_iter()
+                .map(|location| linearizer.represents(location))
+                .collect::<Vec<_>>()
+        });
+        let mut runs = Vec::new();
+        let mut days = HashMap::new();
+        let eve = (dates[0].pred(), true);
+        let dates = iter::once(eve).chain(dates.iter().map(|&date| (date, false)));
+        for (bit, (date, overnight_only)) in dates.enumerate() {
+            let runs_at = match realtime {
+                Some(realtime) => {
+                    self.realtime_runs_at(lines, date, overnight_only, realtime, &stored, scheduler)
+                }
+                None => self.runs_at(date, overnight_only, &stored, scheduler),
+            };
+            for run in runs_at {
+                match days.get_mut(&run) {
+                    Some(days) => *days |= 1 << bit,
+                    None => {
+                        runs.push(run.clone());
+                        days.insert(run, 1 << bit);
+                    }
+                }
+            }
+        }
+        runs.into_iter()
+            .map(|run| {
+                let days = days[&run];
+                let (direction, start, schedule, platforms) = run;
+                storage::Train::new(direction, start, schedule, days).with_platforms(platforms)
+            })
             .collect()
     }
 }
@@ -69,6 +265,8 @@ pub(crate) mod fixtures {
                             Route {
                                 nodes: nodes::$line::$route(Directions::Both),
                                 trips,
+                                synthesized_shape: false,
+                                misplaced_stops: Vec::new(),
                             }
                         }
                     )*
@@ -93,3 +291,160 @@ pub(crate) mod fixtures {
         },
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use chrono::FixedOffset;
+
+    use super::*;
+    use crate::fixtures::{locations, routes, trips};
+    use test_utils::{map, time};
+
+    fn realtime() -> Realtime {
+        Realtime::read(
+            "tests/data/realtime/trip_updates.pb",
+            FixedOffset::east(0),
+            None,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_store_realtime_trains() {
+        let route = routes::tram_12::oranienburger_tor_am_kupfergraben();
+        let date = NaiveDate::from_ymd(2019, 1, 7);
+        let mut scheduler = Scheduler::new();
+        let trains = route.store_trains(
+            &["4".into()],
+            &[date],
+            Some(&realtime()),
+            &Linearizer::new(),
+            &mut scheduler,
+        );
+        assert_eq!(
+            trains,
+            vec![
+                storage::Train::new(Direction::Upstream, time!(9:01:40), 0, 0b10),
+                storage::Train::new(Direction::Upstream, time!(10:01:40), 1, 0b10),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_store_realtime_trains_of_other_date() {
+        let route = routes::tram_12::oranienburger_tor_am_kupfergraben();
+        let date = NaiveDate::from_ymd(2019, 1, 8);
+        let mut scheduler = Scheduler::new();
+        assert_eq!(
+            route.store_trains(
+                &["4".into()],
+                &[date],
+                Some(&realtime()),
+                &Linearizer::new(),
+                &mut scheduler
+            ),
+            route.store_trains(
+                &["4".into()],
+                &[date],
+                None,
+                &Linearizer::new(),
+                &mut Scheduler::new()
+            )
+        );
+    }
+
+    #[test]
+    fn test_store_trains_with_platforms() {
+        let mut route = routes::tram_12::oranienburger_tor_am_kupfergraben();
+        let trip = route.trips.remove(0);
+        route.trips = vec![trip.with_platforms(vec![Some(1), None, None, Some(0)])];
+        let date = NaiveDate::from_ymd(2019, 1, 7);
+        let mut linearizer = Linearizer::new();
+        route.store_nodes(&mut linearizer);
+        assert_eq!(
+            route.store_trains(
+                &["4".into()],
+                &[date],
+                None,
+                &linearizer,
+                &mut Scheduler::new()
+            ),
+            vec![
+                storage::Train::new(Direction::Upstream, time!(9:01:40), 0, 0b10)
+                    .with_platforms(vec![Some(1), None, None, Some(0)])
+            ]
+        );
+
+        // the platforms of merged stations are left out
+        let merged_stations = map! { "oranienburger_tor" => "friedrichstr".into() };
+        let mut linearizer = Linearizer::with_merged_stations(merged_stations);
+        linearizer.retrieve(&Rc::new(locations::friedrichstr()));
+        route.store_nodes(&mut linearizer);
+        assert_eq!(
+            route.store_trains(
+                &["4".into()],
+                &[date],
+                None,
+                &linearizer,
+                &mut Scheduler::new()
+            ),
+            vec![
+                storage::Train::new(Direction::Upstream, time!(9:01:40), 0, 0b10)
+                    .with_platforms(vec![None, None, None, Some(0)])
+            ]
+        );
+    }
+
+    #[test]
+    fn test_store_trains_of_several_days() {
+        let route = routes::tram_12::oranienburger_tor_am_kupfergraben();
+        // friday, saturday and monday
+        let dates = [
+            NaiveDate::from_ymd(2019, 1, 11),
+            NaiveDate::from_ymd(2019, 1, 12),
+            NaiveDate::from_ymd(2019, 1, 14),
+        ];
+        let mut scheduler = Scheduler::new();
+        assert_eq!(
+            route.store_trains(
+                &["4".into()],
+                &dates,
+                None,
+                &Linearizer::new(),
+                &mut scheduler
+            ),
+            vec![
+                storage::Train::new(Direction::Upstream, time!(9:01:40), 0, 0b1010),
+                storage::Train::new(Direction::Downstream, time!(8:33:40), 1, 0b1010),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_store_trains_of_previous_night() {
+        let mut route = routes::tram_12::oranienburger_tor_am_kupfergraben();
+        route
+            .trips
+            .push(trips::tram_12::oranienburger_tor_am_kupfergraben(
+                time!(23:58:00),
+            ));
+        // saturday, the trips only run on weekdays
+        let dates = [NaiveDate::from_ymd(2019, 1, 12)];
+        let mut scheduler = Scheduler::new();
+        assert_eq!(
+            route.store_trains(
+                &["4".into()],
+                &dates,
+                None,
+                &Linearizer::new(),
+                &mut scheduler
+            ),
+            vec![storage::Train::new(
+                Direction::Upstream,
+                time!(23:57:40),
+                0,
+                0b1
+            )]
+        );
+    }
+}
@@ -19,21 +19,34 @@ impl RouteBuffer {
         }
     }
 
-    pub(super) fn add_trip(&mut self, locations: Vec<Rc<Location>>, shape: &Shape, trip: Trip) {
+    pub(super) fn add_trip(
+        &mut self,
+        locations: Vec<Rc<Location>>,
+        passed_through: Vec<bool>,
+        shape_distances: Option<Vec<f64>>,
+        shape: &Shape,
+        trip: Trip,
+    ) {
         let variants = match trip.direction() {
             Direction::Upstream => &mut self.upstream,
             Direction::Downstream => &mut self.downstream,
         };
-        let variant = variants
-            .iter_mut()
-            .find(|variant| variant.matches(&locations, shape));
+        let variant = variants.iter_mut().find(|variant| {
+            variant.matches(
+                &locations,
+                &passed_through,
+                shape_distances.as_deref(),
+                shape,
+            )
+        });
 
         match variant {
             Some(variant) => {
                 variant.add_trip(trip);
             }
             None => {
-                let mut variant = RouteVariant::new(locations, shape.clone());
+                let mut variant =
+                    RouteVariant::new(locations, passed_through, shape_distances, shape.clone());
                 variant.add_trip(trip);
                 variants.push(variant);
             }
@@ -133,6 +146,8 @@ mod tests {
         let trip = trips::tram_12::oranienburger_tor_am_kupfergraben(time!(9:02:00));
         buffer.add_trip(
             stop_locations::tram_12::oranienburger_tor_am_kupfergraben(),
+            Vec::new(),
+            None,
             &shapes::tram_12::oranienburger_tor_am_kupfergraben(),
             trip,
         );
@@ -145,6 +160,8 @@ mod tests {
         let trip = trips::tram_12::am_kupfergraben_oranienburger_tor(time!(8:34:00));
         buffer.add_trip(
             stop_locations::tram_12::am_kupfergraben_oranienburger_tor(),
+            Vec::new(),
+            None,
             &shapes::tram_12::am_kupfergraben_oranienburger_tor(),
             trip,
         );
@@ -157,6 +174,8 @@ mod tests {
         let trip = trips::tram_12::oranienburger_tor_am_kupfergraben(time!(9:12:00));
         buffer.add_trip(
             stop_locations::tram_12::oranienburger_tor_am_kupfergraben(),
+            Vec::new(),
+            None,
             &shapes::tram_12::oranienburger_tor_am_kupfergraben(),
             trip,
         );
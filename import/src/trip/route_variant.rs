@@ -3,11 +3,12 @@ use std::rc::Rc;
 
 use ordered_float::NotNan;
 
-use super::{Node, Route, Trip};
+use super::{Implausibility, Node, Route, Trip};
+use crate::coord::Point;
 use crate::location::Location;
 use crate::shape::Shape;
 use itertools::Itertools;
-use simulation::Direction;
+use simulation::{Direction, Directions};
 
 struct StopCandidate {
     pos: usize,
@@ -101,24 +102,129 @@ impl StopCandidate {
     }
 }
 
+/// Where a stop lies on the shape according to the distance travelled to it.
+struct LinearStop {
+    position: Point,
+    /// Index of the first shape point not before the stop.
+    next: usize,
+    on_point: bool,
+}
+
+impl LinearStop {
+    /// Locates the stops by linear reference, if the distances are consistently increasing.
+    fn locate(
+        points: &[Point],
+        point_distances: &[f64],
+        stop_distances: &[f64],
+    ) -> Option<Vec<Self>> {
+        let increasing = |distances: &[f64]| distances.iter().tuple_windows().all(|(a, b)| a <= b);
+        if points.is_empty() || !increasing(point_distances) || !increasing(stop_distances) {
+            return None;
+        }
+
+        let last = points.len() - 1;
+        let mut next = 0;
+        let stops = stop_distances
+            .iter()
+            .map(|&distance| {
+                while next <= last && point_distances[next] < distance {
+                    next += 1;
+                }
+                if next == 0 || next > last || point_distances[next] == distance {
+                    next = next.min(last);
+                    return Self {
+                        position: points[next],
+                        next,
+                        on_point: true,
+                    };
+                }
+
+                let (before, after) = (point_distances[next - 1], point_distances[next]);
+                let fraction = (distance - before) / (after - before);
+                Self {
+                    position: points[next - 1] + (points[next] - points[next - 1]) * fraction,
+                    next,
+                    on_point: false,
+                }
+            })
+            .collect();
+        Some(stops)
+    }
+
+    /// Creates the nodes along the shape, inserting new ones for stops between shape points.
+    fn place(
+        stops: &[Self],
+        points: &[Point],
+        locations: &[Rc<Location>],
+        directions: Directions,
+    ) -> Vec<Node> {
+        let mut nodes = Vec::with_capacity(points.len() + stops.len());
+        let mut i = 0;
+        for (stop, location) in stops.iter().zip(locations) {
+            while i < stop.next {
+                nodes.push(Node::new(points[i], directions));
+                i += 1;
+            }
+            if stop.on_point && i == stop.next {
+                i += 1;
+            }
+            let mut node = Node::new(stop.position, directions);
+            node.make_stop(Rc::clone(location));
+            nodes.push(node);
+        }
+        nodes.extend(
+            points[i..]
+                .iter()
+                .map(|&position| Node::new(position, directions)),
+        );
+        nodes
+    }
+
+    /// Whether the position chosen for the stop by the nearest distance lies on the same segment
+    /// of the shape, both given as distance travelled along the shape points.
+    fn agrees_with(&self, along: f64, travelled: &[f64]) -> bool {
+        travelled[self.next.saturating_sub(1)] <= along && along <= travelled[self.next]
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub(super) struct RouteVariant {
     locations: Vec<Rc<Location>>,
+    /// Whether the trips pass through each stop without stopping, empty if they stop at all.
+    passed_through: Vec<bool>,
+    /// Distances travelled along the shape up to each stop, if the dataset gives them.
+    shape_distances: Option<Vec<f64>>,
     shape: Shape,
     trips: Vec<Trip>,
 }
 
 impl RouteVariant {
-    pub(super) fn new(locations: Vec<Rc<Location>>, shape: Shape) -> Self {
+    pub(super) fn new(
+        locations: Vec<Rc<Location>>,
+        passed_through: Vec<bool>,
+        shape_distances: Option<Vec<f64>>,
+        shape: Shape,
+    ) -> Self {
         Self {
             locations,
+            passed_through,
+            shape_distances,
             shape,
             trips: Vec::new(),
         }
     }
 
-    pub(super) fn matches(&self, locations: &[Rc<Location>], shape: &Shape) -> bool {
-        self.locations == locations && &self.shape == shape
+    pub(super) fn matches(
+        &self,
+        locations: &[Rc<Location>],
+        passed_through: &[bool],
+        shape_distances: Option<&[f64]>,
+        shape: &Shape,
+    ) -> bool {
+        self.locations == locations
+            && self.passed_through == passed_through
+            && self.shape_distances.as_deref() == shape_distances
+            && &self.shape == shape
     }
 
     pub(super) fn difference(&self, downstream: &Self) -> impl Ord {
@@ -154,28 +260,104 @@ impl RouteVariant {
         self.trips.push(trip);
     }
 
-    fn nodes(&self, direction: Direction) -> Vec<Node> {
-        let mut nodes = self
+    fn linear_stops(&self) -> Option<Vec<LinearStop>> {
+        let point_distances = self.shape.distances()?;
+        let stop_distances = self.shape_distances.as_ref()?;
+        LinearStop::locate(self.shape.points(), point_distances, stop_distances)
+    }
+
+    fn nearest_candidates(&self, directions: Directions) -> (Vec<Node>, Vec<StopCandidate>) {
+        let nodes = self
             .shape
             .iter_count(self.locations.len())
-            .map(|position| Node::new(position, direction.into()))
+            .map(|position| Node::new(position, directions))
             .collect::<Vec<_>>();
+        let candidates = StopCandidate::distribute_across(&nodes, &self.locations);
+        (nodes, candidates)
+    }
 
-        for candidate in StopCandidate::distribute_across(&nodes, &self.locations) {
-            candidate.accept(&mut nodes);
+    fn nodes(&self, direction: Direction) -> Vec<Node> {
+        let mut nodes = self.stop_nodes(direction);
+        let stops = nodes.iter_mut().filter(|node| node.location().is_some());
+        for (node, &passed_through) in stops.zip(&self.passed_through) {
+            if passed_through {
+                node.make_pass_through();
+            }
+        }
+        nodes
+    }
+
+    fn stop_nodes(&self, direction: Direction) -> Vec<Node> {
+        if let Some(stops) = self.linear_stops() {
+            return LinearStop::place(
+                &stops,
+                self.shape.points(),
+                &self.locations,
+                direction.into(),
+            );
         }
 
+        if let Some(stops) = self.shape.synthesized_stops() {
+            let mut nodes = self
+                .shape
+                .iter_count(self.locations.len())
+                .map(|position| Node::new(position, direction.into()))
+                .collect::<Vec<_>>();
+            for (location, &pos) in self.locations.iter().zip(stops) {
+                nodes[pos].make_stop(Rc::clone(location));
+            }
+            return nodes;
+        }
+
+        let (mut nodes, candidates) = self.nearest_candidates(direction.into());
+        for candidate in candidates {
+            candidate.accept(&mut nodes);
+        }
         nodes
     }
 
+    /// Compares the placement of stops by linear reference with the one by nearest distance.
+    fn misplaced_stops(&self) -> Vec<Implausibility> {
+        let stops = match self.linear_stops() {
+            Some(stops) => stops,
+            None => return Vec::new(),
+        };
+        let (nodes, candidates) = self.nearest_candidates(Directions::Both);
+        let travelled = iter::once(0.0)
+            .chain(nodes.iter().tuple_windows().scan(0.0, |travelled, (a, b)| {
+                *travelled += na::distance(&a.position(), &b.position());
+                Some(*travelled)
+            }))
+            .collect::<Vec<_>>();
+        stops
+            .iter()
+            .zip(candidates)
+            .filter(|(stop, candidate)| !stop.agrees_with(travelled[candidate.pos], &travelled))
+            .map(|(stop, candidate)| Implausibility::MisplacedStop {
+                stop: candidate.location.name().to_string(),
+                distance: na::distance(&stop.position, &nodes[candidate.pos].position()),
+            })
+            .collect()
+    }
+
     pub(super) fn single(self, direction: Direction) -> Route {
-        Route::new(self.nodes(direction), self.trips)
+        let synthesized_shape = self.shape.is_synthesized();
+        let misplaced_stops = self.misplaced_stops();
+        Route::new(
+            self.nodes(direction),
+            self.trips,
+            synthesized_shape,
+            misplaced_stops,
+        )
     }
 
     pub(super) fn merge(mut self, mut downstream: Self) -> Route {
         let nodes = self.merge_nodes(&downstream);
+        let synthesized_shape = self.shape.is_synthesized() || downstream.shape.is_synthesized();
+        let mut misplaced_stops = self.misplaced_stops();
+        misplaced_stops.extend(downstream.misplaced_stops());
         self.trips.append(&mut downstream.trips);
-        Route::new(nodes, self.trips)
+        Route::new(nodes, self.trips, synthesized_shape, misplaced_stops)
     }
 
     fn merge_nodes(&self, downstream: &Self) -> Vec<Node> {
@@ -223,6 +405,8 @@ pub(crate) mod fixtures {
                         pub(in crate::trip) fn $name() -> RouteVariant {
                             RouteVariant {
                                 locations: stop_locations::$line::$route(),
+                                passed_through: Vec::new(),
+                                shape_distances: None,
                                 shape: shapes::$line::$route(),
                                 trips: route_variants!(@trips $line, $route, $times),
                             }
@@ -256,6 +440,7 @@ pub(crate) mod fixtures {
 mod tests {
     use super::*;
     use crate::fixtures::{nodes, shapes, stop_locations};
+    use crate::shape::FallbackMode;
     use simulation::Directions;
     use test_utils::assert_eq_alternate;
 
@@ -264,8 +449,12 @@ mod tests {
             test_nodes!($line::$route, $line::$route, $direction)
         }};
         ($line:ident :: $route:ident, $line_nodes:ident :: $nodes:ident, $direction:ident) => {{
-            let variant =
-                RouteVariant::new(stop_locations::$line::$route(), shapes::$line::$route());
+            let variant = RouteVariant::new(
+                stop_locations::$line::$route(),
+                Vec::new(),
+                None,
+                shapes::$line::$route(),
+            );
             let directions = Directions::from(Direction::$direction);
             let mut expected_nodes = nodes::$line_nodes::$nodes(directions);
             if Direction::$direction == Direction::Downstream {
@@ -303,4 +492,115 @@ mod tests {
     fn test_nodes_lasso() {
         test_nodes!(bus_114::wannsee_heckeshorn_wannsee, Upstream);
     }
+
+    #[test]
+    fn test_nodes_pass_through() {
+        let variant = RouteVariant::new(
+            stop_locations::tram_12::oranienburger_tor_am_kupfergraben(),
+            vec![false, true, false, false],
+            None,
+            shapes::tram_12::oranienburger_tor_am_kupfergraben(),
+        );
+        let mut expected_nodes =
+            nodes::tram_12::oranienburger_tor_am_kupfergraben(Directions::UpstreamOnly);
+        expected_nodes
+            .iter_mut()
+            .filter(|node| node.location().is_some())
+            .nth(1)
+            .unwrap()
+            .make_pass_through();
+        assert_eq_alternate!(variant.nodes(Direction::Upstream), expected_nodes);
+    }
+
+    #[test]
+    fn test_nodes_synthesized_shape() {
+        let locations = stop_locations::bus_114::wannsee_heckeshorn_wannsee();
+        let shape = FallbackMode::Densified.synthesize(&locations);
+        let variant = RouteVariant::new(locations.clone(), Vec::new(), None, shape);
+        let stops = variant
+            .nodes(Direction::Upstream)
+            .into_iter()
+            .filter_map(|node| node.location().cloned())
+            .collect::<Vec<_>>();
+        assert_eq!(stops, locations);
+    }
+
+    fn tram_12_measured(stop_distances: Vec<f64>) -> RouteVariant {
+        let shape = shapes::tram_12::oranienburger_tor_am_kupfergraben()
+            .with_distances((0..10).map(|i| f64::from(i) * 100.0).collect());
+        RouteVariant::new(
+            stop_locations::tram_12::oranienburger_tor_am_kupfergraben(),
+            Vec::new(),
+            Some(stop_distances),
+            shape,
+        )
+    }
+
+    #[test]
+    fn test_nodes_linear_reference() {
+        let variant = tram_12_measured(vec![0.0, 300.0, 850.0, 900.0]);
+        let nodes = variant.nodes(Direction::Upstream);
+        let points = variant.shape.points();
+        assert_eq!(nodes.len(), 11);
+        let stops = nodes
+            .iter()
+            .enumerate()
+            .filter_map(|(i, node)| Some((i, node.location()?.name())))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            stops,
+            [
+                (0, "Oranienburger Tor"),
+                (3, "Friedrichstr."),
+                (9, "Universitätsstr."),
+                (10, "Am Kupfergraben"),
+            ]
+        );
+        assert_eq!(nodes[8].position(), points[8]);
+        assert_eq!(
+            nodes[9].position(),
+            points[8] + (points[9] - points[8]) * 0.5
+        );
+        assert_eq!(nodes[10].position(), points[9]);
+        assert_eq!(variant.misplaced_stops(), Vec::new());
+    }
+
+    #[test]
+    fn test_nodes_linear_reference_fallback() {
+        let variant = tram_12_measured(vec![0.0, 300.0, 200.0, 900.0]);
+        assert_eq_alternate!(
+            variant.nodes(Direction::Upstream),
+            nodes::tram_12::oranienburger_tor_am_kupfergraben(Directions::UpstreamOnly)
+        );
+        assert_eq!(variant.misplaced_stops(), Vec::new());
+    }
+
+    #[test]
+    fn test_misplaced_stops() {
+        let variant = tram_12_measured(vec![0.0, 600.0, 850.0, 900.0]);
+        let misplaced_stops = variant.misplaced_stops();
+        assert_eq!(misplaced_stops.len(), 1);
+        match &misplaced_stops[0] {
+            Implausibility::MisplacedStop { stop, distance } => {
+                assert_eq!(stop, "Friedrichstr.");
+                assert!(*distance > 100.0);
+            }
+            implausibility => panic!("unexpected {:?}", implausibility),
+        }
+    }
+
+    #[test]
+    fn test_no_misplaced_stops_beyond_shape() {
+        let locations = stop_locations::tram_12::oranienburger_tor_am_kupfergraben();
+        let (first, last) = (&locations[0], &locations[locations.len() - 1]);
+        let shape =
+            Shape::from(vec![first.position(), last.position()]).with_distances(vec![0.0, 900.0]);
+        let variant = RouteVariant::new(
+            vec![Rc::clone(first), Rc::clone(last), Rc::clone(last)],
+            Vec::new(),
+            Some(vec![0.0, 900.0, 900.0]),
+            shape,
+        );
+        assert_eq!(variant.misplaced_stops(), Vec::new());
+    }
 }
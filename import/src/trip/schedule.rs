@@ -87,14 +87,22 @@ impl Schedule {
         }
     }
 
+    /// Lengthens stops shorter than the minimum stop duration, taking the time from driving.
+    ///
+    /// Stops which are passed through keep their duration.
     pub(super) fn adjust_stop_durations<'a>(
         &mut self,
         weights: impl Iterator<Item = f64> + 'a,
+        passed_through: &[bool],
     ) -> i32 {
         let added_stop_durations = self
             .driving_durations
             .iter_mut()
-            .map(|(stopping, _)| {
+            .enumerate()
+            .map(|(i, (stopping, _))| {
+                if passed_through.get(i) == Some(&true) {
+                    return 0;
+                }
                 let missing_stop_duration = Self::MINIMUM_STOP_DURATION.saturating_sub(*stopping);
                 *stopping += missing_stop_duration;
                 missing_stop_duration
@@ -144,7 +152,7 @@ mod tests {
             &nodes::s3::hackescher_markt_bellevue(Directions::Both),
             Direction::Upstream,
         );
-        let start_time_offset = schedule.adjust_stop_durations(weights.into_iter());
+        let start_time_offset = schedule.adjust_stop_durations(weights.into_iter(), &[]);
         assert_eq!(start_time_offset, 0);
         let expected_durations = times![0:30, 1:30, 0:48, 1:54, 0:36, 2:06, 0:30];
         assert_eq!(schedule, Schedule::new(expected_durations.into_iter()));
@@ -158,7 +166,7 @@ mod tests {
             &nodes::tram_12::oranienburger_tor_am_kupfergraben(Directions::Both),
             Direction::Upstream,
         );
-        let start_time_offset = schedule.adjust_stop_durations(weights.into_iter());
+        let start_time_offset = schedule.adjust_stop_durations(weights.into_iter(), &[]);
         assert_eq!(start_time_offset, -20);
         let expected_durations = times![0:20, 2:15, 0:20, 1:05, 0:20, 1:00, 0:20];
         assert_eq!(schedule, Schedule::new(expected_durations.into_iter()));
@@ -172,9 +180,24 @@ mod tests {
             &nodes::tram_12::oranienburger_tor_am_kupfergraben(Directions::Both),
             Direction::Downstream,
         );
-        let start_time_offset = schedule.adjust_stop_durations(weights.into_iter());
+        let start_time_offset = schedule.adjust_stop_durations(weights.into_iter(), &[]);
         assert_eq!(start_time_offset, -20);
         let expected_durations = times![0:20, 1:15, 0:20, 1:33, 0:20, 2:32, 0:20];
         assert_eq!(schedule, Schedule::new(expected_durations.into_iter()));
     }
+
+    #[test]
+    fn test_pass_through_keeps_stop_time() {
+        let durations = times![0:00, 2:00, 0:00, 2:00, 0:00, 1:00, 0:00];
+        let mut schedule = Schedule::new(durations.into_iter());
+        let weights = Node::segment_weights(
+            &nodes::tram_12::oranienburger_tor_am_kupfergraben(Directions::Both),
+            Direction::Upstream,
+        );
+        let start_time_offset =
+            schedule.adjust_stop_durations(weights.into_iter(), &[false, true, false, false]);
+        assert_eq!(start_time_offset, -20);
+        let expected_durations = times![0:20, 2:00, 0:00, 1:25, 0:20, 1:15, 0:20];
+        assert_eq!(schedule, Schedule::new(expected_durations.into_iter()));
+    }
 }
@@ -10,6 +10,8 @@ use simulation::Direction;
 pub(crate) struct Scheduler {
     upstream_weights: Vec<f64>,
     downstream_weights: Vec<f64>,
+    upstream_passed_through: Vec<bool>,
+    downstream_passed_through: Vec<bool>,
     schedules: HashMap<Schedule, usize>,
 }
 
@@ -18,6 +20,8 @@ impl Scheduler {
         Self {
             upstream_weights: Vec::new(),
             downstream_weights: Vec::new(),
+            upstream_passed_through: Vec::new(),
+            downstream_passed_through: Vec::new(),
             schedules: HashMap::new(),
         }
     }
@@ -25,6 +29,8 @@ impl Scheduler {
     pub(super) fn update_weights(&mut self, nodes: &[Node]) {
         self.upstream_weights = Node::segment_weights(nodes, Direction::Upstream);
         self.downstream_weights = Node::segment_weights(nodes, Direction::Downstream);
+        self.upstream_passed_through = Node::passed_through(nodes, Direction::Upstream);
+        self.downstream_passed_through = Node::passed_through(nodes, Direction::Downstream);
     }
 
     fn weights(&self, direction: Direction) -> impl Iterator<Item = f64> + '_ {
@@ -40,7 +46,12 @@ impl Scheduler {
             .map(|duration| duration.num_seconds() as u32);
         let start_time = durations.next().unwrap();
         let mut schedule = Schedule::new(durations);
-        let start_time_offset = schedule.adjust_stop_durations(self.weights(direction));
+        let passed_through = match direction {
+            Direction::Upstream => &self.upstream_passed_through,
+            Direction::Downstream => &self.downstream_passed_through,
+        };
+        let start_time_offset =
+            schedule.adjust_stop_durations(self.weights(direction), passed_through);
         let len = self.schedules.len();
         let schedule_id = *self.schedules.entry(schedule).or_insert(len);
         ((start_time as i32 + start_time_offset) as u32, schedule_id)
@@ -0,0 +1,399 @@
+use std::rc::Rc;
+
+use chrono::Duration;
+use itertools::Itertools;
+use ordered_float::NotNan;
+
+use super::TripId;
+use crate::location::Location;
+use crate::shape::Shape;
+use crate::ImportError;
+
+/// A stop of a trip as listed in `stop_times.txt`, whose times may still be missing.
+#[derive(Debug, PartialEq)]
+pub(super) struct StopTime {
+    line: u64,
+    sequence: Option<u32>,
+    location: Rc<Location>,
+    arrival: Option<Duration>,
+    departure: Option<Duration>,
+    shape_distance: Option<f64>,
+    pass_through: bool,
+    platform: Option<usize>,
+}
+
+impl StopTime {
+    pub(super) fn new(
+        line: u64,
+        sequence: Option<u32>,
+        location: Rc<Location>,
+        arrival: Option<Duration>,
+        departure: Option<Duration>,
+        shape_distance: Option<f64>,
+        pass_through: bool,
+    ) -> Self {
+        Self {
+            line,
+            sequence,
+            location,
+            arrival: arrival.or(departure),
+            departure: departure.or(arrival),
+            shape_distance,
+            pass_through,
+            platform: None,
+        }
+    }
+
+    /// Sets the platform of the stop, as index into the places of the complex of its station.
+    pub(super) fn with_platform(mut self, platform: Option<usize>) -> Self {
+        self.platform = platform;
+        self
+    }
+
+    pub(super) fn sequence(&self) -> Option<u32> {
+        self.sequence
+    }
+
+    pub(super) fn location(&self) -> &Rc<Location> {
+        &self.location
+    }
+
+    pub(super) fn arrival(&self) -> Duration {
+        self.arrival.unwrap()
+    }
+
+    pub(super) fn departure(&self) -> Duration {
+        self.departure.unwrap()
+    }
+
+    /// Distance travelled along the shape up to this stop, as given by `shape_dist_traveled`.
+    pub(super) fn shape_distance(&self) -> Option<f64> {
+        self.shape_distance
+    }
+
+    pub(super) fn platform(&self) -> Option<usize> {
+        self.platform
+    }
+
+    /// Whether passengers can neither board nor alight at this stop.
+    pub(super) fn is_pass_through(&self) -> bool {
+        self.pass_through
+    }
+
+    /// Distances in meters the stops are apart from the first one.
+    ///
+    /// The distances are taken from the dataset if given and increasing for every stop. Otherwise
+    /// they are measured along the shape if there is one and along straight lines between the
+    /// stops.
+    fn distances(stop_times: &[Self], shape: Option<&Shape>) -> Vec<f64> {
+        let given: Option<Vec<_>> = stop_times.iter().map(Self::shape_distance).collect();
+        if let Some(distances) = given {
+            if distances.iter().tuple_windows().all(|(a, b)| a <= b) {
+                return distances;
+            }
+        }
+
+        let points = shape.map_or(&[][..], Shape::points);
+        if points.len() < 2 {
+            let mut distance = 0.0;
+            let mut distances = vec![0.0];
+            for (before, after) in stop_times.iter().tuple_windows() {
+                distance += na::distance(&before.location.position(), &after.location.position());
+                distances.push(distance);
+            }
+            return distances;
+        }
+
+        let mut travelled = vec![0.0];
+        for (before, after) in points.iter().tuple_windows() {
+            travelled.push(travelled.last().unwrap() + na::distance(before, after));
+        }
+
+        // each stop is projected onto the shape no earlier than the stop before
+        let mut segment = 0;
+        let mut previous = 0.0;
+        stop_times
+            .iter()
+            .map(|stop_time| {
+                let position = stop_time.location.position();
+                let (index, distance, _) = (segment..points.len() - 1)
+                    .map(|i| {
+                        let (start, end) = (points[i], points[i + 1]);
+                        let length = travelled[i + 1] - travelled[i];
+                        let fraction = if length > 0.0 {
+                            (position - start).dot(&(end - start)) / (length * length)
+                        } else {
+                            0.0
+                        };
+                        let along = fraction.clamp(0.0, 1.0) * length;
+                        let along = along.max(previous - travelled[i]);
+                        let projected = if length > 0.0 {
+                            start + (end - start) * (along / length)
+                        } else {
+                            start
+                        };
+                        let offset = NotNan::new(na::distance(&projected, &position)).unwrap();
+                        (i, travelled[i] + along, offset)
+                    })
+                    .min_by_key(|&(_, _, offset)| offset)
+                    .unwrap();
+                segment = index;
+                previous = distance;
+                distance
+            })
+            .collect()
+    }
+
+    /// Orders the stops by their sequence and fills in missing times.
+    ///
+    /// Missing times are interpolated between the surrounding stops with times, proportional to
+    /// the distance travelled.
+    pub(super) fn complete(
+        stop_times: &mut [Self],
+        shape: Option<&Shape>,
+        trip_id: &TripId,
+    ) -> Result<(), ImportError> {
+        stop_times.sort_by_key(|stop_time| (stop_time.sequence, stop_time.line));
+        let terminals = [stop_times.first(), stop_times.last()];
+        for stop_time in terminals.iter().flatten() {
+            if stop_time.arrival.is_none() {
+                return Err(ImportError::InvalidRecord {
+                    file: "stop_times.txt",
+                    line: stop_time.line,
+                    field: "trip_id",
+                    id: trip_id.to_string(),
+                    reason: "lacks times at its first or last stop",
+                });
+            }
+        }
+        if stop_times
+            .iter()
+            .all(|stop_time| stop_time.arrival.is_some())
+        {
+            return Ok(());
+        }
+
+        let distances = Self::distances(stop_times, shape);
+        let mut last_timed = 0;
+        for i in 1..stop_times.len() {
+            if stop_times[i].arrival.is_none() {
+                continue;
+            }
+
+            let start = stop_times[last_timed].departure();
+            let duration = (stop_times[i].arrival() - start).num_seconds() as f64;
+            let (from, to) = (distances[last_timed], distances[i]);
+            for j in last_timed + 1..i {
+                let fraction = if to > from {
+                    (distances[j] - from) / (to - from)
+                } else {
+                    (j - last_timed) as f64 / (i - last_timed) as f64
+                };
+                let time = start + Duration::seconds((duration * fraction).round() as i64);
+                stop_times[j].arrival = Some(time);
+                stop_times[j].departure = Some(time);
+            }
+            last_timed = i;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{locations, shapes};
+    use test_utils::time;
+
+    fn stop_time(line: u64, location: Location, time: Option<Duration>) -> StopTime {
+        StopTime::new(line, None, Rc::new(location), time, time, None, false)
+    }
+
+    fn times(stop_times: &[StopTime]) -> Vec<Duration> {
+        stop_times.iter().map(StopTime::arrival).collect()
+    }
+
+    #[test]
+    fn test_single_time() {
+        let location = Rc::new(locations::nollendorfplatz());
+        let stop_time = StopTime::new(
+            2,
+            None,
+            location,
+            None,
+            Some(time!(Duration; 4:36:00)),
+            None,
+            false,
+        );
+        assert_eq!(stop_time.arrival(), time!(Duration; 4:36:00));
+        assert_eq!(stop_time.departure(), time!(Duration; 4:36:00));
+    }
+
+    #[test]
+    fn test_sort_by_sequence() {
+        let mut stop_times = vec![
+            StopTime::new(
+                2,
+                Some(3),
+                Rc::new(locations::bayerischer_platz()),
+                Some(time!(Duration; 4:39:30)),
+                Some(time!(Duration; 4:39:30)),
+                None,
+                false,
+            ),
+            StopTime::new(
+                3,
+                Some(1),
+                Rc::new(locations::nollendorfplatz()),
+                Some(time!(Duration; 4:36:00)),
+                Some(time!(Duration; 4:36:00)),
+                None,
+                false,
+            ),
+        ];
+        StopTime::complete(&mut stop_times, None, &"u4_0".into()).unwrap();
+        assert_eq!(
+            times(&stop_times),
+            [time!(Duration; 4:36:00), time!(Duration; 4:39:30)]
+        );
+    }
+
+    #[test]
+    fn test_interpolate_along_shape() {
+        let mut stop_times = vec![
+            stop_time(
+                2,
+                locations::nollendorfplatz(),
+                Some(time!(Duration; 4:36:00)),
+            ),
+            stop_time(3, locations::viktoria_luise_platz(), None),
+            stop_time(4, locations::bayerischer_platz(), None),
+            stop_time(5, locations::rathaus_schoeneberg(), None),
+            stop_time(
+                6,
+                locations::innsbrucker_platz(),
+                Some(time!(Duration; 4:42:00)),
+            ),
+        ];
+        let shape = shapes::u4::nollendorfplatz_innsbrucker_platz();
+        StopTime::complete(&mut stop_times, Some(&shape), &"u4_0".into()).unwrap();
+        let times = times(&stop_times);
+        assert!(times.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(times[4], time!(Duration; 4:42:00));
+        // the first section is longer than the others
+        assert!(times[1] - times[0] > times[2] - times[1]);
+    }
+
+    #[test]
+    fn test_interpolate_without_shape() {
+        let mut stop_times = vec![
+            stop_time(
+                2,
+                locations::nollendorfplatz(),
+                Some(time!(Duration; 4:36:00)),
+            ),
+            stop_time(3, locations::viktoria_luise_platz(), None),
+            stop_time(
+                4,
+                locations::bayerischer_platz(),
+                Some(time!(Duration; 4:39:30)),
+            ),
+        ];
+        StopTime::complete(&mut stop_times, None, &"u4_0".into()).unwrap();
+        let times = times(&stop_times);
+        assert!(times[0] < times[1] && times[1] < times[2]);
+    }
+
+    #[test]
+    fn test_missing_terminal_times() {
+        let mut stop_times = vec![
+            stop_time(
+                2,
+                locations::nollendorfplatz(),
+                Some(time!(Duration; 4:36:00)),
+            ),
+            stop_time(3, locations::viktoria_luise_platz(), None),
+        ];
+        let error = StopTime::complete(&mut stop_times, None, &"u4_0".into()).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "stop_times.txt, line 3: trip_id u4_0 lacks times at its first or last stop"
+        );
+    }
+
+    #[test]
+    fn test_interpolate_by_given_distances() {
+        let mut stop_times = vec![
+            stop_time(
+                2,
+                locations::nollendorfplatz(),
+                Some(time!(Duration; 4:36:00)),
+            ),
+            stop_time(3, locations::viktoria_luise_platz(), None),
+            stop_time(
+                4,
+                locations::bayerischer_platz(),
+                Some(time!(Duration; 4:40:00)),
+            ),
+        ];
+        for (stop_time, &distance) in stop_times.iter_mut().zip(&[0.0, 3.0, 4.0]) {
+            stop_time.shape_distance = Some(distance);
+        }
+        StopTime::complete(&mut stop_times, None, &"u4_0".into()).unwrap();
+        assert_eq!(stop_times[1].arrival(), time!(Duration; 4:39:00));
+    }
+
+    #[test]
+    fn test_interpolate_along_loop() {
+        // the shape returns the same way, passing the stop in between twice
+        let shape = Shape::from(vec![
+            locations::nollendorfplatz().position(),
+            locations::bayerischer_platz().position(),
+            locations::nollendorfplatz().position(),
+        ]);
+        let mut stop_times = vec![
+            stop_time(
+                2,
+                locations::nollendorfplatz(),
+                Some(time!(Duration; 4:36:00)),
+            ),
+            stop_time(3, locations::viktoria_luise_platz(), None),
+            stop_time(4, locations::bayerischer_platz(), None),
+            stop_time(5, locations::viktoria_luise_platz(), None),
+            stop_time(
+                6,
+                locations::nollendorfplatz(),
+                Some(time!(Duration; 4:44:00)),
+            ),
+        ];
+        let distances = StopTime::distances(&stop_times, Some(&shape));
+        assert!(distances.iter().tuple_windows().all(|(a, b)| a < b));
+        StopTime::complete(&mut stop_times, Some(&shape), &"u4_0".into()).unwrap();
+        let times = times(&stop_times);
+        assert!(times.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(times[2], time!(Duration; 4:40:00));
+    }
+
+    #[test]
+    fn test_decreasing_given_distances() {
+        let mut stop_times = vec![
+            stop_time(
+                2,
+                locations::nollendorfplatz(),
+                Some(time!(Duration; 4:36:00)),
+            ),
+            stop_time(3, locations::viktoria_luise_platz(), None),
+            stop_time(
+                4,
+                locations::bayerischer_platz(),
+                Some(time!(Duration; 4:40:00)),
+            ),
+        ];
+        for (stop_time, &distance) in stop_times.iter_mut().zip(&[0.0, 5.0, 4.0]) {
+            stop_time.shape_distance = Some(distance);
+        }
+        StopTime::complete(&mut stop_times, None, &"u4_0".into()).unwrap();
+        let times = times(&stop_times);
+        assert!(times[0] < times[1] && times[1] < times[2]);
+    }
+}
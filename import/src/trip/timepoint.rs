@@ -0,0 +1,54 @@
+use std::fmt;
+
+use serde::de::{Deserialize, Deserializer, Error as DeserializeError, Visitor};
+
+/// Whether the times at a stop are strictly adhered to or only approximate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Timepoint {
+    Approximate,
+    Exact,
+}
+
+impl<'de> Deserialize<'de> for Timepoint {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct TimepointVisitor;
+
+        impl<'de> Visitor<'de> for TimepointVisitor {
+            type Value = Timepoint;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("either 0 or 1")
+            }
+
+            fn visit_u64<E>(self, value: u64) -> Result<Timepoint, E>
+            where
+                E: DeserializeError,
+            {
+                match value {
+                    0 => Ok(Timepoint::Approximate),
+                    1 => Ok(Timepoint::Exact),
+                    _ => Err(E::custom(format!("unknown timepoint of value: {}", value))),
+                }
+            }
+        }
+
+        deserializer.deserialize_u64(TimepointVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_test::{assert_de_tokens, assert_de_tokens_error, Token};
+
+    use super::*;
+
+    #[test]
+    fn test_deserialize_timepoint() {
+        assert_de_tokens(&Timepoint::Approximate, &[Token::U8(0)]);
+        assert_de_tokens(&Timepoint::Exact, &[Token::U8(1)]);
+        assert_de_tokens_error::<Timepoint>(&[Token::U8(2)], "unknown timepoint of value: 2");
+    }
+}
@@ -2,42 +2,105 @@ use std::rc::Rc;
 
 use chrono::{Duration, NaiveDate};
 
-use super::Scheduler;
+use super::{Scheduler, TripId};
+use crate::location::LocationId;
+use crate::realtime::Realtime;
 use crate::service::Service;
 use simulation::Direction;
 
+/// Whether a trip with the durations is still running after midnight of its service day.
+pub(super) fn runs_past_midnight(durations: &[Duration]) -> bool {
+    durations
+        .iter()
+        .fold(Duration::zero(), |total, &duration| total + duration)
+        > Duration::days(1)
+}
+
 #[derive(Debug, PartialEq)]
 pub(super) struct Trip {
+    id: TripId,
     direction: Direction,
     service: Rc<Service>,
+    stop_sequences: Option<Vec<u32>>,
     durations: Vec<Duration>,
+    platforms: Vec<Option<usize>>,
 }
 
 impl Trip {
     pub(super) fn new(
+        id: TripId,
         direction: Direction,
         service: Rc<Service>,
+        stop_sequences: Option<Vec<u32>>,
         durations: Vec<Duration>,
     ) -> Self {
         Self {
+            id,
             direction,
             service,
+            stop_sequences,
             durations,
+            platforms: Vec::new(),
         }
     }
 
+    /// Sets the platforms of the stops, which are left empty if none is known.
+    pub(super) fn with_platforms(mut self, platforms: Vec<Option<usize>>) -> Self {
+        self.platforms = platforms;
+        self
+    }
+
     pub(super) fn direction(&self) -> Direction {
         self.direction
     }
 
-    pub(super) fn store(&self, scheduler: &mut Scheduler) -> storage::Train {
-        let (start, schedule) = scheduler.process(self.direction, &self.durations);
-        storage::Train::new(self.direction, start, schedule)
+    pub(super) fn platforms(&self) -> &[Option<usize>] {
+        &self.platforms
+    }
+
+    /// Durations spent driving between consecutive stops.
+    pub(super) fn driving_durations(&self) -> impl Iterator<Item = Duration> + '_ {
+        self.durations.iter().copied().skip(2).step_by(2)
+    }
+
+    /// Whether both trips run in the same direction with the same service and times.
+    pub(super) fn duplicates(&self, other: &Self) -> bool {
+        self.direction == other.direction
+            && self.service == other.service
+            && self.durations == other.durations
+    }
+
+    /// Stores the schedule of the trip, returning its start time and schedule index.
+    pub(super) fn store(&self, scheduler: &mut Scheduler) -> (u32, usize) {
+        scheduler.process(self.direction, &self.durations)
+    }
+
+    /// Stores the trip with the deviations reported for it, unless it is canceled.
+    ///
+    /// The locations of the stops are needed to match the stop updates of the realtime feed.
+    pub(super) fn store_realtime(
+        &self,
+        locations: &[LocationId],
+        date: NaiveDate,
+        realtime: &Realtime,
+        scheduler: &mut Scheduler,
+    ) -> Option<(u32, usize)> {
+        let durations = match realtime.update(&self.id, date, self.durations[0]) {
+            Some(update) => {
+                update.apply(locations, self.stop_sequences.as_deref(), &self.durations)?
+            }
+            None => self.durations.clone(),
+        };
+        Some(scheduler.process(self.direction, &durations))
     }
 
     pub(super) fn available_at(&self, date: NaiveDate) -> bool {
         self.service.available_at(date)
     }
+
+    pub(super) fn runs_past_midnight(&self) -> bool {
+        runs_past_midnight(&self.durations)
+    }
 }
 
 #[cfg(test)]
@@ -45,7 +108,7 @@ pub(crate) mod fixtures {
     macro_rules! trips {
         ($(
             $line:ident: {
-                $( $trip:ident => $direction:ident, $service:ident, $times:tt );* $(;)?
+                $( $trip:ident => $id:literal, $direction:ident, $service:ident, $times:tt );* $(;)?
             }
         ),* $(,)?) => (
             $(
@@ -58,9 +121,12 @@ pub(crate) mod fixtures {
                     $(
                         pub(in crate::trip) fn $trip(start: i64) -> Trip {
                             Trip {
+                                id: $id.into(),
                                 direction: Direction::$direction,
                                 service: Rc::new(services::$service()),
+                                stop_sequences: None,
                                 durations: times!(Duration; start, $times),
+                                platforms: Vec::new(),
                             }
                         }
                     )*
@@ -71,9 +137,9 @@ pub(crate) mod fixtures {
 
     trips! {
         tram_12: {
-            oranienburger_tor_am_kupfergraben => Upstream, mon_fri,
+            oranienburger_tor_am_kupfergraben => "1", Upstream, mon_fri,
             [0:00, 2:00, 0:00, 2:00, 0:00, 1:00, 0:00];
-            am_kupfergraben_oranienburger_tor => Downstream, mon_fri,
+            am_kupfergraben_oranienburger_tor => "2", Downstream, mon_fri,
             [0:00, 1:00, 0:00, 3:00, 0:00, 2:00, 0:00];
         },
     }
@@ -81,12 +147,10 @@ pub(crate) mod fixtures {
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashMap;
-
     use super::*;
     use crate::fixtures::{nodes, trips};
     use simulation::Directions;
-    use test_utils::{map, time};
+    use test_utils::time;
 
     #[test]
     fn test_available_at() {
@@ -95,17 +159,20 @@ mod tests {
         assert!(trip.available_at(date));
     }
 
+    #[test]
+    fn test_runs_past_midnight() {
+        let trip = trips::tram_12::oranienburger_tor_am_kupfergraben(time!(9:02:00));
+        assert!(!trip.runs_past_midnight());
+        let trip = trips::tram_12::oranienburger_tor_am_kupfergraben(time!(23:58:00));
+        assert!(trip.runs_past_midnight());
+    }
+
     #[test]
     fn test_store() {
         let mut scheduler = Scheduler::new();
         let nodes = nodes::tram_12::oranienburger_tor_am_kupfergraben(Directions::UpstreamOnly);
         scheduler.update_weights(&nodes);
         let trip = trips::tram_12::oranienburger_tor_am_kupfergraben(time!(9:02:00));
-        let schedule_ids: HashMap<&str, usize> = map! {"oranienburger_tor_am_kupfergraben" => 0};
-        let expected = storage::fixtures::trains::tram_12::oranienburger_tor_am_kupfergraben(
-            time!(9:01:40),
-            &schedule_ids,
-        );
-        assert_eq!(trip.store(&mut scheduler), expected);
+        assert_eq!(trip.store(&mut scheduler), (time!(9:01:40), 0));
     }
 }
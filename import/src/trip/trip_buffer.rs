@@ -1,13 +1,15 @@
 use std::collections::HashMap;
+use std::mem;
 use std::rc::Rc;
 
 use chrono::Duration;
 
-use super::{RouteBuffer, Trip};
+use super::{Frequency, RouteBuffer, StopTime, Trip};
 use crate::create_id_type;
 use crate::location::Location;
 use crate::service::Service;
-use crate::shape::{Shape, ShapeId};
+use crate::shape::{FallbackMode, Shape, ShapeId};
+use crate::ImportError;
 use simulation::Direction;
 
 create_id_type!(TripId);
@@ -16,9 +18,16 @@ create_id_type!(TripId);
 pub(super) struct TripBuffer {
     line_id: usize,
     service: Rc<Service>,
-    shape_id: ShapeId,
+    shape_id: Option<ShapeId>,
     direction: Direction,
+    stop_times: Vec<StopTime>,
     locations: Vec<Rc<Location>>,
+    /// Whether the trip passes through each stop, which is empty if it stops at all of them.
+    passed_through: Vec<bool>,
+    /// Platforms of the stops, which is empty if none is known.
+    platforms: Vec<Option<usize>>,
+    shape_distances: Option<Vec<f64>>,
+    stop_sequences: Option<Vec<u32>>,
     arrivals: Vec<Duration>,
     departures: Vec<Duration>,
 }
@@ -27,7 +36,7 @@ impl TripBuffer {
     pub(super) fn new(
         line_id: usize,
         service: Rc<Service>,
-        shape_id: ShapeId,
+        shape_id: Option<ShapeId>,
         direction: Direction,
     ) -> TripBuffer {
         TripBuffer {
@@ -35,21 +44,109 @@ impl TripBuffer {
             service,
             shape_id,
             direction,
+            stop_times: Vec::new(),
             locations: Vec::new(),
+            passed_through: Vec::new(),
+            platforms: Vec::new(),
+            shape_distances: None,
+            stop_sequences: None,
             arrivals: Vec::new(),
             departures: Vec::new(),
         }
     }
 
-    pub(super) fn add_stop(
+    pub(super) fn add_stop(&mut self, stop_time: StopTime) {
+        self.stop_times.push(stop_time);
+    }
+
+    /// Puts the added stops in order and completes their times.
+    ///
+    /// Vehicles pass through intermediate stops where passengers can neither board nor alight,
+    /// which keep their times but get no platform.
+    pub(super) fn complete_stops(
         &mut self,
-        location: Rc<Location>,
-        arrival: Duration,
-        departure: Duration,
-    ) {
-        self.locations.push(location);
-        self.arrivals.push(arrival);
-        self.departures.push(departure);
+        id: &TripId,
+        shapes: &HashMap<ShapeId, Shape>,
+    ) -> Result<(), ImportError> {
+        let mut stop_times = mem::take(&mut self.stop_times);
+        let shape = self.shape_id.as_ref().and_then(|id| shapes.get(id));
+        StopTime::complete(&mut stop_times, shape, id)?;
+
+        let last = stop_times.len().saturating_sub(1);
+        let mut shape_distances = Vec::new();
+        let mut stop_sequences = Vec::new();
+        for (i, stop_time) in stop_times.into_iter().enumerate() {
+            let passed_through = stop_time.is_pass_through() && i != 0 && i != last;
+            self.arrivals.push(stop_time.arrival());
+            self.departures.push(stop_time.departure());
+            self.locations.push(Rc::clone(stop_time.location()));
+            self.passed_through.push(passed_through);
+            if !passed_through {
+                self.platforms.push(stop_time.platform());
+            }
+            shape_distances.push(stop_time.shape_distance());
+            stop_sequences.push(stop_time.sequence());
+        }
+        if !self.passed_through.contains(&true) {
+            self.passed_through.clear();
+        }
+        if self.platforms.iter().all(Option::is_none) {
+            self.platforms.clear();
+        }
+        self.shape_distances = shape_distances.into_iter().collect();
+        self.stop_sequences = stop_sequences.into_iter().collect();
+        Ok(())
+    }
+
+    #[cfg(test)]
+    pub(super) fn locations(&self) -> &[Rc<Location>] {
+        &self.locations
+    }
+
+    #[cfg(test)]
+    pub(super) fn passed_through(&self) -> &[bool] {
+        &self.passed_through
+    }
+
+    #[cfg(test)]
+    pub(super) fn platforms(&self) -> &[Option<usize>] {
+        &self.platforms
+    }
+
+    #[cfg(test)]
+    pub(super) fn with_stop_sequences(mut self, stop_sequences: Vec<u32>) -> Self {
+        self.stop_sequences = Some(stop_sequences);
+        self
+    }
+
+    fn starting_at(&self, start: Duration) -> Self {
+        let offset = self
+            .arrivals
+            .first()
+            .map_or_else(Duration::zero, |&first| start - first);
+        Self {
+            line_id: self.line_id,
+            service: Rc::clone(&self.service),
+            shape_id: self.shape_id.clone(),
+            direction: self.direction,
+            stop_times: Vec::new(),
+            locations: self.locations.clone(),
+            passed_through: self.passed_through.clone(),
+            platforms: self.platforms.clone(),
+            shape_distances: self.shape_distances.clone(),
+            stop_sequences: self.stop_sequences.clone(),
+            arrivals: self.arrivals.iter().map(|&time| time + offset).collect(),
+            departures: self.departures.iter().map(|&time| time + offset).collect(),
+        }
+    }
+
+    /// Replaces this template trip with the trips implied by the frequencies.
+    pub(super) fn expand(self, frequencies: &[Frequency]) -> Vec<Self> {
+        frequencies
+            .iter()
+            .flat_map(Frequency::start_times)
+            .map(|start| self.starting_at(start))
+            .collect()
     }
 
     fn durations(&self) -> Vec<Duration> {
@@ -67,13 +164,36 @@ impl TripBuffer {
 
     pub(super) fn create_and_place_trip(
         self,
+        id: TripId,
         shapes: &HashMap<ShapeId, Shape>,
+        shape_fallback: FallbackMode,
         route_buffers: &mut Vec<RouteBuffer>,
     ) {
         let durations = self.durations();
-        let trip = Trip::new(self.direction, self.service, durations);
+        let trip = Trip::new(
+            id,
+            self.direction,
+            self.service,
+            self.stop_sequences,
+            durations,
+        )
+        .with_platforms(self.platforms);
+        let synthesized_shape;
+        let (shape, shape_distances) = match self.shape_id.as_ref().and_then(|id| shapes.get(id)) {
+            Some(shape) => (shape, self.shape_distances),
+            None => {
+                synthesized_shape = shape_fallback.synthesize(&self.locations);
+                (&synthesized_shape, None)
+            }
+        };
         let route_buffer = &mut route_buffers[self.line_id];
-        route_buffer.add_trip(self.locations, &shapes[&self.shape_id], trip);
+        route_buffer.add_trip(
+            self.locations,
+            self.passed_through,
+            shape_distances,
+            shape,
+            trip,
+        );
     }
 }
 
@@ -100,9 +220,14 @@ pub(crate) mod fixtures {
                             TripBuffer {
                                 line_id: $line_id,
                                 service: Rc::new(services::$service()),
-                                shape_id: stringify!($shape).into(),
+                                shape_id: Some(stringify!($shape).into()),
                                 direction: Direction::$direction,
+                                stop_times: Vec::new(),
                                 locations: stop_locations::$line::$trip(),
+                                passed_through: Vec::new(),
+                                platforms: Vec::new(),
+                                shape_distances: None,
+                                stop_sequences: None,
                                 arrivals: times!(Duration; +start, $arrival_times),
                                 departures: times!(Duration; +start, $departure_times),
                             }
@@ -132,21 +257,100 @@ pub(crate) mod fixtures {
 mod tests {
     use super::*;
     use crate::fixtures::{locations, route_buffers, shapes, trip_buffers};
+    use crate::trip::ExactTimes;
     use test_utils::{time, times};
 
+    fn stop_time(location: Location, time: Duration, pass_through: bool) -> StopTime {
+        StopTime::new(
+            2,
+            None,
+            Rc::new(location),
+            Some(time),
+            Some(time),
+            None,
+            pass_through,
+        )
+    }
+
     #[test]
     fn test_add_stop() {
         let mut buffer = trip_buffers::u4::empty(time!(0:00));
-        buffer.add_stop(
-            Rc::new(locations::innsbrucker_platz()),
+        buffer.add_stop(stop_time(
+            locations::innsbrucker_platz(),
             Duration::seconds(16560),
-            Duration::seconds(16560),
-        );
+            false,
+        ));
+        buffer
+            .complete_stops(&"u4_0".into(), &HashMap::new())
+            .unwrap();
         assert_eq!(buffer.locations, [Rc::new(locations::innsbrucker_platz())]);
         assert_eq!(buffer.arrivals, vec![Duration::seconds(16560)]);
         assert_eq!(buffer.departures, vec![Duration::seconds(16560)]);
     }
 
+    #[test]
+    fn test_pass_through_stops() {
+        let mut buffer = trip_buffers::u4::empty(time!(0:00));
+        buffer.add_stop(stop_time(
+            locations::nollendorfplatz(),
+            time!(Duration; 4:36:00),
+            true,
+        ));
+        buffer.add_stop(stop_time(
+            locations::viktoria_luise_platz(),
+            time!(Duration; 4:38:00),
+            true,
+        ));
+        buffer.add_stop(stop_time(
+            locations::bayerischer_platz(),
+            time!(Duration; 4:39:30),
+            false,
+        ));
+        buffer
+            .complete_stops(&"u4_0".into(), &HashMap::new())
+            .unwrap();
+        assert_eq!(
+            buffer.locations,
+            [
+                Rc::new(locations::nollendorfplatz()),
+                Rc::new(locations::viktoria_luise_platz()),
+                Rc::new(locations::bayerischer_platz()),
+            ]
+        );
+        assert_eq!(buffer.passed_through, [false, true, false]);
+        assert_eq!(
+            buffer.durations(),
+            times!(Duration; 4:36:00, 0:00, 2:00, 0:00, 1:30, 0:00)
+        );
+    }
+
+    #[test]
+    fn test_expand() {
+        let buffer = trip_buffers::tram_12::oranienburger_tor_am_kupfergraben(time!(9:02:00));
+        let frequencies = [
+            Frequency::new(
+                time!(Duration; 6:00:00),
+                time!(Duration; 6:20:00),
+                time!(Duration; 0:10:00),
+                ExactTimes::ScheduleBased,
+            ),
+            Frequency::new(
+                time!(Duration; 9:00:00),
+                time!(Duration; 9:10:00),
+                time!(Duration; 0:10:00),
+                ExactTimes::FrequencyBased,
+            ),
+        ];
+        assert_eq!(
+            buffer.expand(&frequencies),
+            vec![
+                trip_buffers::tram_12::oranienburger_tor_am_kupfergraben(time!(6:00:00)),
+                trip_buffers::tram_12::oranienburger_tor_am_kupfergraben(time!(6:10:00)),
+                trip_buffers::tram_12::oranienburger_tor_am_kupfergraben(time!(9:05:00)),
+            ]
+        );
+    }
+
     #[test]
     fn test_durations() {
         let buffer = trip_buffers::u4::nollendorfplatz_innsbrucker_platz(time!(4:36:00));
@@ -163,7 +367,12 @@ mod tests {
     fn test_create_route_with_upstream_buffer() {
         let mut route_buffers = vec![RouteBuffer::new()];
         let buffer = trip_buffers::tram_12::oranienburger_tor_am_kupfergraben(time!(9:02:00));
-        buffer.create_and_place_trip(&shapes::tram_12::by_id(), &mut route_buffers);
+        buffer.create_and_place_trip(
+            "1".into(),
+            &shapes::tram_12::by_id(),
+            FallbackMode::Straight,
+            &mut route_buffers,
+        );
         assert_eq!(route_buffers[0], route_buffers::tram_12::with_1_upstream());
     }
 
@@ -171,7 +380,12 @@ mod tests {
     fn test_create_route_with_downstream_buffer() {
         let mut route_buffers = vec![RouteBuffer::new()];
         let buffer = trip_buffers::tram_12::am_kupfergraben_oranienburger_tor(time!(8:34:00));
-        buffer.create_and_place_trip(&shapes::tram_12::by_id(), &mut route_buffers);
+        buffer.create_and_place_trip(
+            "2".into(),
+            &shapes::tram_12::by_id(),
+            FallbackMode::Straight,
+            &mut route_buffers,
+        );
         assert_eq!(
             route_buffers[0],
             route_buffers::tram_12::with_1_downstream()
@@ -182,9 +396,19 @@ mod tests {
     fn test_add_trips_to_route() {
         let mut route_buffers = vec![RouteBuffer::new()];
         let buffer = trip_buffers::tram_12::oranienburger_tor_am_kupfergraben(time!(9:02:00));
-        buffer.create_and_place_trip(&shapes::tram_12::by_id(), &mut route_buffers);
+        buffer.create_and_place_trip(
+            "1".into(),
+            &shapes::tram_12::by_id(),
+            FallbackMode::Straight,
+            &mut route_buffers,
+        );
         let buffer = trip_buffers::tram_12::am_kupfergraben_oranienburger_tor(time!(8:34:00));
-        buffer.create_and_place_trip(&shapes::tram_12::by_id(), &mut route_buffers);
+        buffer.create_and_place_trip(
+            "2".into(),
+            &shapes::tram_12::by_id(),
+            FallbackMode::Straight,
+            &mut route_buffers,
+        );
         assert_eq!(
             route_buffers[0],
             route_buffers::tram_12::with_1_upstream_1_downstream()
@@ -1,13 +1,98 @@
+use std::io::Read;
 #[cfg(feature = "progress")]
 use std::time::Instant;
 
 #[cfg(feature = "progress")]
 use console::Term;
+use csv::StringRecord;
 #[cfg(feature = "progress")]
 use indicatif::{ProgressBar, ProgressIterator, ProgressStyle};
+use itertools::Itertools;
 use serde::de::DeserializeOwned;
 
+use super::dataset::Table;
 use super::Dataset;
+use crate::ImportError;
+
+fn open_table<'s>(
+    dataset: &'s mut impl Dataset,
+    name: &'static str,
+) -> Result<Table<'s>, ImportError> {
+    if !dataset.contains(name) {
+        return Err(ImportError::MissingFile { file: name });
+    }
+    dataset
+        .open_csv(name)
+        .map_err(|err| ImportError::Unreadable {
+            file: name,
+            source: Box::new(err),
+        })
+}
+
+/// Columns referring to ids, which are prefixed with the namespace of their feed.
+const ID_COLUMNS: &[&str] = &[
+    "agency_id",
+    "route_id",
+    "trip_id",
+    "service_id",
+    "shape_id",
+    "stop_id",
+    "parent_station",
+    "from_stop_id",
+    "to_stop_id",
+];
+
+fn prefix_ids(record: &StringRecord, namespace: &str, id_columns: &[usize]) -> StringRecord {
+    record
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            if id_columns.contains(&i) && !field.is_empty() {
+                format!("{}:{}", namespace, field)
+            } else {
+                field.to_string()
+            }
+        })
+        .collect()
+}
+
+/// Deserializes the records of a csv file together with the line they start on.
+fn deserialize_records<'r, D>(
+    name: &'static str,
+    namespace: Option<String>,
+    reader: impl Read + 'r,
+) -> Result<impl Iterator<Item = Result<(u64, D), ImportError>> + 'r, ImportError>
+where
+    D: DeserializeOwned + 'r,
+{
+    let parse_error = move |source: csv::Error| ImportError::Parse {
+        file: name,
+        line: source.position().map(|position| position.line()),
+        source,
+    };
+    let mut reader = csv::Reader::from_reader(reader);
+    let headers = reader.headers().map_err(parse_error)?.clone();
+    let id_columns = headers
+        .iter()
+        .positions(|header| ID_COLUMNS.contains(&header))
+        .collect::<Vec<_>>();
+    let records = reader.into_records().map(move |result| {
+        let mut record = result.map_err(parse_error)?;
+        let line = record.position().map_or(0, |position| position.line());
+        if let Some(namespace) = &namespace {
+            record = prefix_ids(&record, namespace, &id_columns);
+        }
+        let value = record
+            .deserialize(Some(&headers))
+            .map_err(|source| ImportError::Parse {
+                file: name,
+                line: Some(line),
+                source,
+            })?;
+        Ok((line, value))
+    });
+    Ok(records)
+}
 
 #[cfg(feature = "progress")]
 #[cfg(not(tarpaulin_include))]
@@ -39,15 +124,15 @@ impl Action {
     pub(crate) fn read_csv<'s, D, S>(
         &self,
         dataset: &'s mut S,
-        name: &str,
-    ) -> Result<impl Iterator<Item = csv::Result<D>> + 's, S::Error>
+        name: &'static str,
+    ) -> Result<impl Iterator<Item = Result<(u64, D), ImportError>> + 's, ImportError>
     where
         D: DeserializeOwned + 's,
         S: Dataset,
     {
-        let table = dataset.open_csv(name)?;
+        let table = open_table(dataset, name)?;
         let reader = Self::percent_bar(table.size, self.message).wrap_read(table.reader);
-        Ok(csv::Reader::from_reader(reader).into_deserialize())
+        deserialize_records(name, table.namespace, reader)
     }
 
     pub(crate) fn wrap_iter<T>(&mut self, collection: T) -> impl Iterator<Item = T::Item>
@@ -83,14 +168,14 @@ impl Action {
     pub(crate) fn read_csv<'s, D, S>(
         &self,
         dataset: &'s mut S,
-        name: &str,
-    ) -> Result<impl Iterator<Item = csv::Result<D>> + 's, S::Error>
+        name: &'static str,
+    ) -> Result<impl Iterator<Item = Result<(u64, D), ImportError>> + 's, ImportError>
     where
         D: DeserializeOwned + 's,
         S: Dataset,
     {
-        let table = dataset.open_csv(name)?;
-        Ok(csv::Reader::from_reader(table.reader).into_deserialize())
+        let table = open_table(dataset, name)?;
+        deserialize_records(name, table.namespace, table.reader)
     }
 
     pub(crate) fn wrap_iter<T>(&mut self, collection: T) -> impl Iterator<Item = T::Item>
@@ -9,6 +9,8 @@ pub(crate) struct Table<'r> {
     #[cfg_attr(not(feature = "progress"), allow(dead_code))]
     pub(super) size: u64,
     pub(super) reader: Box<dyn Read + 'r>,
+    /// Prefix for the ids in this table, if the feed is imported alongside others.
+    pub(super) namespace: Option<String>,
 }
 
 impl<'r> Table<'r> {
@@ -16,6 +18,7 @@ impl<'r> Table<'r> {
         Self {
             reader: Box::new(reader),
             size,
+            namespace: None,
         }
     }
 }
@@ -23,28 +26,66 @@ impl<'r> Table<'r> {
 pub(crate) trait Dataset {
     type Error: Error + 'static;
 
+    fn contains(&mut self, name: &str) -> bool;
+
     fn open_csv(&mut self, name: &str) -> Result<Table, Self::Error>;
 }
 
 impl Dataset for PathBuf {
     type Error = io::Error;
 
+    fn contains(&mut self, name: &str) -> bool {
+        self.set_file_name(name);
+        self.is_file()
+    }
+
     fn open_csv(&mut self, name: &str) -> Result<Table, Self::Error> {
         self.set_file_name(name);
         let file = File::open(&self)?;
-        Ok(Table::new(file.metadata().unwrap().len(), file))
+        Ok(Table::new(file.metadata()?.len(), file))
     }
 }
 
 impl<R: Read + Seek> Dataset for ZipArchive<R> {
     type Error = zip::result::ZipError;
 
+    fn contains(&mut self, name: &str) -> bool {
+        self.by_name(name).is_ok()
+    }
+
     fn open_csv(&mut self, name: &str) -> Result<Table, Self::Error> {
         let file = self.by_name(name)?;
         Ok(Table::new(file.size(), file))
     }
 }
 
+/// A feed whose ids are prefixed with a namespace, so that they do not collide with the ids of
+/// other feeds.
+pub(crate) struct Namespaced<D> {
+    dataset: D,
+    namespace: String,
+}
+
+impl<D> Namespaced<D> {
+    pub(crate) fn new(dataset: D, namespace: String) -> Self {
+        Self { dataset, namespace }
+    }
+}
+
+impl<D: Dataset> Dataset for Namespaced<D> {
+    type Error = D::Error;
+
+    fn contains(&mut self, name: &str) -> bool {
+        self.dataset.contains(name)
+    }
+
+    fn open_csv(&mut self, name: &str) -> Result<Table<'_>, Self::Error> {
+        let mut table = self.dataset.open_csv(name)?;
+        table.namespace = Some(self.namespace.clone());
+        Ok(table)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
@@ -55,6 +96,10 @@ mod tests {
     impl Dataset for HashMap<String, String> {
         type Error = io::Error;
 
+        fn contains(&mut self, name: &str) -> bool {
+            self.contains_key(name)
+        }
+
         fn open_csv(&mut self, name: &str) -> Result<Table, Self::Error> {
             let data = self
                 .get(name)
@@ -65,7 +110,8 @@ mod tests {
 
     #[macro_export]
     macro_rules! dataset {
-        ($($name:ident: $($($value:expr),+);+)*) => ({
+        () => (HashMap::<String, String>::new());
+        ($($name:ident: $($($value:expr),+);+)+) => ({
             let mut dataset = HashMap::new();
             $(
                 let mut data = String::new();
@@ -93,6 +139,17 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_contains() -> Result<(), Box<dyn Error>> {
+        let mut path = PathBuf::from("tests/data/sample/.txt");
+        assert!(path.contains("sample.txt"));
+        assert!(!path.contains("missing.txt"));
+        let mut archive = ZipArchive::new(File::open("tests/data/sample.bzip")?)?;
+        assert!(archive.contains("sample.txt"));
+        assert!(!archive.contains("missing.txt"));
+        Ok(())
+    }
+
     #[test]
     fn test_open_dataset_archive() -> Result<(), Box<dyn Error>> {
         let path = PathBuf::from("tests/data/sample.bzip");
@@ -104,4 +161,13 @@ mod tests {
         assert_eq!(content, "sample dataset file\n");
         Ok(())
     }
+
+    #[test]
+    fn test_namespaced() -> Result<(), Box<dyn Error>> {
+        let mut dataset = Namespaced::new(PathBuf::from("tests/data/sample/.txt"), "vbb".into());
+        assert!(dataset.contains("sample.txt"));
+        let table = dataset.open_csv("sample.txt")?;
+        assert_eq!(table.namespace, Some("vbb".to_string()));
+        Ok(())
+    }
 }
@@ -2,7 +2,7 @@ mod action;
 mod dataset;
 
 pub(crate) use action::Action;
-pub(crate) use dataset::Dataset;
+pub(crate) use dataset::{Dataset, Namespaced};
 
 #[macro_export]
 macro_rules! create_id_type {
@@ -0,0 +1,564 @@
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+
+use chrono::{Datelike, NaiveDate};
+use itertools::Itertools;
+
+use super::feed::Table;
+use super::records::{ServiceRecord, StopTimeRecord};
+use super::{Feed, Report, Severity};
+use crate::coord::{project, Point};
+use crate::service::ExceptionType;
+
+fn report_duplicates<'a>(
+    report: &mut Report,
+    file: &'static str,
+    field: &'static str,
+    ids: impl Iterator<Item = (u64, &'a str)>,
+) {
+    let mut first_lines = HashMap::new();
+    for (line, id) in ids {
+        match first_lines.entry(id) {
+            Entry::Occupied(first_line) => report.add(
+                Severity::Error,
+                file,
+                line,
+                format!(
+                    "duplicate {} {}, first defined on line {}",
+                    field,
+                    id,
+                    first_line.get()
+                ),
+            ),
+            Entry::Vacant(entry) => {
+                entry.insert(line);
+            }
+        }
+    }
+}
+
+/// Checks that ids which identify a record are unique within their file.
+pub(super) fn duplicate_ids(feed: &Feed, report: &mut Report) {
+    let routes = feed
+        .routes
+        .iter()
+        .map(|(line, record)| (*line, record.route_id.as_str()));
+    report_duplicates(report, "routes.txt", "route_id", routes);
+    let trips = feed
+        .trips
+        .iter()
+        .map(|(line, record)| (*line, record.trip_id.as_str()));
+    report_duplicates(report, "trips.txt", "trip_id", trips);
+    let stops = feed
+        .stops
+        .iter()
+        .map(|(line, record)| (*line, record.stop_id.as_str()));
+    report_duplicates(report, "stops.txt", "stop_id", stops);
+    let services = feed
+        .services
+        .iter()
+        .map(|(line, record)| (*line, record.service_id.as_str()));
+    report_duplicates(report, "calendar.txt", "service_id", services);
+}
+
+fn report_dangling(
+    report: &mut Report,
+    file: &'static str,
+    line: u64,
+    field: &'static str,
+    id: &str,
+    known: &HashSet<&str>,
+) {
+    if !known.contains(id) {
+        report.add(
+            Severity::Error,
+            file,
+            line,
+            format!("{} {} not found", field, id),
+        );
+    }
+}
+
+/// Checks that trips and stop times only refer to records defined elsewhere in the feed.
+pub(super) fn references(feed: &Feed, report: &mut Report) {
+    let routes = feed
+        .routes
+        .iter()
+        .map(|(_, record)| record.route_id.as_str())
+        .collect();
+    let services = feed
+        .services
+        .iter()
+        .map(|(_, record)| record.service_id.as_str())
+        .chain(
+            feed.service_exceptions
+                .iter()
+                .map(|(_, record)| record.service_id.as_str()),
+        )
+        .collect();
+    let shapes = feed
+        .shapes
+        .iter()
+        .map(|(_, record)| record.shape_id.as_str())
+        .collect();
+    for (line, record) in &feed.trips {
+        let line = *line;
+        report_dangling(
+            report,
+            "trips.txt",
+            line,
+            "route_id",
+            &record.route_id,
+            &routes,
+        );
+        report_dangling(
+            report,
+            "trips.txt",
+            line,
+            "service_id",
+            &record.service_id,
+            &services,
+        );
+        if let Some(shape_id) = &record.shape_id {
+            report_dangling(report, "trips.txt", line, "shape_id", shape_id, &shapes);
+        }
+    }
+
+    let trips = feed
+        .trips
+        .iter()
+        .map(|(_, record)| record.trip_id.as_str())
+        .collect();
+    let stops = feed
+        .stops
+        .iter()
+        .map(|(_, record)| record.stop_id.as_str())
+        .collect();
+    for (line, record) in &feed.stop_times {
+        let line = *line;
+        report_dangling(
+            report,
+            "stop_times.txt",
+            line,
+            "trip_id",
+            &record.trip_id,
+            &trips,
+        );
+        report_dangling(
+            report,
+            "stop_times.txt",
+            line,
+            "stop_id",
+            &record.stop_id,
+            &stops,
+        );
+    }
+}
+
+fn runs_regularly(record: &ServiceRecord, removed: &HashSet<NaiveDate>) -> bool {
+    let weekdays = record.weekdays();
+    let mut date = record.start_date;
+    while date <= record.end_date {
+        let day = date.weekday().num_days_from_monday() as usize;
+        if weekdays[day] && !removed.contains(&date) {
+            return true;
+        }
+        date = date.succ();
+    }
+    false
+}
+
+/// Checks that every service runs on at least one day.
+pub(super) fn calendars(feed: &Feed, report: &mut Report) {
+    let mut added = HashMap::new();
+    let mut removed = HashMap::new();
+    for (line, record) in &feed.service_exceptions {
+        let dates = match record.exception_type {
+            ExceptionType::Added => &mut added,
+            ExceptionType::Removed => &mut removed,
+        };
+        dates
+            .entry(record.service_id.as_str())
+            .or_insert_with(|| (*line, HashSet::new()))
+            .1
+            .insert(record.date);
+    }
+
+    let no_dates = (0, HashSet::new());
+    for (line, record) in &feed.services {
+        let id = record.service_id.as_str();
+        if record.start_date > record.end_date {
+            report.add(
+                Severity::Error,
+                "calendar.txt",
+                *line,
+                format!("service {} ends before it starts", id),
+            );
+        } else if !added.contains_key(id)
+            && !runs_regularly(record, &removed.get(id).unwrap_or(&no_dates).1)
+        {
+            report.add(
+                Severity::Warning,
+                "calendar.txt",
+                *line,
+                format!("service {} never runs", id),
+            );
+        }
+    }
+
+    let regular = feed
+        .services
+        .iter()
+        .map(|(_, record)| record.service_id.as_str())
+        .collect::<HashSet<_>>();
+    for (id, (line, _)) in removed.iter().sorted_by_key(|(_, (line, _))| *line) {
+        if !regular.contains(id) && !added.contains_key(id) {
+            report.add(
+                Severity::Warning,
+                "calendar_dates.txt",
+                *line,
+                format!("service {} never runs", id),
+            );
+        }
+    }
+}
+
+fn group_by_trip(stop_times: &Table<StopTimeRecord>) -> Vec<Vec<&(u64, StopTimeRecord)>> {
+    let mut trips = HashMap::new();
+    for entry in stop_times {
+        trips
+            .entry(entry.1.trip_id.as_str())
+            .or_insert_with(Vec::new)
+            .push(entry);
+    }
+    trips
+        .into_iter()
+        .sorted_by_key(|(_, entries)| entries[0].0)
+        .map(|(_, mut entries)| {
+            entries.sort_by_key(|(line, record)| (record.stop_sequence, *line));
+            entries
+        })
+        .collect()
+}
+
+/// Checks that the times of every trip never go backwards.
+pub(super) fn stop_times(feed: &Feed, report: &mut Report) {
+    for entries in group_by_trip(&feed.stop_times) {
+        let mut previous: Option<&StopTimeRecord> = None;
+        for (line, record) in entries {
+            let (arrival, departure) = match (record.arrival(), record.departure()) {
+                (Some(arrival), Some(departure)) => (arrival, departure),
+                _ => continue,
+            };
+            if departure < arrival {
+                report.add(
+                    Severity::Error,
+                    "stop_times.txt",
+                    *line,
+                    format!(
+                        "trip {} departs from {} before arriving",
+                        record.trip_id, record.stop_id
+                    ),
+                );
+            }
+            if let Some(previous) = previous {
+                if arrival < previous.departure().unwrap() {
+                    report.add(
+                        Severity::Error,
+                        "stop_times.txt",
+                        *line,
+                        format!(
+                            "trip {} arrives at {} before departing from {}",
+                            record.trip_id, record.stop_id, previous.stop_id
+                        ),
+                    );
+                }
+            }
+            previous = Some(record);
+        }
+    }
+}
+
+fn distance_to_segment(point: Point, start: Point, end: Point) -> f64 {
+    let segment = end - start;
+    let length_squared = segment.norm_squared();
+    if length_squared == 0.0 {
+        return na::distance(&point, &start);
+    }
+    let factor = ((point - start).dot(&segment) / length_squared).clamp(0.0, 1.0);
+    na::distance(&point, &(start + segment * factor))
+}
+
+fn distance_to_shape(point: Point, shape: &[Point]) -> f64 {
+    match shape {
+        [] => 0.0,
+        [single] => na::distance(&point, single),
+        _ => shape
+            .iter()
+            .tuple_windows()
+            .map(|(&start, &end)| distance_to_segment(point, start, end))
+            .fold(f64::INFINITY, f64::min),
+    }
+}
+
+/// Checks that the stops of every trip lie close to the shape of the trip.
+///
+/// Each pair of stop and shape is only reported once, on the first stop time visiting it.
+pub(super) fn stop_distances(feed: &Feed, max_distance: f64, report: &mut Report) {
+    let mut shapes: HashMap<&str, Vec<_>> = HashMap::new();
+    for (line, record) in &feed.shapes {
+        shapes.entry(record.shape_id.as_str()).or_default().push((
+            record.shape_pt_sequence,
+            *line,
+            record,
+        ));
+    }
+    let shapes = shapes
+        .into_iter()
+        .map(|(id, mut points)| {
+            points.sort_by_key(|&(sequence, line, _)| (sequence, line));
+            let points = points
+                .into_iter()
+                .map(|(_, _, record)| project(record.shape_pt_lat, record.shape_pt_lon))
+                .collect::<Vec<_>>();
+            (id, points)
+        })
+        .collect::<HashMap<_, _>>();
+    let stops = feed
+        .stops
+        .iter()
+        .map(|(_, record)| {
+            let position = project(record.stop_lat, record.stop_lon);
+            (record.stop_id.as_str(), position)
+        })
+        .collect::<HashMap<_, _>>();
+    let trip_shapes = feed
+        .trips
+        .iter()
+        .filter_map(|(_, record)| {
+            let shape_id = record.shape_id.as_deref()?;
+            Some((record.trip_id.as_str(), shape_id))
+        })
+        .collect::<HashMap<_, _>>();
+
+    let mut reported = HashSet::new();
+    for (line, record) in &feed.stop_times {
+        let shape_id = match trip_shapes.get(record.trip_id.as_str()) {
+            Some(&shape_id) => shape_id,
+            None => continue,
+        };
+        let (shape, &position) = match (shapes.get(shape_id), stops.get(record.stop_id.as_str())) {
+            (Some(shape), Some(position)) => (shape, position),
+            _ => continue,
+        };
+        if reported.contains(&(record.stop_id.as_str(), shape_id)) {
+            continue;
+        }
+        let distance = distance_to_shape(position, shape);
+        if distance > max_distance {
+            reported.insert((record.stop_id.as_str(), shape_id));
+            report.add(
+                Severity::Warning,
+                "stop_times.txt",
+                *line,
+                format!(
+                    "stop {} is {:.0} m away from shape {}",
+                    record.stop_id, distance, shape_id
+                ),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::dataset;
+
+    fn check(
+        dataset: HashMap<String, String>,
+        check: impl FnOnce(&Feed, &mut Report),
+    ) -> Vec<String> {
+        let mut dataset = dataset;
+        let mut report = Report::new();
+        let feed = Feed::read(&mut dataset, &mut report);
+        assert_eq!(report, Report::new());
+        check(&feed, &mut report);
+        report
+            .findings()
+            .iter()
+            .map(|finding| finding.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_duplicate_ids() {
+        let dataset = dataset!(
+            routes:
+                route_id;
+                u4;
+                u4
+            trips:
+                trip_id, route_id, service_id
+            stops:
+                stop_id, stop_lat, stop_lon
+            stop_times:
+                trip_id, stop_id, arrival_time, departure_time
+            calendar_dates:
+                service_id, date, exception_type
+        );
+        assert_eq!(
+            check(dataset, duplicate_ids),
+            ["error: routes.txt, line 3: duplicate route_id u4, first defined on line 2"]
+        );
+    }
+
+    #[test]
+    fn test_references() {
+        let dataset = dataset!(
+            routes:
+                route_id;
+                u4
+            trips:
+                trip_id, route_id, service_id, shape_id;
+                1,       u4,       mon_fri,    "";
+                2,       u5,       weekend,    u4
+            stops:
+                stop_id,         stop_lat, stop_lon;
+                nollendorfplatz, 52.500,   13.354
+            stop_times:
+                trip_id, stop_id,           arrival_time, departure_time;
+                1,       nollendorfplatz,   "4:36:00",    "4:36:00";
+                3,       innsbrucker_platz, "4:42:00",    "4:42:00"
+            calendar_dates:
+                service_id, date,     exception_type;
+                mon_fri,    20190107, 1
+        );
+        assert_eq!(
+            check(dataset, references),
+            [
+                "error: trips.txt, line 3: route_id u5 not found",
+                "error: trips.txt, line 3: service_id weekend not found",
+                "error: trips.txt, line 3: shape_id u4 not found",
+                "error: stop_times.txt, line 3: trip_id 3 not found",
+                "error: stop_times.txt, line 3: stop_id innsbrucker_platz not found",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_calendars() {
+        let dataset = dataset!(
+            routes:
+                route_id
+            trips:
+                trip_id, route_id, service_id
+            stops:
+                stop_id, stop_lat, stop_lon
+            stop_times:
+                trip_id, stop_id, arrival_time, departure_time
+            calendar:
+                service_id, monday, tuesday, wednesday, thursday, friday, saturday, sunday, start_date, end_date;
+                mon_fri,    1,      1,       1,         1,        1,      0,        0,      20190101,   20191231;
+                reversed,   1,      1,       1,         1,        1,      0,        0,      20191231,   20190101;
+                never,      0,      0,       0,         0,        0,      0,        0,      20190101,   20191231;
+                cancelled,  1,      0,       0,         0,        0,      0,        0,      20190107,   20190107
+            calendar_dates:
+                service_id, date,     exception_type;
+                cancelled,  20190107, 2;
+                removed,    20190108, 2
+        );
+        assert_eq!(
+            check(dataset, calendars),
+            [
+                "error: calendar.txt, line 3: service reversed ends before it starts",
+                "warning: calendar.txt, line 4: service never never runs",
+                "warning: calendar.txt, line 5: service cancelled never runs",
+                "warning: calendar_dates.txt, line 3: service removed never runs",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_stop_times() {
+        let dataset = dataset!(
+            routes:
+                route_id
+            trips:
+                trip_id, route_id, service_id
+            stops:
+                stop_id, stop_lat, stop_lon
+            stop_times:
+                trip_id, stop_id,              stop_sequence, arrival_time, departure_time;
+                1,       bayerischer_platz,    3,             "4:39:30",    "4:39:30";
+                1,       nollendorfplatz,      1,             "4:36:00",    "4:36:00";
+                1,       viktoria_luise_platz, 2,             "4:38:00",    "4:38:00";
+                2,       nollendorfplatz,      1,             "4:46:00",    "4:45:00";
+                2,       viktoria_luise_platz, 2,             "4:44:00",    "4:48:00";
+                3,       nollendorfplatz,      1,             "4:50:00",    "4:50:00";
+                3,       viktoria_luise_platz, 2,             "",           "";
+                3,       bayerischer_platz,    3,             "4:49:00",    "4:49:00"
+            calendar_dates:
+                service_id, date, exception_type
+        );
+        assert_eq!(
+            check(dataset, stop_times),
+            [
+                "error: stop_times.txt, line 5: trip 2 departs from nollendorfplatz before arriving",
+                "error: stop_times.txt, line 6: trip 2 arrives at viktoria_luise_platz before departing from nollendorfplatz",
+                "error: stop_times.txt, line 9: trip 3 arrives at bayerischer_platz before departing from nollendorfplatz",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_stop_distances() {
+        let dataset = dataset!(
+            routes:
+                route_id
+            trips:
+                trip_id, route_id, service_id, shape_id;
+                1,       u4,       mon_fri,    u4;
+                2,       u4,       mon_fri,    u4
+            stops:
+                stop_id,           stop_lat, stop_lon;
+                nollendorfplatz,   52.500,   13.354;
+                innsbrucker_platz, 52.478,   13.343;
+                alexanderplatz,    52.521,   13.411
+            stop_times:
+                trip_id, stop_id,           arrival_time, departure_time;
+                1,       nollendorfplatz,   "4:36:00",    "4:36:00";
+                1,       alexanderplatz,    "4:40:00",    "4:40:00";
+                1,       innsbrucker_platz, "4:42:00",    "4:42:00";
+                2,       alexanderplatz,    "4:50:00",    "4:50:00"
+            calendar_dates:
+                service_id, date, exception_type
+            shapes:
+                shape_id, shape_pt_lat, shape_pt_lon;
+                u4,       52.500,       13.354;
+                u4,       52.489,       13.340;
+                u4,       52.478,       13.343
+        );
+        let findings = check(dataset, |feed, report| stop_distances(feed, 100.0, report));
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].starts_with("warning: stop_times.txt, line 3: stop alexanderplatz is"));
+        assert!(findings[0].ends_with("m away from shape u4"));
+    }
+
+    #[test]
+    fn test_distance_to_segment() {
+        let start = Point::new(0.0, 0.0);
+        let end = Point::new(100.0, 0.0);
+        assert_eq!(
+            distance_to_segment(Point::new(50.0, 30.0), start, end),
+            30.0
+        );
+        assert_eq!(
+            distance_to_segment(Point::new(-40.0, 30.0), start, end),
+            50.0
+        );
+        assert_eq!(distance_to_segment(Point::new(3.0, 4.0), start, start), 5.0);
+    }
+}
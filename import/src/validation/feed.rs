@@ -0,0 +1,75 @@
+use serde::de::DeserializeOwned;
+
+use super::records::{
+    RouteRecord, ServiceExceptionRecord, ServiceRecord, ShapeRecord, StopRecord, StopTimeRecord,
+    TripRecord,
+};
+use super::Report;
+use crate::utils::{Action, Dataset};
+
+/// Records of a feed together with the line they were read from.
+pub(super) type Table<R> = Vec<(u64, R)>;
+
+/// The raw tables of a feed which are relevant for validation.
+///
+/// Unlike the importer, reading continues after broken records, which are added to the report
+/// instead.
+pub(super) struct Feed {
+    pub(super) routes: Table<RouteRecord>,
+    pub(super) trips: Table<TripRecord>,
+    pub(super) stops: Table<StopRecord>,
+    pub(super) stop_times: Table<StopTimeRecord>,
+    pub(super) services: Table<ServiceRecord>,
+    pub(super) service_exceptions: Table<ServiceExceptionRecord>,
+    pub(super) shapes: Table<ShapeRecord>,
+}
+
+impl Feed {
+    fn read_table<D: DeserializeOwned>(
+        dataset: &mut impl Dataset,
+        name: &'static str,
+        required: bool,
+        report: &mut Report,
+    ) -> Table<D> {
+        let mut table = Vec::new();
+        if !required && !dataset.contains(name) {
+            return table;
+        }
+
+        let action = Action::start(name);
+        let records = match action.read_csv(dataset, name) {
+            Ok(records) => records,
+            Err(err) => {
+                report.add_import_error(err);
+                return table;
+            }
+        };
+        for result in records {
+            match result {
+                Ok(entry) => table.push(entry),
+                Err(err) => report.add_import_error(err),
+            }
+        }
+        action.complete(&format!("Read {}", name));
+        table
+    }
+
+    pub(super) fn read(dataset: &mut impl Dataset, report: &mut Report) -> Self {
+        let has_calendar = dataset.contains("calendar.txt");
+        let has_calendar_dates = dataset.contains("calendar_dates.txt");
+        Self {
+            routes: Self::read_table(dataset, "routes.txt", true, report),
+            trips: Self::read_table(dataset, "trips.txt", true, report),
+            stops: Self::read_table(dataset, "stops.txt", true, report),
+            stop_times: Self::read_table(dataset, "stop_times.txt", true, report),
+            services: Self::read_table(dataset, "calendar.txt", !has_calendar_dates, report),
+            service_exceptions: Self::read_table(
+                dataset,
+                "calendar_dates.txt",
+                !has_calendar,
+                report,
+            ),
+            shapes: Self::read_table(dataset, "shapes.txt", false, report),
+        }
+    }
+}
@@ -0,0 +1,10 @@
+mod checks;
+mod feed;
+mod records;
+mod report;
+mod validator;
+
+use feed::Feed;
+
+pub use report::{Finding, Report, Severity};
+pub use validator::Validator;
@@ -0,0 +1,103 @@
+use serde_derive::Deserialize;
+
+use chrono::{Duration, NaiveDate};
+
+use crate::deserialize;
+use crate::service::ExceptionType;
+
+#[derive(Debug, Deserialize)]
+pub(super) struct RouteRecord {
+    pub(super) route_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct TripRecord {
+    pub(super) trip_id: String,
+    pub(super) route_id: String,
+    pub(super) service_id: String,
+    #[serde(default)]
+    pub(super) shape_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct StopRecord {
+    pub(super) stop_id: String,
+    pub(super) stop_lat: f64,
+    pub(super) stop_lon: f64,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct StopTimeRecord {
+    pub(super) trip_id: String,
+    pub(super) stop_id: String,
+    #[serde(default)]
+    pub(super) stop_sequence: Option<u32>,
+    #[serde(deserialize_with = "deserialize::optional_duration")]
+    pub(super) arrival_time: Option<Duration>,
+    #[serde(deserialize_with = "deserialize::optional_duration")]
+    pub(super) departure_time: Option<Duration>,
+}
+
+impl StopTimeRecord {
+    pub(super) fn arrival(&self) -> Option<Duration> {
+        self.arrival_time.or(self.departure_time)
+    }
+
+    pub(super) fn departure(&self) -> Option<Duration> {
+        self.departure_time.or(self.arrival_time)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct ServiceRecord {
+    pub(super) service_id: String,
+    #[serde(deserialize_with = "deserialize::naive_date")]
+    pub(super) start_date: NaiveDate,
+    #[serde(deserialize_with = "deserialize::naive_date")]
+    pub(super) end_date: NaiveDate,
+    #[serde(deserialize_with = "deserialize::numeric_bool")]
+    monday: bool,
+    #[serde(deserialize_with = "deserialize::numeric_bool")]
+    tuesday: bool,
+    #[serde(deserialize_with = "deserialize::numeric_bool")]
+    wednesday: bool,
+    #[serde(deserialize_with = "deserialize::numeric_bool")]
+    thursday: bool,
+    #[serde(deserialize_with = "deserialize::numeric_bool")]
+    friday: bool,
+    #[serde(deserialize_with = "deserialize::numeric_bool")]
+    saturday: bool,
+    #[serde(deserialize_with = "deserialize::numeric_bool")]
+    sunday: bool,
+}
+
+impl ServiceRecord {
+    pub(super) fn weekdays(&self) -> [bool; 7] {
+        [
+            self.monday,
+            self.tuesday,
+            self.wednesday,
+            self.thursday,
+            self.friday,
+            self.saturday,
+            self.sunday,
+        ]
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct ServiceExceptionRecord {
+    pub(super) service_id: String,
+    #[serde(deserialize_with = "deserialize::naive_date")]
+    pub(super) date: NaiveDate,
+    pub(super) exception_type: ExceptionType,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct ShapeRecord {
+    pub(super) shape_id: String,
+    pub(super) shape_pt_lat: f64,
+    pub(super) shape_pt_lon: f64,
+    #[serde(default)]
+    pub(super) shape_pt_sequence: Option<u32>,
+}
@@ -0,0 +1,239 @@
+use std::fmt;
+
+use serde_derive::Serialize;
+
+use crate::ImportError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Warning => write!(formatter, "warning"),
+            Severity::Error => write!(formatter, "error"),
+        }
+    }
+}
+
+/// A single problem found in a feed, located by file and line.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Finding {
+    severity: Severity,
+    file: &'static str,
+    line: Option<u64>,
+    message: String,
+}
+
+impl Finding {
+    pub(super) fn new(
+        severity: Severity,
+        file: &'static str,
+        line: Option<u64>,
+        message: String,
+    ) -> Self {
+        Self {
+            severity,
+            file,
+            line,
+            message,
+        }
+    }
+
+    pub fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    pub fn file(&self) -> &str {
+        self.file
+    }
+
+    pub fn line(&self) -> Option<u64> {
+        self.line
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(
+                formatter,
+                "{}: {}, line {}: {}",
+                self.severity, self.file, line, self.message
+            ),
+            None => write!(
+                formatter,
+                "{}: {}: {}",
+                self.severity, self.file, self.message
+            ),
+        }
+    }
+}
+
+/// Findings of a feed validation.
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct Report {
+    findings: Vec<Finding>,
+}
+
+impl Report {
+    pub(super) fn new() -> Self {
+        Self::default()
+    }
+
+    pub(super) fn add(
+        &mut self,
+        severity: Severity,
+        file: &'static str,
+        line: impl Into<Option<u64>>,
+        message: String,
+    ) {
+        self.findings
+            .push(Finding::new(severity, file, line.into(), message));
+    }
+
+    /// Records an error which prevented reading (part of) a file.
+    pub(super) fn add_import_error(&mut self, error: ImportError) {
+        let (file, line, message) = match error {
+            ImportError::Archive { .. } => unreachable!(),
+            ImportError::MissingFile { file } => (file, None, "missing file".to_string()),
+            ImportError::Unreadable { file, source } => (file, None, source.to_string()),
+            ImportError::Parse { file, line, source } => (file, line, source.to_string()),
+            ImportError::DanglingReference {
+                file,
+                line,
+                field,
+                id,
+            } => (file, Some(line), format!("{} {} not found", field, id)),
+            ImportError::InvalidRecord {
+                file,
+                line,
+                field,
+                id,
+                reason,
+            } => (file, Some(line), format!("{} {} {}", field, id, reason)),
+        };
+        self.add(Severity::Error, file, line, message);
+    }
+
+    pub fn findings(&self) -> &[Finding] {
+        &self.findings
+    }
+
+    pub fn count(&self, severity: Severity) -> usize {
+        self.findings
+            .iter()
+            .filter(|finding| finding.severity == severity)
+            .count()
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.count(Severity::Error) > 0
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for finding in &self.findings {
+            writeln!(formatter, "{}", finding)?;
+        }
+        write!(
+            formatter,
+            "{} errors, {} warnings",
+            self.count(Severity::Error),
+            self.count(Severity::Warning)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report() -> Report {
+        let mut report = Report::new();
+        report.add(
+            Severity::Error,
+            "trips.txt",
+            3,
+            "route_id u4 not found".to_string(),
+        );
+        report.add(
+            Severity::Warning,
+            "calendar.txt",
+            None,
+            "service 1 never runs".to_string(),
+        );
+        report
+    }
+
+    #[test]
+    fn test_count() {
+        let report = report();
+        assert_eq!(report.count(Severity::Error), 1);
+        assert_eq!(report.count(Severity::Warning), 1);
+        assert!(report.has_errors());
+        assert!(!Report::new().has_errors());
+    }
+
+    #[test]
+    fn test_text() {
+        assert_eq!(
+            report().to_string(),
+            "error: trips.txt, line 3: route_id u4 not found\n\
+             warning: calendar.txt: service 1 never runs\n\
+             1 errors, 1 warnings"
+        );
+    }
+
+    #[test]
+    fn test_json() {
+        let json: serde_json::Value = serde_json::from_str(&report().to_json().unwrap()).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "findings": [
+                    {
+                        "severity": "error",
+                        "file": "trips.txt",
+                        "line": 3,
+                        "message": "route_id u4 not found",
+                    },
+                    {
+                        "severity": "warning",
+                        "file": "calendar.txt",
+                        "line": null,
+                        "message": "service 1 never runs",
+                    },
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn test_add_import_error() {
+        let mut report = Report::new();
+        report.add_import_error(ImportError::MissingFile { file: "stops.txt" });
+        assert_eq!(
+            report.findings(),
+            &[Finding::new(
+                Severity::Error,
+                "stops.txt",
+                None,
+                "missing file".to_string()
+            )]
+        );
+    }
+}
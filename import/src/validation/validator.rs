@@ -0,0 +1,105 @@
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+
+use super::{checks, Feed, Report};
+use crate::utils::Dataset;
+use crate::{ImportError, ImportedDataset};
+
+/// Lints a GTFS feed without importing it.
+#[derive(Debug, Clone, Copy)]
+pub struct Validator {
+    max_stop_distance: f64,
+}
+
+impl Validator {
+    /// Creates a validator which reports stops farther than `max_stop_distance` meters away
+    /// from the shape of their trip.
+    pub fn new(max_stop_distance: f64) -> Self {
+        Self { max_stop_distance }
+    }
+
+    fn run(self, mut dataset: impl Dataset) -> Report {
+        let mut report = Report::new();
+        let feed = Feed::read(&mut dataset, &mut report);
+        checks::duplicate_ids(&feed, &mut report);
+        checks::references(&feed, &mut report);
+        checks::calendars(&feed, &mut report);
+        checks::stop_times(&feed, &mut report);
+        checks::stop_distances(&feed, self.max_stop_distance, &mut report);
+        report
+    }
+
+    pub fn validate(self, path: impl AsRef<OsStr>) -> Result<Report, ImportError> {
+        let path = Path::new(&path);
+        if path.is_dir() {
+            let mut path = PathBuf::from(&path);
+            path.push(".txt");
+            Ok(self.run(path))
+        } else {
+            let archive =
+                ImportedDataset::open_archive(path).map_err(|source| ImportError::Archive {
+                    path: path.to_path_buf(),
+                    source,
+                })?;
+            Ok(self.run(archive))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::dataset;
+
+    #[test]
+    fn test_valid_feed() {
+        let dataset = dataset!(
+            routes:
+                route_id;
+                u4
+            trips:
+                trip_id, route_id, service_id;
+                1,       u4,       mon_fri
+            stops:
+                stop_id,           stop_lat, stop_lon;
+                nollendorfplatz,   52.500,   13.354;
+                innsbrucker_platz, 52.478,   13.343
+            stop_times:
+                trip_id, stop_id,           arrival_time, departure_time;
+                1,       nollendorfplatz,   "4:36:00",    "4:36:00";
+                1,       innsbrucker_platz, "4:42:00",    "4:42:00"
+            calendar:
+                service_id, monday, tuesday, wednesday, thursday, friday, saturday, sunday, start_date, end_date;
+                mon_fri,    1,      1,       1,         1,        1,      0,        0,      20190101,   20191231
+        );
+        assert_eq!(Validator::new(100.0).run(dataset), Report::new());
+    }
+
+    #[test]
+    fn test_broken_records() {
+        let dataset = dataset!(
+            routes:
+                route_id;
+                u4
+            trips:
+                trip_id, route_id, service_id;
+                1,       u4,       mon_fri
+            stops:
+                stop_id,           stop_lat, stop_lon;
+                nollendorfplatz,   52.500,   13.354;
+                innsbrucker_platz, north,    13.343
+            calendar:
+                service_id, monday, tuesday, wednesday, thursday, friday, saturday, sunday, start_date, end_date;
+                mon_fri,    1,      1,       1,         1,        1,      0,        0,      20190101,   20191231
+        );
+        let report = Validator::new(100.0).run(dataset);
+        let findings = report
+            .findings()
+            .iter()
+            .map(|finding| (finding.file(), finding.line()))
+            .collect::<Vec<_>>();
+        assert_eq!(findings, [("stops.txt", Some(3)), ("stop_times.txt", None)]);
+    }
+}
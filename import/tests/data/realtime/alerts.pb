@@ -0,0 +1,11 @@
+
+
+2.0A
+construction*1
+*4R
+
+Construction worksdepI
+closure*>
+**friedrichstrR
+
+Friedrichstr. closeddep
\ No newline at end of file
@@ -0,0 +1,75 @@
+use std::time::{Duration, Instant};
+
+use tiny_transport_simulation::fixtures::{nodes, trains};
+use tiny_transport_simulation::line::Kind;
+use tiny_transport_simulation::{Color, Line, Train};
+
+const FRAME: u32 = 60;
+const FRAMES: u32 = 24 * 60 * 60 / FRAME;
+
+fn timetable() -> Vec<Train> {
+    (0..24 * 60 * 60)
+        .step_by(5)
+        .flat_map(|start| {
+            vec![
+                trains::tram_12::oranienburger_tor_am_kupfergraben(start),
+                trains::tram_12::am_kupfergraben_oranienburger_tor(start),
+            ]
+        })
+        .collect()
+}
+
+fn measure(mut frame: impl FnMut(&mut Vec<f32>) -> usize) -> (Duration, usize) {
+    let mut buffer = Vec::new();
+    let mut total = 0;
+    let start = Instant::now();
+    for _ in 0..FRAMES {
+        buffer.clear();
+        total += frame(&mut buffer);
+    }
+    (start.elapsed(), total)
+}
+
+fn main() {
+    let nodes = nodes::tram_12();
+    let mut all_trains = timetable();
+    let (naive, naive_total) = measure(|buffer| {
+        for train in &mut all_trains {
+            train.update(FRAME, &nodes);
+        }
+        let mut count = 0;
+        for train in all_trains.iter().filter(|train| train.is_active()) {
+            train.fill_vertice_buffer(buffer, &nodes);
+            count += 1;
+        }
+        count
+    });
+
+    let mut line = Line::new(
+        "12".to_string(),
+        Kind::Tram.color(),
+        Color::new(0, 0, 0),
+        Kind::Tram,
+        nodes.clone(),
+        timetable(),
+    );
+    let (indexed, indexed_total) = measure(|buffer| {
+        line.update(FRAME);
+        let mut count = 0;
+        for train in line.active_trains() {
+            train.fill_vertice_buffer(buffer, line.nodes());
+            count += 1;
+        }
+        count
+    });
+
+    assert_eq!(naive_total, indexed_total);
+    let trains = timetable().len();
+    let average = indexed_total as f64 / f64::from(FRAMES);
+    println!(
+        "{} trains, {:.1} active per frame on average",
+        trains, average
+    );
+    println!("updating all trains:    {:?} per frame", naive / FRAMES);
+    println!("updating active trains: {:?} per frame", indexed / FRAMES);
+}
@@ -0,0 +1,109 @@
+use serde_derive::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Severity {
+    Unknown = 1,
+    Info = 2,
+    Warning = 3,
+    Severe = 4,
+}
+
+/// A disruption of some lines and stations during certain periods.
+///
+/// Periods are given in seconds since midnight of the first stored service day.
+#[derive(Debug, PartialEq)]
+pub struct Alert {
+    header: String,
+    severity: Severity,
+    periods: Vec<(u32, u32)>,
+    lines: Vec<usize>,
+    stations: Vec<usize>,
+}
+
+impl Alert {
+    pub fn new(
+        header: String,
+        severity: Severity,
+        periods: Vec<(u32, u32)>,
+        lines: Vec<usize>,
+        stations: Vec<usize>,
+    ) -> Self {
+        Self {
+            header,
+            severity,
+            periods,
+            lines,
+            stations,
+        }
+    }
+
+    pub fn header(&self) -> &str {
+        &self.header
+    }
+
+    pub fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    pub fn is_active_at(&self, time: u32) -> bool {
+        self.periods
+            .iter()
+            .any(|&(start, end)| start <= time && time < end)
+    }
+
+    pub fn affects_line(&self, line: usize) -> bool {
+        self.lines.contains(&line)
+    }
+
+    pub fn affects_station(&self, station: usize) -> bool {
+        self.stations.contains(&station)
+    }
+}
+
+#[cfg(any(test, feature = "fixtures"))]
+pub mod fixtures {
+    use super::*;
+    use test_utils::time;
+
+    pub fn closure_friedrichstr() -> Alert {
+        Alert::new(
+            "Friedrichstr. closed".to_string(),
+            Severity::Severe,
+            vec![(time!(9:00:00), time!(12:00:00))],
+            vec![],
+            vec![1],
+        )
+    }
+
+    pub fn construction_tram_12() -> Alert {
+        Alert::new(
+            "Construction works".to_string(),
+            Severity::Warning,
+            vec![(time!(6:00:00), time!(10:00:00))],
+            vec![0],
+            vec![],
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixtures::alerts;
+    use test_utils::time;
+
+    #[test]
+    fn test_is_active_at() {
+        let alert = alerts::closure_friedrichstr();
+        assert!(!alert.is_active_at(time!(8:59:59)));
+        assert!(alert.is_active_at(time!(9:00:00)));
+        assert!(!alert.is_active_at(time!(12:00:00)));
+    }
+
+    #[test]
+    fn test_affects() {
+        let alert = alerts::construction_tram_12();
+        assert!(alert.affects_line(0));
+        assert!(!alert.affects_line(1));
+        assert!(!alert.affects_station(0));
+    }
+}
@@ -0,0 +1,262 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use serde_derive::{Deserialize, Serialize};
+
+use na::Point2;
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Complex {
+    places: Vec<Place>,
+    pathways: Vec<Pathway>,
+}
+
+impl Complex {
+    pub fn new(places: Vec<Place>, pathways: Vec<Pathway>) -> Self {
+        Self { places, pathways }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.places.is_empty()
+    }
+
+    pub fn places(&self) -> &[Place] {
+        &self.places
+    }
+
+    pub fn pathways(&self) -> &[Pathway] {
+        &self.pathways
+    }
+
+    pub fn places_of_kind(&self, kind: PlaceKind) -> impl Iterator<Item = &Place> {
+        self.places.iter().filter(move |place| place.kind == kind)
+    }
+
+    /// Yields the places reachable in one step from the place with the given index,
+    /// together with the time needed for the step.
+    pub fn neighbours(&self, place: usize) -> impl Iterator<Item = (usize, u32)> + '_ {
+        self.pathways.iter().filter_map(move |pathway| {
+            if pathway.from == place {
+                Some((pathway.to, pathway.traversal_time))
+            } else if pathway.bidirectional && pathway.to == place {
+                Some((pathway.from, pathway.traversal_time))
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Computes the shortest walking time between two places of this complex.
+    ///
+    /// Returns `None` if either place is not part of the complex or there is no way between them.
+    pub fn walking_time(&self, from: usize, to: usize) -> Option<u32> {
+        if from >= self.places.len() || to >= self.places.len() {
+            return None;
+        }
+
+        let mut times = vec![None; self.places.len()];
+        let mut queue = BinaryHeap::new();
+        times[from] = Some(0);
+        queue.push(Reverse((0, from)));
+
+        while let Some(Reverse((time, place))) = queue.pop() {
+            if place == to {
+                return Some(time);
+            }
+            if matches!(times[place], Some(best) if best < time) {
+                continue;
+            }
+            for (neighbour, step) in self.neighbours(place) {
+                let candidate = time + step;
+                if !matches!(times[neighbour], Some(best) if best <= candidate) {
+                    times[neighbour] = Some(candidate);
+                    queue.push(Reverse((candidate, neighbour)));
+                }
+            }
+        }
+        None
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Place {
+    kind: PlaceKind,
+    name: String,
+    position: Point2<f32>,
+}
+
+impl Place {
+    pub fn new(kind: PlaceKind, name: String, position: Point2<f32>) -> Self {
+        Self {
+            kind,
+            name,
+            position,
+        }
+    }
+
+    pub fn kind(&self) -> PlaceKind {
+        self.kind
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn position(&self) -> Point2<f32> {
+        self.position
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PlaceKind {
+    Platform,
+    Entrance,
+    GenericNode,
+    BoardingArea,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pathway {
+    from: usize,
+    to: usize,
+    mode: PathwayMode,
+    bidirectional: bool,
+    traversal_time: u32,
+}
+
+impl Pathway {
+    pub fn new(
+        from: usize,
+        to: usize,
+        mode: PathwayMode,
+        bidirectional: bool,
+        traversal_time: u32,
+    ) -> Self {
+        Self {
+            from,
+            to,
+            mode,
+            bidirectional,
+            traversal_time,
+        }
+    }
+
+    pub fn from(&self) -> usize {
+        self.from
+    }
+
+    pub fn to(&self) -> usize {
+        self.to
+    }
+
+    pub fn mode(&self) -> PathwayMode {
+        self.mode
+    }
+
+    pub fn is_bidirectional(&self) -> bool {
+        self.bidirectional
+    }
+
+    pub fn traversal_time(&self) -> u32 {
+        self.traversal_time
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PathwayMode {
+    Walkway,
+    Stairs,
+    MovingSidewalk,
+    Escalator,
+    Elevator,
+    FareGate,
+    ExitGate,
+}
+
+#[cfg(any(test, feature = "fixtures"))]
+pub mod fixtures {
+    use super::*;
+
+    pub fn hauptbahnhof() -> Complex {
+        Complex::new(
+            vec![
+                Place::new(
+                    PlaceKind::Entrance,
+                    "Hauptbahnhof Nord".to_string(),
+                    Point2::new(-1388.0, -1890.0),
+                ),
+                Place::new(
+                    PlaceKind::GenericNode,
+                    "Hauptbahnhof Mittelhalle".to_string(),
+                    Point2::new(-1385.0, -1812.0),
+                ),
+                Place::new(
+                    PlaceKind::Platform,
+                    "Hauptbahnhof Gleis 1".to_string(),
+                    Point2::new(-1380.0, -1810.0),
+                ),
+                Place::new(
+                    PlaceKind::Platform,
+                    "Hauptbahnhof Gleis 16".to_string(),
+                    Point2::new(-1390.0, -1815.0),
+                ),
+            ],
+            vec![
+                Pathway::new(0, 1, PathwayMode::Walkway, true, 90),
+                Pathway::new(1, 2, PathwayMode::Escalator, false, 40),
+                Pathway::new(2, 1, PathwayMode::Stairs, false, 60),
+                Pathway::new(1, 3, PathwayMode::Elevator, true, 120),
+            ],
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::complexes;
+
+    #[test]
+    fn test_places_of_kind() {
+        let complex = complexes::hauptbahnhof();
+        let platforms = complex
+            .places_of_kind(PlaceKind::Platform)
+            .map(Place::name)
+            .collect::<Vec<_>>();
+        assert_eq!(platforms, ["Hauptbahnhof Gleis 1", "Hauptbahnhof Gleis 16"]);
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let complex = complexes::hauptbahnhof();
+        assert_eq!(
+            complex.neighbours(1).collect::<Vec<_>>(),
+            [(0, 90), (2, 40), (3, 120)]
+        );
+        assert_eq!(complex.neighbours(2).collect::<Vec<_>>(), [(1, 60)]);
+    }
+
+    #[test]
+    fn test_walking_time() {
+        let complex = complexes::hauptbahnhof();
+        assert_eq!(complex.walking_time(0, 2), Some(130));
+        assert_eq!(complex.walking_time(2, 0), Some(150));
+        assert_eq!(complex.walking_time(2, 3), Some(180));
+        assert_eq!(complex.walking_time(3, 3), Some(0));
+    }
+
+    #[test]
+    fn test_unreachable_place() {
+        let mut complex = complexes::hauptbahnhof();
+        complex.pathways.pop();
+        assert_eq!(complex.walking_time(0, 3), None);
+    }
+
+    #[test]
+    fn test_place_out_of_range() {
+        let complex = complexes::hauptbahnhof();
+        let count = complex.places().len();
+        assert_eq!(complex.walking_time(count, 0), None);
+        assert_eq!(complex.walking_time(0, count), None);
+    }
+}
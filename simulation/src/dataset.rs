@@ -1,27 +1,247 @@
+use std::collections::HashMap;
 use std::iter;
 use std::ops::Deref;
 use std::rc::Rc;
 
 use na::Point2;
 
+use crate::alert::{Alert, Severity};
+use crate::departure::{self, Departure};
+use crate::info::{TrainId, TrainInfo};
 use crate::line::Line;
-use crate::station::Station;
+use crate::passengers::{self, Demand, Passengers};
+use crate::scenario::{Impact, Scenario};
+use crate::station::{self, Station};
+use crate::train::{Train, DAY};
+use crate::transfer::Transfer;
 
 #[derive(Debug, PartialEq)]
 pub struct Dataset {
     stations: Vec<Rc<Station>>,
     lines: Vec<Line>,
+    station_indices: Vec<Vec<Option<usize>>>,
+    transfers: Vec<Transfer>,
+    alerts: Vec<Alert>,
+    day_count: usize,
+    day: usize,
+    time: u32,
+    passengers: Option<Passengers>,
 }
 
 impl Dataset {
-    pub fn new(stations: Vec<Rc<Station>>, lines: Vec<Line>) -> Self {
-        Self { stations, lines }
+    pub fn new(
+        stations: Vec<Rc<Station>>,
+        lines: Vec<Line>,
+        transfers: Vec<Transfer>,
+        alerts: Vec<Alert>,
+        day_count: usize,
+    ) -> Self {
+        let station_indices = station::station_indices(&stations, &lines);
+        Self {
+            stations,
+            lines,
+            station_indices,
+            transfers,
+            alerts,
+            day_count,
+            day: 0,
+            time: 0,
+            passengers: None,
+        }
+    }
+
+    /// Number of service days the dataset was stored for.
+    pub fn day_count(&self) -> usize {
+        self.day_count
+    }
+
+    /// Index of the current service day, counted from the first stored day.
+    pub fn day(&self) -> usize {
+        self.day
+    }
+
+    /// Restarts the simulation at midnight of the service day, returning `false` if it is not
+    /// stored.
+    pub fn select_day(&mut self, day: usize) -> bool {
+        if day >= self.day_count {
+            return false;
+        }
+        self.start_day(day);
+        true
+    }
+
+    /// Continues with the next service day, returning `false` if it is not stored.
+    pub fn advance_day(&mut self) -> bool {
+        self.select_day(self.day + 1)
     }
 
-    pub fn update(&mut self, time_passed: u32) {
+    fn start_day(&mut self, day: usize) {
+        self.day = day;
+        self.time = 0;
+        for line in &mut self.lines {
+            line.select_day(day);
+        }
+        if let Some(passengers) = &mut self.passengers {
+            passengers.empty_vehicles(&self.lines);
+        }
+    }
+
+    /// Seconds since midnight of the current service day.
+    ///
+    /// This exceeds a day only after the last stored day.
+    pub fn time(&self) -> u32 {
+        self.time
+    }
+
+    /// Jumps to the time since midnight of the current service day.
+    ///
+    /// Times past midnight continue on the next stored day.
+    pub fn set_time(&mut self, mut time: u32) {
+        while self.day + 1 < self.day_count && time >= DAY {
+            time -= DAY;
+            self.start_day(self.day + 1);
+        }
+
+        self.time = time;
+        for line in &mut self.lines {
+            line.set_time(time);
+        }
+        if let Some(passengers) = &mut self.passengers {
+            passengers.empty_vehicles(&self.lines);
+        }
+    }
+
+    /// Moves the simulation forwards or backwards, for scrubbing and reverse playback.
+    ///
+    /// Going back before midnight continues on the previous stored day, but stops at the
+    /// beginning of the first one.
+    pub fn shift_time(&mut self, delta: i32) {
+        let mut time = i64::from(self.time) + i64::from(delta);
+        while time < 0 && self.day > 0 {
+            self.start_day(self.day - 1);
+            time += i64::from(DAY);
+        }
+        self.set_time(time.max(0) as u32);
+    }
+
+    /// Advances the simulation, handing over to the trains of the next stored day at midnight.
+    pub fn update(&mut self, mut time_passed: u32) {
+        while self.day + 1 < self.day_count && self.time + time_passed >= DAY {
+            time_passed -= DAY - self.time;
+            self.start_day(self.day + 1);
+        }
+
+        self.time += time_passed;
+        let progress = self
+            .passengers
+            .as_ref()
+            .map(|_| Passengers::progress(&self.lines));
         for line in &mut self.lines {
             line.update(time_passed);
         }
+        if let (Some(passengers), Some(progress)) = (&mut self.passengers, progress) {
+            passengers.update(time_passed, &self.lines, &progress);
+        }
+    }
+
+    /// Starts simulating passengers travelling between the stations of the demand.
+    ///
+    /// Returns the number of flows ignored because a station of them is unknown or ambiguous,
+    /// since several stations have its name.
+    pub fn set_demand(&mut self, demand: &Demand) -> usize {
+        let mut indices = HashMap::new();
+        for (index, station) in self.stations.iter().enumerate() {
+            indices
+                .entry(station.name())
+                .and_modify(|found| *found = None)
+                .or_insert(Some(index));
+        }
+        let flows = demand
+            .flows()
+            .iter()
+            .filter_map(|flow| {
+                let origin = (*indices.get(flow.from())?)?;
+                let destination = (*indices.get(flow.to())?)?;
+                Some((origin, destination, flow.per_hour()))
+            })
+            .collect::<Vec<_>>();
+        let ignored = demand.flows().len() - flows.len();
+        self.passengers = Some(Passengers::new(
+            flows,
+            self.station_indices.clone(),
+            self.stations.len(),
+            &self.lines,
+        ));
+        ignored
+    }
+
+    /// Starts simulating passengers with a synthetic demand between stations connected by a
+    /// line, totalling the passengers per hour.
+    pub fn set_gravity_demand(&mut self, per_hour: f32) {
+        let flows = passengers::gravity_flows(&self.stations, &self.station_indices, per_hour);
+        self.passengers = Some(Passengers::new(
+            flows,
+            self.station_indices.clone(),
+            self.stations.len(),
+            &self.lines,
+        ));
+    }
+
+    pub fn passengers(&self) -> Option<&Passengers> {
+        self.passengers.as_ref()
+    }
+
+    /// Passengers waiting at each station, which are all zero without a demand.
+    pub fn station_waiting_counts(&self) -> Vec<u32> {
+        (0..self.stations.len())
+            .map(|station| {
+                self.passengers
+                    .as_ref()
+                    .map_or(0, |passengers| passengers.waiting(station))
+            })
+            .collect()
+    }
+
+    /// Passengers on board of each active train relative to the capacity of its vehicles, in the
+    /// order of the other train buffers.
+    pub fn train_loads(&self) -> Vec<f32> {
+        let mut buffer = Vec::new();
+        for (line_index, line) in self.lines.iter().enumerate() {
+            let capacity = line.kind().capacity() as f32;
+            for &train_index in line.active_indices() {
+                let load = self
+                    .passengers
+                    .as_ref()
+                    .map_or(0, |passengers| passengers.load(line_index, train_index));
+                buffer.push(load as f32 / capacity);
+            }
+        }
+        buffer
+    }
+
+    /// Applies the disruptions of the scenario to the trips of all days.
+    ///
+    /// Returns the impact of each disruption, which is `None` if no line has its line name.
+    pub fn apply_scenario(&mut self, scenario: &Scenario) -> Vec<Option<Impact>> {
+        let impacts = scenario
+            .disruptions()
+            .iter()
+            .map(|disruption| {
+                self.lines
+                    .iter_mut()
+                    .filter(|line| line.name() == disruption.line())
+                    .fold(None, |total: Option<Impact>, line| {
+                        let mut total = total.unwrap_or_default();
+                        total += line.apply_disruption(disruption);
+                        Some(total)
+                    })
+            })
+            .collect();
+
+        let time = self.time;
+        self.start_day(self.day);
+        self.set_time(time);
+        impacts
     }
 
     pub fn station_count(&self) -> usize {
@@ -44,11 +264,181 @@ impl Dataset {
         buffer
     }
 
+    /// Index of the first station with the name.
+    pub fn station_index(&self, name: &str) -> Option<usize> {
+        self.stations
+            .iter()
+            .position(|station| station.name() == name)
+    }
+
+    /// The next departures from the station, ordered by the time they are expected to leave.
+    ///
+    /// Only trains of the current service day leaving at or after the time since its midnight
+    /// are listed, trips of the following day are not included.
+    pub fn departures(&self, station: usize, from_time: u32, count: usize) -> Vec<Departure> {
+        let mut departures = Vec::new();
+        if count == 0 {
+            return departures;
+        }
+        for (line_index, line) in self.lines.iter().enumerate() {
+            let stations = &self.station_indices[line_index];
+            if !stations.contains(&Some(station)) {
+                continue;
+            }
+
+            let mut line_departures: Vec<Departure> = Vec::new();
+            for (after, until, train) in line.trains_by_dispatch() {
+                if until < i64::from(from_time) {
+                    continue;
+                }
+                // trains leave no stop before they are dispatched
+                if line_departures.len() >= count {
+                    line_departures.sort_by_key(Departure::expected_time);
+                    line_departures.truncate(count);
+                    if after >= i64::from(line_departures[count - 1].expected_time()) {
+                        break;
+                    }
+                }
+
+                let shift = if train.is_from_previous_day() { DAY } else { 0 };
+                let stops = train.served_stops();
+                let headsign = match stops.last() {
+                    Some(&(_, node)) => line.station_name(node),
+                    None => continue,
+                };
+                for &(index, node) in &stops[..stops.len() - 1] {
+                    let (scheduled, expected) =
+                        (train.scheduled_departure(index), train.departure(index));
+                    if stations[node] != Some(station) || expected < from_time + shift {
+                        continue;
+                    }
+                    line_departures.push(Departure::new(
+                        self.train_id(line_index, train),
+                        line.name().to_string(),
+                        headsign.to_string(),
+                        scheduled.saturating_sub(shift),
+                        expected.saturating_sub(scheduled),
+                        departure::platform(&self.stations[station], train.platform(index)),
+                    ));
+                }
+            }
+            departures.append(&mut line_departures);
+        }
+        departures.sort_by_key(Departure::expected_time);
+        departures.truncate(count);
+        departures
+    }
+
     pub fn find_station(&self, position: Point2<f32>) -> Option<&Station> {
+        self.find_station_index(position)
+            .map(|index| self.stations[index].deref())
+    }
+
+    /// Index of the station at the position.
+    pub fn find_station_index(&self, position: Point2<f32>) -> Option<usize> {
         self.stations
             .iter()
-            .find(|station| station.contains(position))
-            .map(|station| station.deref())
+            .position(|station| station.contains(position))
+    }
+
+    pub fn station(&self, index: usize) -> Option<&Station> {
+        self.stations.get(index).map(Rc::deref)
+    }
+
+    fn train_id(&self, line: usize, train: &Train) -> TrainId {
+        let service_day = if train.is_from_previous_day() {
+            self.day
+        } else {
+            self.day + 1
+        };
+        TrainId::new(line, train.trip(), train.part(), service_day)
+    }
+
+    /// The active train drawn at the position, preferring the one closest to its center.
+    pub fn find_train(&self, position: Point2<f32>) -> Option<TrainId> {
+        self.lines
+            .iter()
+            .enumerate()
+            .flat_map(|(line_index, line)| {
+                line.active_trains()
+                    .filter(move |train| train.contains(position, line.nodes()))
+                    .map(move |train| {
+                        let (center, _) = train.position(line.nodes()).unwrap();
+                        (line_index, train, na::distance(&center, &position))
+                    })
+            })
+            .min_by(|(_, _, a), (_, _, b)| a.partial_cmp(b).unwrap())
+            .map(|(line_index, train, _)| self.train_id(line_index, train))
+    }
+
+    /// Details about the train, which is `None` if it does not run on the current service day.
+    pub fn train_info(&self, id: TrainId) -> Option<TrainInfo> {
+        let line = self.lines.get(id.line())?;
+        line.trains()
+            .iter()
+            .filter(|train| self.train_id(id.line(), train) == id)
+            .max_by_key(|train| train.is_active())
+            .map(|train| TrainInfo::new(id, line, train))
+    }
+
+    /// The active trains of the line, which are none if the line does not exist.
+    pub fn trains_of_line(&self, line_index: usize) -> Vec<TrainId> {
+        self.lines.get(line_index).map_or_else(Vec::new, |line| {
+            line.active_trains()
+                .map(|train| self.train_id(line_index, train))
+                .collect()
+        })
+    }
+
+    pub fn transfers_from(&self, station: usize) -> impl Iterator<Item = &Transfer> {
+        self.transfers
+            .iter()
+            .filter(move |transfer| transfer.origin() == station)
+    }
+
+    pub fn lines(&self) -> &[Line] {
+        &self.lines
+    }
+
+    pub fn active_alerts(&self) -> impl Iterator<Item = &Alert> {
+        let time = self.day as u32 * DAY + self.time;
+        self.alerts
+            .iter()
+            .filter(move |alert| alert.is_active_at(time))
+    }
+
+    /// The highest severity of the currently active alerts for the line.
+    pub fn line_severity(&self, line: usize) -> Option<Severity> {
+        self.active_alerts()
+            .filter(|alert| alert.affects_line(line))
+            .map(Alert::severity)
+            .max()
+    }
+
+    /// The highest severity of the currently active alerts for the station.
+    pub fn station_severity(&self, station: usize) -> Option<Severity> {
+        self.active_alerts()
+            .filter(|alert| alert.affects_station(station))
+            .map(Alert::severity)
+            .max()
+    }
+
+    pub fn line_severities(&self) -> Vec<u8> {
+        (0..self.lines.len())
+            .map(|line| {
+                self.line_severity(line)
+                    .map_or(0, |severity| severity as u8)
+            })
+            .collect()
+    }
+
+    pub fn station_severities(&self) -> Vec<u8> {
+        (0..self.stations.len())
+            .map(|station| {
+                self.station_severity(station)
+                    .map_or(0, |severity| severity as u8)
+            })
+            .collect()
     }
 
     pub fn line_count(&self) -> usize {
@@ -63,6 +453,14 @@ impl Dataset {
         colors
     }
 
+    pub fn line_text_colors(&self) -> Vec<f32> {
+        let mut colors = Vec::new();
+        for line in &self.lines {
+            line.fill_text_color_buffer(&mut colors);
+        }
+        colors
+    }
+
     fn line_vertices_with_sizes(&self) -> (Vec<f32>, Vec<usize>) {
         let mut vertices = Vec::new();
         let mut sizes = Vec::new();
@@ -149,19 +547,27 @@ impl Dataset {
 #[cfg(any(test, feature = "fixtures"))]
 pub mod fixtures {
     use super::*;
-    use crate::fixtures::{lines, stations};
+    use crate::fixtures::{alerts, lines, stations};
+    use crate::transfer::Kind as TransferKind;
 
     macro_rules! datasets {
         ( $( $dataset:ident => {
                 stations: [ $($station:ident),* $(,)? ],
                 lines: [ $($line:ident),* $(,)? ],
+                transfers: [ $( $from:literal -> $to:literal: $kind:ident, $min_time:expr );* $(;)? ],
+                alerts: [ $($alert:ident),* $(,)? ],
             } ),* $(,)? ) => {
             $(
                 pub fn $dataset() -> Dataset {
-                    Dataset {
-                        stations: vec![ $(Rc::new(stations::$station())),* ],
-                        lines: vec![ $(lines::$line()),* ],
-                    }
+                    Dataset::new(
+                        vec![ $(stations::shared(stations::$station)),* ],
+                        vec![ $(lines::$line()),* ],
+                        vec![
+                            $( Transfer::new($from, $to, TransferKind::$kind, $min_time) ),*
+                        ],
+                        vec![ $(alerts::$alert()),* ],
+                        1,
+                    )
                 }
             )*
         }
@@ -174,6 +580,8 @@ pub mod fixtures {
                 georgenstr_am_kupfergraben,
             ],
             lines: [tram_12],
+            transfers: [],
+            alerts: [construction_tram_12, closure_friedrichstr],
         },
         hauptbahnhof_friedrichstr => {
             stations: [
@@ -182,13 +590,29 @@ pub mod fixtures {
                 universitaetsstr, am_kupfergraben, georgenstr_am_kupfergraben,
             ],
             lines: [u6, s3, tram_12],
+            transfers: [
+                1 -> 6: MinimumTime, Some(240);
+                6 -> 1: MinimumTime, Some(240);
+                1 -> 7: Recommended, None;
+            ],
+            alerts: [],
         },
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::fixtures::datasets;
+    use std::rc::Rc;
+
+    use approx::assert_relative_eq;
+    use na::Point2;
+
+    use crate::alert::Severity;
+    use crate::fixtures::{datasets, stations};
+    use crate::passengers::{Demand, Flow};
+    use crate::scenario::{Disruption, DisruptionKind, Scenario};
+    use crate::transfer::Kind as TransferKind;
+    use test_utils::time;
 
     #[test]
     fn test_static_data() {
@@ -198,8 +622,342 @@ mod tests {
         assert_eq!(dataset.station_types().len(), 5);
         assert_eq!(dataset.line_count(), 1);
         assert_eq!(dataset.line_colors().len(), 3);
+        assert_eq!(dataset.line_text_colors().len(), 3);
         assert_eq!(dataset.line_vertices_sizes(), [20, 28]);
         assert_eq!(dataset.line_vertices().len(), 2 * 48);
         assert_eq!(dataset.line_names(), "12".to_string());
     }
+
+    #[test]
+    fn test_transfers_from() {
+        let dataset = datasets::hauptbahnhof_friedrichstr();
+        let transfers = dataset
+            .transfers_from(1)
+            .map(|transfer| (transfer.target(), transfer.kind(), transfer.min_time()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            transfers,
+            [
+                (6, TransferKind::MinimumTime, Some(240)),
+                (7, TransferKind::Recommended, None),
+            ]
+        );
+        assert_eq!(dataset.transfers_from(0).count(), 0);
+    }
+
+    #[test]
+    fn test_alerts() {
+        let mut dataset = datasets::tram_12();
+        assert_eq!(dataset.active_alerts().count(), 0);
+        assert_eq!(dataset.line_severities(), [0]);
+
+        dataset.update(time!(9:30:00));
+        assert_eq!(dataset.active_alerts().count(), 2);
+        assert_eq!(dataset.line_severity(0), Some(Severity::Warning));
+        assert_eq!(dataset.station_severity(1), Some(Severity::Severe));
+        assert_eq!(dataset.station_severities(), [0, 4, 0, 0, 0]);
+
+        dataset.update(time!(1:00:00));
+        assert_eq!(dataset.line_severity(0), None);
+        assert_eq!(dataset.station_severity(1), Some(Severity::Severe));
+    }
+
+    #[test]
+    fn test_apply_scenario() {
+        let mut dataset = datasets::tram_12();
+        dataset.set_time(time!(9:03:00));
+        assert_eq!(dataset.train_count(), 1);
+
+        let scenario = Scenario::new(
+            "Tram strike".to_string(),
+            vec![
+                Disruption::new("12".to_string(), None, None, DisruptionKind::Cancellation),
+                Disruption::new("M10".to_string(), None, None, DisruptionKind::Cancellation),
+            ],
+        );
+        let impacts = dataset.apply_scenario(&scenario);
+        assert_eq!(impacts[0].unwrap().cancelled_trips(), 2);
+        assert_eq!(impacts[1], None);
+        assert_eq!(dataset.time(), time!(9:03:00));
+        assert_eq!(dataset.train_count(), 0);
+    }
+
+    #[test]
+    fn test_find_station_index() {
+        let dataset = datasets::tram_12();
+        let position = dataset.station(1).unwrap().position();
+        assert_eq!(dataset.find_station_index(position), Some(1));
+        assert_eq!(
+            dataset.find_station(position).unwrap().name(),
+            "Friedrichstr."
+        );
+        assert_eq!(dataset.find_station_index(Point2::new(1e6, 1e6)), None);
+        assert!(dataset.station(5).is_none());
+    }
+
+    #[test]
+    fn test_passengers() {
+        let mut dataset = datasets::tram_12();
+        assert_eq!(dataset.station_waiting_counts(), [0, 0, 0, 0, 0]);
+
+        let demand = Demand::new(vec![
+            Flow::new(
+                "Oranienburger Tor".to_string(),
+                "Am Kupfergraben".to_string(),
+                6.0,
+            ),
+            Flow::new(
+                "Oranienburger Tor".to_string(),
+                "Alexanderplatz".to_string(),
+                6.0,
+            ),
+            Flow::new(
+                "Friedrichstr.".to_string(),
+                "Oranienburger Tor".to_string(),
+                6.0,
+            ),
+        ]);
+        assert_eq!(dataset.set_demand(&demand), 1);
+        dataset.update(time!(9:01:45));
+        assert_eq!(dataset.train_loads(), [54.0 / 180.0]);
+        assert_eq!(dataset.station_waiting_counts(), [0, 54, 0, 0, 0]);
+
+        // seeking returns the passengers on board to their origin
+        dataset.set_time(time!(9:03:00));
+        assert_eq!(dataset.train_loads(), [0.0]);
+        assert_eq!(dataset.station_waiting_counts(), [54, 54, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_demand_with_ambiguous_station() {
+        let mut dataset = datasets::tram_12();
+        dataset.stations.push(Rc::new(stations::friedrichstr()));
+
+        let demand = Demand::new(vec![Flow::new(
+            "Friedrichstr.".to_string(),
+            "Oranienburger Tor".to_string(),
+            6.0,
+        )]);
+        assert_eq!(dataset.set_demand(&demand), 1);
+    }
+
+    #[test]
+    fn test_gravity_demand() {
+        let mut dataset = datasets::tram_12();
+        dataset.set_gravity_demand(600.0);
+        dataset.update(time!(9:01:45));
+        assert!(dataset.train_loads()[0] > 0.0);
+        assert!(dataset.station_waiting_counts()[1] > 0);
+    }
+
+    #[test]
+    fn test_train_info() {
+        let mut dataset = datasets::tram_12();
+        dataset.set_time(time!(9:03:00));
+        let ids = dataset.trains_of_line(0);
+        assert_eq!(ids.len(), 1);
+        assert_eq!((ids[0].line(), ids[0].trip()), (0, 0));
+
+        assert!(dataset.trains_of_line(1).is_empty());
+
+        let info = dataset.train_info(ids[0]).unwrap();
+        assert_eq!(info.line(), "12");
+        assert_eq!(info.headsign(), "Am Kupfergraben");
+        assert_eq!(info.origin(), "Oranienburger Tor");
+        assert_eq!(info.destination(), "Am Kupfergraben");
+        assert_eq!(info.current_stop(), None);
+        assert_eq!(info.next_stop(), Some("Friedrichstr."));
+        assert_eq!(info.delay(), 0);
+        assert_relative_eq!(info.progress(), 60.0 / 300.0);
+
+        dataset.set_time(time!(9:04:30));
+        let info = dataset.train_info(ids[0]).unwrap();
+        assert_eq!(info.current_stop(), Some("Friedrichstr."));
+        assert_eq!(info.next_stop(), Some("Universitätsstr."));
+    }
+
+    #[test]
+    fn test_train_info_of_split_trip() {
+        let mut dataset = datasets::tram_12();
+        let scenario = Scenario::new(
+            "Closure".to_string(),
+            vec![Disruption::new(
+                "12".to_string(),
+                None,
+                None,
+                DisruptionKind::Closure {
+                    between: "Friedrichstr.".to_string(),
+                    and: "Universitätsstr.".to_string(),
+                },
+            )],
+        );
+        dataset.apply_scenario(&scenario);
+        dataset.set_time(time!(9:03:00));
+        let first = dataset.trains_of_line(0)[0];
+        dataset.set_time(time!(9:06:30));
+        let second = dataset.trains_of_line(0)[0];
+        assert_ne!(first, second);
+
+        let info = dataset.train_info(first).unwrap();
+        assert_eq!(info.origin(), "Oranienburger Tor");
+        assert_eq!(info.destination(), "Friedrichstr.");
+        let info = dataset.train_info(second).unwrap();
+        assert_eq!(info.origin(), "Universitätsstr.");
+        assert_eq!(info.destination(), "Am Kupfergraben");
+    }
+
+    #[test]
+    fn test_train_info_with_delay() {
+        let mut dataset = datasets::tram_12();
+        let scenario = Scenario::new(
+            "Delay".to_string(),
+            vec![Disruption::new(
+                "12".to_string(),
+                None,
+                None,
+                DisruptionKind::Delay {
+                    at: None,
+                    delay: 120,
+                },
+            )],
+        );
+        dataset.apply_scenario(&scenario);
+        dataset.set_time(time!(9:05:00));
+        let id = dataset.trains_of_line(0)[0];
+        let info = dataset.train_info(id).unwrap();
+        assert_eq!(info.delay(), 120);
+        assert_eq!(
+            info.to_string(),
+            "12 → Am Kupfergraben (+2 min), next Friedrichstr."
+        );
+    }
+
+    #[test]
+    fn test_departures() {
+        let dataset = datasets::tram_12();
+        let friedrichstr = dataset.station_index("Friedrichstr.").unwrap();
+        let departures = dataset.departures(friedrichstr, 0, 5);
+        let board = departures
+            .iter()
+            .map(|departure| (departure.time(), departure.line(), departure.headsign()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            board,
+            [
+                (time!(8:37:28), "12", "Oranienburger Tor"),
+                (time!(9:04:35), "12", "Am Kupfergraben"),
+            ]
+        );
+        assert_eq!(departures[1].train().trip(), 0);
+        assert_eq!(departures[1].platform(), None);
+
+        assert_eq!(dataset.departures(friedrichstr, time!(9:00:00), 5).len(), 1);
+        assert_eq!(dataset.departures(friedrichstr, 0, 1).len(), 1);
+        // the terminus of the downstream trip
+        assert_eq!(dataset.departures(0, 0, 5).len(), 1);
+        assert_eq!(dataset.station_index("Alexanderplatz"), None);
+    }
+
+    #[test]
+    fn test_departures_with_delay() {
+        let mut dataset = datasets::tram_12();
+        let scenario = Scenario::new(
+            "Delay".to_string(),
+            vec![Disruption::new(
+                "12".to_string(),
+                Some(time!(9:00:00)),
+                None,
+                DisruptionKind::Delay {
+                    at: None,
+                    delay: 120,
+                },
+            )],
+        );
+        dataset.apply_scenario(&scenario);
+        let departures = dataset.departures(1, time!(9:05:00), 5);
+        assert_eq!(departures.len(), 1);
+        assert_eq!(departures[0].time(), time!(9:04:35));
+        assert_eq!(departures[0].delay(), 120);
+    }
+
+    #[test]
+    fn test_find_train() {
+        let mut dataset = datasets::tram_12();
+        dataset.set_time(time!(9:03:00));
+        let line = &dataset.lines()[0];
+        let train = line.active_trains().next().unwrap();
+        let (position, orientation) = train.position(line.nodes()).unwrap();
+        let id = dataset.trains_of_line(0)[0];
+        assert_eq!(dataset.find_train(position), Some(id));
+        assert_eq!(dataset.find_train(position + 70.0 * orientation), Some(id));
+        assert_eq!(dataset.find_train(position + 90.0 * orientation), None);
+        assert_eq!(dataset.find_train(Point2::new(0.0, 0.0)), None);
+    }
+
+    #[test]
+    fn test_select_day() {
+        let mut dataset = datasets::tram_12();
+        dataset.update(time!(9:03:00));
+        assert_eq!(dataset.train_count(), 1);
+        assert!(!dataset.advance_day());
+
+        dataset.day_count = 2;
+        assert!(dataset.advance_day());
+        assert_eq!(dataset.day(), 1);
+        dataset.update(time!(9:30:00));
+        assert_eq!(dataset.train_count(), 0);
+        assert_eq!(dataset.active_alerts().count(), 0);
+
+        assert!(!dataset.select_day(2));
+        assert_eq!(dataset.day(), 1);
+        assert!(dataset.select_day(0));
+        dataset.update(time!(9:03:00));
+        assert_eq!(dataset.train_count(), 1);
+    }
+
+    #[test]
+    fn test_set_time() {
+        let mut dataset = datasets::tram_12();
+        dataset.set_time(time!(9:30:00));
+        assert_eq!(dataset.active_alerts().count(), 2);
+        dataset.set_time(time!(9:03:00));
+        assert_eq!(dataset.train_count(), 1);
+        assert_eq!(dataset.time(), time!(9:03:00));
+
+        let mut updated = datasets::tram_12();
+        updated.update(time!(9:03:00));
+        assert_eq!(dataset.train_vertices(), updated.train_vertices());
+    }
+
+    #[test]
+    fn test_shift_time() {
+        let mut dataset = datasets::tram_12();
+        dataset.day_count = 2;
+        dataset.set_time(time!(25:00:00));
+        assert_eq!((dataset.day(), dataset.time()), (1, time!(1:00:00)));
+
+        dataset.shift_time(-time!(16:00:00));
+        assert_eq!((dataset.day(), dataset.time()), (0, time!(9:00:00)));
+        dataset.shift_time(time!(0:03:00));
+        assert_eq!(dataset.train_count(), 1);
+
+        dataset.shift_time(-time!(12:00:00));
+        assert_eq!((dataset.day(), dataset.time()), (0, 0));
+    }
+
+    #[test]
+    fn test_update_past_midnight() {
+        let mut dataset = datasets::tram_12();
+        dataset.update(time!(23:00:00));
+        dataset.update(time!(2:00:00));
+        assert_eq!(dataset.day(), 0);
+        assert_eq!(dataset.time(), time!(25:00:00));
+
+        assert!(dataset.select_day(0));
+        dataset.day_count = 2;
+        dataset.update(time!(23:00:00));
+        dataset.update(time!(2:00:00));
+        assert_eq!(dataset.day(), 1);
+        assert_eq!(dataset.time(), time!(1:00:00));
+    }
 }
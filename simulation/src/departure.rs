@@ -0,0 +1,148 @@
+use std::fmt;
+
+use crate::complex::PlaceKind;
+use crate::info::TrainId;
+use crate::station::Station;
+use crate::time::TimeDisplay;
+
+/// A train leaving a station, as listed on its departure board.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Departure {
+    train: TrainId,
+    line: String,
+    headsign: String,
+    time: u32,
+    delay: u32,
+    platform: Option<String>,
+}
+
+impl Departure {
+    pub(crate) fn new(
+        train: TrainId,
+        line: String,
+        headsign: String,
+        time: u32,
+        delay: u32,
+        platform: Option<String>,
+    ) -> Self {
+        Self {
+            train,
+            line,
+            headsign,
+            time,
+            delay,
+            platform,
+        }
+    }
+
+    pub fn train(&self) -> TrainId {
+        self.train
+    }
+
+    /// Name of the line the train runs on.
+    pub fn line(&self) -> &str {
+        &self.line
+    }
+
+    /// The destination shown on the train, which is the name of the last station it serves.
+    pub fn headsign(&self) -> &str {
+        &self.headsign
+    }
+
+    /// Scheduled time of the departure since midnight of the current service day.
+    pub fn time(&self) -> u32 {
+        self.time
+    }
+
+    /// Time the train is expected to leave since midnight of the current service day.
+    pub fn expected_time(&self) -> u32 {
+        self.time + self.delay
+    }
+
+    /// Seconds the train leaves after the scheduled time.
+    pub fn delay(&self) -> u32 {
+        self.delay
+    }
+
+    pub fn platform(&self) -> Option<&str> {
+        self.platform.as_deref()
+    }
+}
+
+impl fmt::Display for Departure {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "{} {} → {}",
+            TimeDisplay(self.time),
+            self.line,
+            self.headsign
+        )?;
+        if self.delay > 0 {
+            write!(formatter, " (+{} min)", self.delay.div_ceil(60))?;
+        }
+        if let Some(platform) = &self.platform {
+            write!(formatter, ", {}", platform)?;
+        }
+        Ok(())
+    }
+}
+
+/// Name of the platform with the index among the places of the complex of the station.
+///
+/// This is `None` if the dataset gives no platform or the place is not a platform.
+pub(crate) fn platform(station: &Station, platform: Option<usize>) -> Option<String> {
+    station
+        .complex()
+        .places()
+        .get(platform?)
+        .filter(|place| place.kind() == PlaceKind::Platform)
+        .map(|place| place.name().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use na::Point2;
+
+    use super::*;
+    use crate::fixtures::{complexes, stations};
+    use crate::station::Kind as StationKind;
+    use test_utils::time;
+
+    #[test]
+    fn test_platform() {
+        let hauptbahnhof = Station::new(
+            Point2::new(-1385.0, -1812.0),
+            "Hauptbahnhof".to_string(),
+            StationKind::Interchange,
+            complexes::hauptbahnhof(),
+        );
+        assert_eq!(
+            platform(&hauptbahnhof, Some(3)),
+            Some("Hauptbahnhof Gleis 16".to_string())
+        );
+        assert_eq!(platform(&hauptbahnhof, Some(0)), None);
+        assert_eq!(platform(&hauptbahnhof, Some(4)), None);
+        assert_eq!(platform(&hauptbahnhof, None), None);
+        assert_eq!(platform(&stations::friedrichstr(), Some(0)), None);
+    }
+
+    #[test]
+    fn test_display() {
+        let mut departure = Departure::new(
+            TrainId::new(0, 0, 0, 1),
+            "12".to_string(),
+            "Am Kupfergraben".to_string(),
+            time!(9:02:20),
+            0,
+            None,
+        );
+        assert_eq!(departure.to_string(), "9:02:20 12 → Am Kupfergraben");
+        departure.delay = 90;
+        departure.platform = Some("Gleis 2".to_string());
+        assert_eq!(
+            departure.to_string(),
+            "9:02:20 12 → Am Kupfergraben (+2 min), Gleis 2"
+        );
+    }
+}
@@ -2,7 +2,7 @@ use serde_derive::{Deserialize, Serialize};
 
 use crate::Node;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Direction {
     Upstream,
     Downstream,
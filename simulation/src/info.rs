@@ -0,0 +1,194 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::line::Line;
+use crate::train::Train;
+
+/// Identifies a train of the dataset, which stays the same while the train runs past midnight.
+///
+/// The parts of a trip split by a disruption are told apart by the part, which is the duration
+/// index of the first stop they serve.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TrainId {
+    line: usize,
+    trip: usize,
+    part: usize,
+    service_day: usize,
+}
+
+impl TrainId {
+    /// The service day is counted from the day before the first stored day.
+    pub(crate) fn new(line: usize, trip: usize, part: usize, service_day: usize) -> Self {
+        Self {
+            line,
+            trip,
+            part,
+            service_day,
+        }
+    }
+
+    /// Index of the line in the dataset.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Index of the trip in the timetable of the line.
+    pub fn trip(&self) -> usize {
+        self.trip
+    }
+}
+
+impl fmt::Display for TrainId {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "{}/{}/{}/{}",
+            self.line, self.trip, self.part, self.service_day
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct InvalidTrainIdError(String);
+
+impl fmt::Display for InvalidTrainIdError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "train id '{}' is invalid", self.0)
+    }
+}
+
+impl Error for InvalidTrainIdError {}
+
+impl FromStr for TrainId {
+    type Err = InvalidTrainIdError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let mut parts = value.split('/').map(str::parse);
+        match (
+            parts.next(),
+            parts.next(),
+            parts.next(),
+            parts.next(),
+            parts.next(),
+        ) {
+            (Some(Ok(line)), Some(Ok(trip)), Some(Ok(part)), Some(Ok(service_day)), None) => {
+                Ok(Self::new(line, trip, part, service_day))
+            }
+            _ => Err(InvalidTrainIdError(value.to_string())),
+        }
+    }
+}
+
+/// Details about a train at the current time of the simulation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrainInfo {
+    id: TrainId,
+    line: String,
+    headsign: String,
+    origin: String,
+    destination: String,
+    current_stop: Option<String>,
+    next_stop: Option<String>,
+    delay: u32,
+    progress: f32,
+}
+
+impl TrainInfo {
+    pub(crate) fn new(id: TrainId, line: &Line, train: &Train) -> Self {
+        let name = |node: usize| line.station_name(node).to_string();
+        let stops = train.served_stops();
+        let origin = stops
+            .first()
+            .map(|&(_, node)| name(node))
+            .unwrap_or_default();
+        let destination = stops
+            .last()
+            .map(|&(_, node)| name(node))
+            .unwrap_or_default();
+        let (current_stop, next_stop) = train.current_and_next_stop();
+
+        Self {
+            id,
+            line: line.name().to_string(),
+            headsign: destination.clone(),
+            origin,
+            destination,
+            current_stop: current_stop.map(name),
+            next_stop: next_stop.map(name),
+            delay: train.delay(),
+            progress: train.progress(),
+        }
+    }
+
+    pub fn id(&self) -> TrainId {
+        self.id
+    }
+
+    /// Name of the line the train runs on.
+    pub fn line(&self) -> &str {
+        &self.line
+    }
+
+    /// The destination shown on the train, which is the name of the last station it serves.
+    pub fn headsign(&self) -> &str {
+        &self.headsign
+    }
+
+    pub fn origin(&self) -> &str {
+        &self.origin
+    }
+
+    pub fn destination(&self) -> &str {
+        &self.destination
+    }
+
+    /// The station the train currently stands at.
+    pub fn current_stop(&self) -> Option<&str> {
+        self.current_stop.as_deref()
+    }
+
+    /// The next station the train stops at.
+    pub fn next_stop(&self) -> Option<&str> {
+        self.next_stop.as_deref()
+    }
+
+    /// Seconds the train runs behind its schedule.
+    pub fn delay(&self) -> u32 {
+        self.delay
+    }
+
+    /// Fraction of the trip completed, between zero and one.
+    pub fn progress(&self) -> f32 {
+        self.progress
+    }
+}
+
+impl fmt::Display for TrainInfo {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "{} → {}", self.line, self.headsign)?;
+        if self.delay > 0 {
+            write!(formatter, " (+{} min)", self.delay.div_ceil(60))?;
+        }
+        match (&self.current_stop, &self.next_stop) {
+            (Some(current), _) => write!(formatter, ", at {}", current),
+            (None, Some(next)) => write!(formatter, ", next {}", next),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_train_id() {
+        let id = TrainId::new(2, 14, 0, 1);
+        assert_eq!(id.to_string(), "2/14/0/1");
+        assert_eq!("2/14/0/1".parse::<TrainId>().unwrap(), id);
+        assert!("2/14/1".parse::<TrainId>().is_err());
+        assert!("2/14/0/1/0".parse::<TrainId>().is_err());
+        assert!("a/14/0/1".parse::<TrainId>().is_err());
+    }
+}
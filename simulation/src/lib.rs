@@ -1,23 +1,43 @@
 #![allow(clippy::module_inception, clippy::zero_prefixed_literal)]
 
+pub mod alert;
 mod color;
+pub mod complex;
 mod dataset;
+mod departure;
 mod direction;
+mod info;
 pub mod line;
+mod motion;
 mod node;
+pub mod passengers;
+pub mod scenario;
 pub mod station;
+mod time;
 mod train;
+pub mod transfer;
 
+pub use crate::alert::{Alert, Severity};
 pub use crate::color::Color;
+pub use crate::complex::Complex;
 pub use crate::dataset::Dataset;
+pub use crate::departure::Departure;
 pub use crate::direction::{Direction, Directions};
+pub use crate::info::{InvalidTrainIdError, TrainId, TrainInfo};
 pub use crate::line::Line;
+pub use crate::motion::MotionProfile;
 pub use crate::node::{Kind as NodeKind, Node};
+pub use crate::passengers::{Demand, Passengers};
+pub use crate::scenario::{Impact, Scenario};
 pub use crate::station::Station;
-pub use crate::train::Train;
+pub use crate::time::{parse_time, TimeDisplay};
+pub use crate::train::{StopStatus, Train};
+pub use crate::transfer::Transfer;
 
 #[cfg(any(test, feature = "fixtures"))]
 pub mod fixtures {
+    pub use crate::alert::fixtures as alerts;
+    pub use crate::complex::fixtures as complexes;
     pub use crate::dataset::fixtures as datasets;
     pub use crate::line::fixtures::*;
     pub use crate::node::fixtures as nodes;
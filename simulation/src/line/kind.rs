@@ -3,6 +3,7 @@ use serde_derive::{Deserialize, Serialize};
 use na::Vector2;
 
 use crate::color::Color;
+use crate::motion::MotionProfile;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Kind {
@@ -12,37 +13,103 @@ pub enum Kind {
     Bus,
     Tram,
     WaterTransport,
+    // appended to keep the stored numbering of the kinds above
+    RegionalRailway,
+    Monorail,
+    Coach,
+    Trolleybus,
+    CableTram,
+    Air,
+    AerialLift,
+    Funicular,
+    Taxi,
 }
 
 impl Kind {
     pub(crate) fn line_width(self) -> f32 {
         match self {
             Kind::Railway => 50.0,
+            Kind::RegionalRailway => 45.0,
             Kind::SuburbanRailway | Kind::UrbanRailway => 40.0,
-            Kind::WaterTransport => 30.0,
-            Kind::Bus | Kind::Tram => 15.0,
+            Kind::Monorail | Kind::WaterTransport | Kind::Air => 30.0,
+            Kind::Coach | Kind::Funicular => 20.0,
+            Kind::Bus | Kind::Trolleybus | Kind::Tram | Kind::CableTram => 15.0,
+            Kind::AerialLift | Kind::Taxi => 10.0,
         }
     }
 
     pub fn train_size(self) -> Vector2<f32> {
         match self {
-            Kind::Railway | Kind::SuburbanRailway | Kind::UrbanRailway => {
+            Kind::Railway | Kind::RegionalRailway | Kind::SuburbanRailway | Kind::UrbanRailway => {
                 Vector2::new(220.0, 150.0)
             }
-            Kind::WaterTransport => Vector2::new(180.0, 120.0),
-            Kind::Tram => Vector2::new(160.0, 100.0),
-            Kind::Bus => Vector2::new(130.0, 100.0),
+            Kind::Air => Vector2::new(200.0, 200.0),
+            Kind::Monorail | Kind::WaterTransport => Vector2::new(180.0, 120.0),
+            Kind::Tram | Kind::CableTram => Vector2::new(160.0, 100.0),
+            Kind::Coach => Vector2::new(150.0, 100.0),
+            Kind::Bus | Kind::Trolleybus => Vector2::new(130.0, 100.0),
+            Kind::Funicular => Vector2::new(120.0, 90.0),
+            Kind::AerialLift => Vector2::new(80.0, 80.0),
+            Kind::Taxi => Vector2::new(60.0, 50.0),
+        }
+    }
+
+    /// Typical acceleration, deceleration and top speed of the vehicles.
+    pub fn motion_profile(self) -> MotionProfile {
+        match self {
+            Kind::Railway => MotionProfile::new(0.5, 0.6, 44.0),
+            Kind::RegionalRailway => MotionProfile::new(0.8, 0.9, 44.0),
+            Kind::SuburbanRailway => MotionProfile::new(1.0, 1.0, 28.0),
+            Kind::UrbanRailway => MotionProfile::new(1.2, 1.2, 20.0),
+            Kind::Monorail => MotionProfile::new(1.0, 1.0, 22.0),
+            Kind::Coach => MotionProfile::new(1.0, 1.5, 28.0),
+            Kind::Bus | Kind::Trolleybus => MotionProfile::new(1.2, 1.5, 17.0),
+            Kind::Tram => MotionProfile::new(1.2, 1.3, 19.0),
+            Kind::CableTram => MotionProfile::new(0.8, 0.8, 8.0),
+            Kind::WaterTransport => MotionProfile::new(0.2, 0.3, 10.0),
+            Kind::Air => MotionProfile::new(2.0, 2.0, 250.0),
+            Kind::AerialLift => MotionProfile::new(0.4, 0.4, 6.0),
+            Kind::Funicular => MotionProfile::new(0.6, 0.6, 10.0),
+            Kind::Taxi => MotionProfile::new(2.0, 2.5, 17.0),
+        }
+    }
+
+    /// Number of passengers fitting into a vehicle, including standing room.
+    pub fn capacity(self) -> u32 {
+        match self {
+            Kind::Railway => 800,
+            Kind::RegionalRailway => 600,
+            Kind::SuburbanRailway => 1000,
+            Kind::UrbanRailway => 750,
+            Kind::Monorail | Kind::WaterTransport => 300,
+            Kind::Coach => 50,
+            Kind::Bus | Kind::Trolleybus => 90,
+            Kind::Tram => 180,
+            Kind::CableTram => 60,
+            Kind::Air => 180,
+            Kind::AerialLift => 10,
+            Kind::Funicular => 100,
+            Kind::Taxi => 4,
         }
     }
 
     pub fn color(self) -> Color {
         match self {
             Kind::Railway => Color::new(227, 0, 27),
+            Kind::RegionalRailway => Color::new(187, 0, 22),
             Kind::SuburbanRailway => Color::new(0, 114, 56),
             Kind::UrbanRailway => Color::new(0, 100, 173),
+            Kind::Monorail => Color::new(0, 151, 167),
+            Kind::Coach => Color::new(90, 37, 122),
             Kind::Bus => Color::new(125, 23, 107),
+            Kind::Trolleybus => Color::new(160, 48, 140),
             Kind::Tram => Color::new(204, 10, 34),
+            Kind::CableTram => Color::new(170, 60, 30),
             Kind::WaterTransport => Color::new(0, 128, 186),
+            Kind::Air => Color::new(96, 125, 139),
+            Kind::AerialLift => Color::new(230, 120, 0),
+            Kind::Funicular => Color::new(121, 85, 72),
+            Kind::Taxi => Color::new(240, 190, 0),
         }
     }
 }
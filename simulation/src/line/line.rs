@@ -6,32 +6,63 @@ use super::Kind;
 use crate::color::Color;
 use crate::direction::Direction;
 use crate::node::Node;
-use crate::train::Train;
+use crate::scenario::{Disruption, Impact};
+use crate::station::Station;
+use crate::train::{Train, DAY};
 
 #[derive(Debug, PartialEq)]
 pub struct Line {
     name: String,
     color: Color,
+    text_color: Color,
     kind: Kind,
     nodes: Vec<Node>,
+    timetable: Vec<Train>,
     trains: Vec<Train>,
+    /// Spans in which the trains are active together with their index, sorted by their start.
+    departures: Vec<(i64, i64, usize)>,
+    departed: usize,
+    active: Vec<usize>,
+    time: u32,
+}
+
+/// Time of the train's service day corresponding to the time of the selected day.
+fn train_time(train: &Train, time: u32) -> u32 {
+    if train.is_from_previous_day() {
+        time + DAY
+    } else {
+        time
+    }
 }
 
 impl Line {
     pub fn new(
         name: String,
         color: Color,
+        text_color: Color,
         kind: Kind,
         nodes: Vec<Node>,
-        trains: Vec<Train>,
+        mut trains: Vec<Train>,
     ) -> Line {
-        Line {
+        for (trip, train) in trains.iter_mut().enumerate() {
+            train.set_trip(trip);
+            train.prepare(&nodes);
+        }
+        let mut line = Line {
             name,
             color,
+            text_color,
             kind,
             nodes,
-            trains,
-        }
+            timetable: trains,
+            trains: Vec::new(),
+            departures: Vec::new(),
+            departed: 0,
+            active: Vec::new(),
+            time: 0,
+        };
+        line.select_day(0);
+        line
     }
 
     pub fn name(&self) -> &str {
@@ -46,18 +77,140 @@ impl Line {
         &self.nodes
     }
 
+    /// Name of the station at the node, which is empty for waypoints.
+    pub(crate) fn station_name(&self, node: usize) -> &str {
+        self.nodes[node]
+            .station()
+            .map(Station::name)
+            .unwrap_or_default()
+    }
+
+    pub fn text_color(&self) -> &Color {
+        &self.text_color
+    }
+
     pub fn fill_color_buffer(&self, colors: &mut Vec<f32>) {
         colors.extend(self.color.iter().map(|component| component as f32 / 255.0));
     }
 
+    pub fn fill_text_color_buffer(&self, colors: &mut Vec<f32>) {
+        colors.extend(
+            self.text_color
+                .iter()
+                .map(|component| component as f32 / 255.0),
+        );
+    }
+
+    pub fn trains(&self) -> &[Train] {
+        &self.trains
+    }
+
     pub fn active_trains(&self) -> impl Iterator<Item = &Train> {
-        self.trains.iter().filter(|train| train.is_active())
+        self.active.iter().map(move |&index| &self.trains[index])
+    }
+
+    /// The trains of the selected day which are ever active, in the order they are dispatched,
+    /// with the span in which they are active in seconds since midnight of the selected day.
+    pub(crate) fn trains_by_dispatch(&self) -> impl Iterator<Item = (i64, i64, &Train)> {
+        self.departures
+            .iter()
+            .map(move |&(after, until, index)| (after, until, &self.trains[index]))
     }
 
+    /// Indices of the active trains into the trains of the selected day.
+    pub(crate) fn active_indices(&self) -> &[usize] {
+        &self.active
+    }
+
+    /// Replaces the trains by the ones of the service day, as they are at midnight.
+    ///
+    /// Trains of the previous service day which are still running past midnight are included.
+    pub fn select_day(&mut self, day: usize) {
+        let nodes = &self.nodes;
+        let previous = self
+            .timetable
+            .iter()
+            .filter(|train| train.runs_on_day_before(day) && train.end_time() > DAY)
+            .map(|train| {
+                let mut train = train.clone();
+                train.continue_at_midnight(nodes);
+                train
+            });
+        let current = self
+            .timetable
+            .iter()
+            .filter(|train| train.runs_on(day))
+            .cloned();
+        self.trains = previous.chain(current).collect();
+
+        self.departures = self
+            .trains
+            .iter()
+            .enumerate()
+            .filter_map(|(index, train)| {
+                let shift = i64::from(train_time(train, 0));
+                let (after, until) = train.active_span()?;
+                Some((i64::from(after) - shift, i64::from(until) - shift, index))
+            })
+            .sorted()
+            .collect();
+        self.active.clear();
+        self.set_time(0);
+    }
+
+    /// Changes the timetable of all days, which takes effect with the next selected day.
+    pub(crate) fn apply_disruption(&mut self, disruption: &Disruption) -> Impact {
+        disruption.apply(&mut self.timetable, &self.nodes)
+    }
+
+    /// Moves the trains to the time since midnight of the selected service day.
+    ///
+    /// Only the trains active before or after are touched.
+    pub fn set_time(&mut self, time: u32) {
+        let nodes = &self.nodes;
+        let trains = &mut self.trains;
+        for &index in &self.active {
+            let train_time = train_time(&trains[index], time);
+            trains[index].set_time(train_time, nodes);
+        }
+
+        let now = i64::from(time);
+        self.departed = self
+            .departures
+            .partition_point(|&(after, _, _)| after < now);
+        self.active.clear();
+        for &(_, until, index) in &self.departures[..self.departed] {
+            if now <= until {
+                let train_time = train_time(&trains[index], time);
+                trains[index].set_time(train_time, nodes);
+                self.active.push(index);
+            }
+        }
+        self.time = time;
+    }
+
+    /// Advances the active trains and activates the ones departing in the meantime.
     pub fn update(&mut self, time_passed: u32) {
-        for train in &mut self.trains {
-            train.update(time_passed, &self.nodes);
+        let nodes = &self.nodes;
+        let trains = &mut self.trains;
+        for &index in &self.active {
+            trains[index].update(time_passed, nodes);
+        }
+
+        self.time += time_passed;
+        let now = i64::from(self.time);
+        while let Some(&(after, until, index)) = self.departures.get(self.departed) {
+            if after >= now {
+                break;
+            }
+            self.departed += 1;
+            if now <= until {
+                let train_time = train_time(&trains[index], self.time);
+                trains[index].set_time(train_time, nodes);
+                self.active.push(index);
+            }
         }
+        self.active.retain(|&index| trains[index].is_active());
     }
 
     pub fn fill_vertices_buffer_with_lengths(
@@ -141,16 +294,17 @@ pub mod fixtures {
         ($($line:ident: $name:literal, $kind:ident, $upstream:ident, $upstream_times:tt, $downstream:ident, $downstream_times:tt);* $(;)?) => {
             $(
                 pub fn $line() -> Line {
-                    Line {
-                        name: $name.to_string(),
-                        color: Kind::$kind.color(),
-                        kind: Kind::$kind,
-                        nodes: nodes::$line(),
-                        trains: vec![
+                    Line::new(
+                        $name.to_string(),
+                        Kind::$kind.color(),
+                        Color::new(0, 0, 0),
+                        Kind::$kind,
+                        nodes::$line(),
+                        vec![
                             lines!(@trains $line, $upstream, $upstream_times),
                             lines!(@trains $line, $downstream, $downstream_times),
                         ],
-                    }
+                    )
                 }
             )*
         };
@@ -176,9 +330,9 @@ mod tests {
 
     use super::*;
     use crate::direction::Directions;
-    use crate::fixtures::{lines, nodes};
+    use crate::fixtures::{lines, nodes, trains};
     use crate::node::Kind as NodeKind;
-    use test_utils::time;
+    use test_utils::{time, times};
 
     #[test]
     fn test_getters() {
@@ -196,6 +350,14 @@ mod tests {
         assert_relative_eq!(*colors, [0.8, 0.04, 0.13], epsilon = 0.01);
     }
 
+    #[test]
+    fn test_fill_text_color_buffer() {
+        let line = lines::tram_12();
+        let mut colors = Vec::new();
+        line.fill_text_color_buffer(&mut colors);
+        assert_relative_eq!(*colors, [0.0, 0.0, 0.0]);
+    }
+
     #[test]
     fn test_active_trains() {
         let mut line = lines::tram_12();
@@ -210,6 +372,106 @@ mod tests {
         assert_eq!(line.active_trains().count(), 1);
     }
 
+    #[test]
+    fn test_update_touches_only_active_trains() {
+        let mut timetable = Vec::new();
+        for minute in (0..24 * 60).step_by(7) {
+            timetable.push(trains::tram_12::oranienburger_tor_am_kupfergraben(
+                minute * 60,
+            ));
+            timetable.push(trains::tram_12::am_kupfergraben_oranienburger_tor(
+                minute * 60 + 180,
+            ));
+        }
+        let mut line = Line::new(
+            "12".to_string(),
+            Kind::Tram.color(),
+            Color::new(0, 0, 0),
+            Kind::Tram,
+            nodes::tram_12(),
+            timetable.clone(),
+        );
+        let nodes = nodes::tram_12();
+        for (trip, train) in timetable.iter_mut().enumerate() {
+            train.set_trip(trip);
+            train.prepare(&nodes);
+        }
+        for _ in 0..2000 {
+            line.update(41);
+            for train in &mut timetable {
+                train.update(41, &nodes);
+            }
+            let expected = timetable
+                .iter()
+                .filter(|train| train.is_active())
+                .sorted_by_key(|train| train.end_time())
+                .collect::<Vec<_>>();
+            let actual = line
+                .active_trains()
+                .sorted_by_key(|train| train.end_time())
+                .collect::<Vec<_>>();
+            assert_eq!(actual, expected);
+        }
+    }
+
+    #[test]
+    fn test_select_day() {
+        let late = trains::tram_12::oranienburger_tor_am_kupfergraben(time!(23:58:00));
+        let mut line = Line::new(
+            "12".to_string(),
+            Kind::Tram.color(),
+            Color::new(0, 0, 0),
+            Kind::Tram,
+            nodes::tram_12(),
+            vec![late],
+        );
+        line.update(time!(23:59:00));
+        assert_eq!(line.active_trains().count(), 1);
+
+        line.select_day(1);
+        assert_eq!(line.active_trains().count(), 1);
+        assert!(line.trains()[0].is_from_previous_day());
+        line.update(time!(0:05:00));
+        assert_eq!(line.active_trains().count(), 0);
+
+        line.select_day(2);
+        assert!(line.trains().is_empty());
+    }
+
+    #[test]
+    fn test_set_time() {
+        let mut line = lines::tram_12();
+        line.set_time(time!(8:35:00));
+        assert_eq!(line.active_trains().count(), 1);
+        line.set_time(time!(8:00:00));
+        assert_eq!(line.active_trains().count(), 0);
+        line.set_time(time!(9:03:00));
+        assert_eq!(line.active_trains().count(), 1);
+    }
+
+    #[test]
+    fn test_select_first_day() {
+        let eve = Train::new(
+            Kind::Tram,
+            Direction::Upstream,
+            0b1,
+            times![23:58:00, 0:20, 0:27, 1:21, 0:27, 0:20, 0:19, 0:11, 0:12, 0:12, 0:12,
+                0:20, 1:00, 0:20],
+        );
+        let mut line = Line::new(
+            "12".to_string(),
+            Kind::Tram.color(),
+            Color::new(0, 0, 0),
+            Kind::Tram,
+            nodes::tram_12(),
+            vec![eve],
+        );
+        line.select_day(0);
+        assert_eq!(line.active_trains().count(), 1);
+        line.select_day(1);
+        assert!(line.trains().is_empty());
+    }
+
     macro_rules! test_vertices {
         ($nodes:tt, $upstream:tt) => (
             test_vertices!($nodes, $upstream, $upstream)
@@ -229,11 +491,17 @@ mod tests {
             let line = Line {
                 name: String::new(),
                 color: Kind::$kind.color(),
+                text_color: Color::new(0, 0, 0),
                 kind: Kind::$kind,
                 nodes: vec![ $(
                     Node::new(Point2::new($x, $y), NodeKind::Waypoint, Directions::$in_directions)
                 ),* ],
+                timetable: Vec::new(),
                 trains: Vec::new(),
+                departures: Vec::new(),
+                departed: 0,
+                active: Vec::new(),
+                time: 0,
             };
             let mut upstream_vertices = Vec::new();
             line.fill_vertices_buffer_for_direction(Direction::Upstream, &mut upstream_vertices);
@@ -0,0 +1,151 @@
+/// How a vehicle accelerates, brakes and how fast it can go at most.
+///
+/// Accelerations are given in m/s², speeds in m/s.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MotionProfile {
+    acceleration: f32,
+    deceleration: f32,
+    top_speed: f32,
+}
+
+impl MotionProfile {
+    pub fn new(acceleration: f32, deceleration: f32, top_speed: f32) -> Self {
+        Self {
+            acceleration,
+            deceleration,
+            top_speed,
+        }
+    }
+
+    pub fn acceleration(&self) -> f32 {
+        self.acceleration
+    }
+
+    pub fn deceleration(&self) -> f32 {
+        self.deceleration
+    }
+
+    pub fn top_speed(&self) -> f32 {
+        self.top_speed
+    }
+
+    /// The lowest speed at which a run of the distance can be cruised to arrive in time.
+    ///
+    /// This is `None` if the run is too short or too fast for the profile.
+    fn cruise_speed(&self, distance: f32, duration: f32) -> Option<f32> {
+        let braking = 0.5 / self.acceleration + 0.5 / self.deceleration;
+        let discriminant = duration * duration - 4.0 * braking * distance;
+        if distance <= 0.0 || duration <= 0.0 || discriminant < 0.0 {
+            return None;
+        }
+        let speed = (duration - discriminant.sqrt()) / (2.0 * braking);
+        if speed <= self.top_speed {
+            Some(speed)
+        } else {
+            None
+        }
+    }
+
+    /// Distance covered after the elapsed time on a run from stop to stop.
+    ///
+    /// The vehicle accelerates, cruises and brakes so that it arrives exactly after the duration.
+    /// Runs which do not fit the profile are driven at a constant speed instead.
+    pub fn travelled(&self, distance: f32, duration: f32, elapsed: f32) -> f32 {
+        let elapsed = elapsed.max(0.0).min(duration);
+        match self.cruise_speed(distance, duration) {
+            Some(speed) => {
+                let accelerating = speed / self.acceleration;
+                let braking = speed / self.deceleration;
+                if elapsed < accelerating {
+                    0.5 * self.acceleration * elapsed * elapsed
+                } else if elapsed < duration - braking {
+                    0.5 * speed * accelerating + speed * (elapsed - accelerating)
+                } else {
+                    let remaining = duration - elapsed;
+                    distance - 0.5 * self.deceleration * remaining * remaining
+                }
+            }
+            None if duration > 0.0 => distance * elapsed / duration,
+            None => distance,
+        }
+    }
+
+    /// Time after which the covered distance is reached on a run from stop to stop.
+    ///
+    /// This is the inverse of [`travelled`](Self::travelled).
+    pub fn time_to(&self, distance: f32, duration: f32, covered: f32) -> f32 {
+        let covered = covered.max(0.0).min(distance);
+        match self.cruise_speed(distance, duration) {
+            Some(speed) => {
+                let accelerating = 0.5 * speed * speed / self.acceleration;
+                let braking = 0.5 * speed * speed / self.deceleration;
+                if covered < accelerating {
+                    (2.0 * covered / self.acceleration).sqrt()
+                } else if covered < distance - braking {
+                    speed / self.acceleration + (covered - accelerating) / speed
+                } else {
+                    duration - (2.0 * (distance - covered) / self.deceleration).sqrt()
+                }
+            }
+            None if distance > 0.0 => duration * covered / distance,
+            None => duration,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    fn profile() -> MotionProfile {
+        MotionProfile::new(1.0, 2.0, 20.0)
+    }
+
+    #[test]
+    fn test_travelled() {
+        // cruising at 10 m/s: 10 s accelerating, 5 s braking
+        let profile = profile();
+        assert_relative_eq!(profile.travelled(725.0, 80.0, 0.0), 0.0);
+        assert_relative_eq!(profile.travelled(725.0, 80.0, 4.0), 8.0);
+        assert_relative_eq!(profile.travelled(725.0, 80.0, 10.0), 50.0);
+        assert_relative_eq!(profile.travelled(725.0, 80.0, 40.0), 350.0);
+        assert_relative_eq!(profile.travelled(725.0, 80.0, 77.0), 716.0);
+        assert_relative_eq!(profile.travelled(725.0, 80.0, 80.0), 725.0);
+    }
+
+    #[test]
+    fn test_time_to() {
+        let profile = profile();
+        for &elapsed in &[0.0, 4.0, 10.0, 40.0, 77.0, 80.0] {
+            let covered = profile.travelled(725.0, 80.0, elapsed);
+            assert_relative_eq!(
+                profile.time_to(725.0, 80.0, covered),
+                elapsed,
+                epsilon = 1e-3
+            );
+        }
+    }
+
+    #[test]
+    fn test_constant_speed_when_too_fast() {
+        let profile = profile();
+        assert_relative_eq!(profile.travelled(1000.0, 40.0, 10.0), 250.0);
+        assert_relative_eq!(profile.time_to(1000.0, 40.0, 250.0), 10.0);
+    }
+
+    #[test]
+    fn test_constant_speed_above_top_speed() {
+        let profile = MotionProfile::new(1.0, 2.0, 5.0);
+        assert_relative_eq!(profile.travelled(725.0, 80.0, 40.0), 362.5);
+    }
+
+    #[test]
+    fn test_empty_run() {
+        let profile = profile();
+        assert_relative_eq!(profile.travelled(0.0, 30.0, 10.0), 0.0);
+        assert_relative_eq!(profile.time_to(0.0, 30.0, 0.0), 30.0);
+        assert_relative_eq!(profile.travelled(100.0, 0.0, 0.0), 100.0);
+    }
+}
@@ -27,20 +27,39 @@ impl Node {
 
     pub fn is_stop(&self) -> bool {
         match self.kind {
-            Kind::Waypoint => false,
+            Kind::Waypoint | Kind::PassThrough => false,
             Kind::Stop { .. } => true,
         }
     }
 
+    /// Whether the schedules give the time at which trains are at the node, as for stops.
+    pub fn is_timed(&self) -> bool {
+        match self.kind {
+            Kind::Waypoint => false,
+            Kind::Stop { .. } | Kind::PassThrough => true,
+        }
+    }
+
     pub fn allows(&self, direction: Direction) -> bool {
         self.in_directions.allows(direction)
     }
+
+    pub fn station(&self) -> Option<&Station> {
+        match &self.kind {
+            Kind::Waypoint | Kind::PassThrough => None,
+            Kind::Stop { at } => Some(at),
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Kind {
     Waypoint,
-    Stop { at: Rc<Station> },
+    Stop {
+        at: Rc<Station>,
+    },
+    /// A stop the trains pass through at a scheduled time without stopping.
+    PassThrough,
 }
 
 #[cfg(any(test, feature = "fixtures"))]
@@ -49,7 +68,7 @@ pub mod fixtures {
     use crate::fixtures::stations;
 
     macro_rules! nodes {
-        (kind $station:ident) => ( Kind::Stop { at: Rc::new(stations::$station()) } );
+        (kind $station:ident) => ( Kind::Stop { at: stations::shared(stations::$station) } );
         (kind) => ( Kind::Waypoint );
         ($($line:ident: $($x:literal, $y:literal, $in_directions:ident $(, $location:ident)?);* $(;)?)*) => (
             $(
@@ -132,5 +151,20 @@ mod tests {
         assert!(node.is_stop());
         assert!(node.allows(Direction::Upstream));
         assert!(node.allows(Direction::Downstream));
+        assert_eq!(node.station().map(Station::name), Some("Friedrichstr."));
+        assert_eq!(nodes::tram_12()[1].station(), None);
+        assert!(!nodes::tram_12()[1].is_timed());
+    }
+
+    #[test]
+    fn test_pass_through() {
+        let node = Node::new(
+            Point2::new(-111.0, -1115.0),
+            Kind::PassThrough,
+            Directions::Both,
+        );
+        assert!(!node.is_stop());
+        assert!(node.is_timed());
+        assert_eq!(node.station(), None);
     }
 }
@@ -0,0 +1,409 @@
+use std::collections::BTreeSet;
+use std::rc::Rc;
+
+use itertools::Itertools;
+use serde_derive::Deserialize;
+
+use crate::direction::Direction;
+use crate::line::Line;
+use crate::node::Node;
+use crate::station::{Kind as StationKind, Station};
+
+/// Passengers per hour travelling between stations, referenced by name.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Demand {
+    flows: Vec<Flow>,
+}
+
+impl Demand {
+    pub fn new(flows: Vec<Flow>) -> Self {
+        Self { flows }
+    }
+
+    pub fn flows(&self) -> &[Flow] {
+        &self.flows
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Flow {
+    from: String,
+    to: String,
+    per_hour: f32,
+}
+
+impl Flow {
+    pub fn new(from: String, to: String, per_hour: f32) -> Self {
+        Self { from, to, per_hour }
+    }
+
+    pub fn from(&self) -> &str {
+        &self.from
+    }
+
+    pub fn to(&self) -> &str {
+        &self.to
+    }
+
+    pub fn per_hour(&self) -> f32 {
+        self.per_hour
+    }
+}
+
+fn attraction(kind: StationKind) -> f32 {
+    match kind {
+        StationKind::Interchange => 4.0,
+        StationKind::TramStop => 2.0,
+        StationKind::BusStop | StationKind::FerryPier => 1.0,
+    }
+}
+
+/// Synthetic flows between all stations connected directly by a line.
+///
+/// The flows are proportional to the attraction of both stations and fall with the square of
+/// their distance, which is taken as at least a kilometre.
+pub(crate) fn gravity_flows(
+    stations: &[Rc<Station>],
+    station_indices: &[Vec<Option<usize>>],
+    per_hour: f32,
+) -> Vec<(usize, usize, f32)> {
+    let mut pairs = BTreeSet::new();
+    for indices in station_indices {
+        let served = indices.iter().flatten().unique().collect::<Vec<_>>();
+        for (&origin, &destination) in served.iter().cartesian_product(&served) {
+            if origin != destination {
+                pairs.insert((*origin, *destination));
+            }
+        }
+    }
+
+    let weights = pairs
+        .iter()
+        .map(|&(origin, destination)| {
+            let (origin, destination) = (&stations[origin], &stations[destination]);
+            let distance = na::distance(&origin.position(), &destination.position()).max(1000.0);
+            attraction(origin.kind()) * attraction(destination.kind()) / (distance * distance)
+        })
+        .collect::<Vec<_>>();
+    let total = weights.iter().sum::<f32>();
+    pairs
+        .into_iter()
+        .zip(weights)
+        .map(|((origin, destination), weight)| (origin, destination, per_hour * weight / total))
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Group {
+    origin: usize,
+    destination: usize,
+    count: u32,
+}
+
+fn add(groups: &mut Vec<Group>, origin: usize, destination: usize, count: u32) {
+    match groups
+        .iter_mut()
+        .find(|group| group.origin == origin && group.destination == destination)
+    {
+        Some(group) => group.count += count,
+        None => groups.push(Group {
+            origin,
+            destination,
+            count,
+        }),
+    }
+}
+
+fn total(groups: &[Group]) -> u32 {
+    groups.iter().map(|group| group.count).sum()
+}
+
+/// Stations served after the node, in the direction of travel.
+fn reachable(
+    stations: &[Option<usize>],
+    nodes: &[Node],
+    direction: Direction,
+    at: usize,
+) -> Vec<usize> {
+    let mut reachable = Vec::new();
+    let mut next = direction.find_next(at, nodes);
+    while let Some(node) = next {
+        if nodes[node].is_stop() {
+            reachable.extend(stations[node]);
+        }
+        next = direction.find_next(node, nodes);
+    }
+    reachable
+}
+
+/// Passengers waiting at the stations and travelling in the trains.
+///
+/// Passengers arrive at their origin following the flows and board the next train stopping at
+/// their destination, as long as its vehicles have room for them. Changing trains is not
+/// modelled.
+#[derive(Debug, PartialEq)]
+pub struct Passengers {
+    flows: Vec<(usize, usize, f32)>,
+    pending: Vec<f32>,
+    stations: Vec<Vec<Option<usize>>>,
+    waiting: Vec<Vec<Group>>,
+    onboard: Vec<Vec<Vec<Group>>>,
+}
+
+impl Passengers {
+    /// Passengers following the flows given by the origin, the destination and passengers per
+    /// hour.
+    pub(crate) fn new(
+        flows: Vec<(usize, usize, f32)>,
+        stations: Vec<Vec<Option<usize>>>,
+        station_count: usize,
+        lines: &[Line],
+    ) -> Self {
+        let mut passengers = Self {
+            pending: vec![0.0; flows.len()],
+            flows,
+            stations,
+            waiting: vec![Vec::new(); station_count],
+            onboard: Vec::new(),
+        };
+        passengers.empty_vehicles(lines);
+        passengers
+    }
+
+    /// Returns the passengers on board to their origin, which is necessary after the trains have
+    /// been replaced or moved.
+    pub(crate) fn empty_vehicles(&mut self, lines: &[Line]) {
+        for group in self.onboard.drain(..).flatten().flatten() {
+            add(
+                &mut self.waiting[group.origin],
+                group.origin,
+                group.destination,
+                group.count,
+            );
+        }
+        self.onboard = lines
+            .iter()
+            .map(|line| vec![Vec::new(); line.trains().len()])
+            .collect();
+    }
+
+    fn arrive(&mut self, time_passed: u32) {
+        for (&(origin, destination, per_hour), pending) in self.flows.iter().zip(&mut self.pending)
+        {
+            *pending += per_hour * time_passed as f32 / 3600.0;
+            let arriving = pending.floor();
+            *pending -= arriving;
+            if arriving > 0.0 {
+                add(
+                    &mut self.waiting[origin],
+                    origin,
+                    destination,
+                    arriving as u32,
+                );
+            }
+        }
+    }
+
+    /// Lets passengers arrive and board or alight the trains at every stop they reached since
+    /// the progress was taken with [`progress`](Self::progress).
+    ///
+    /// Trains dispatched in between are handled from their first stop on, so no stop is missed
+    /// even if the time passed covers several of them.
+    pub(crate) fn update(
+        &mut self,
+        time_passed: u32,
+        lines: &[Line],
+        progress: &[Vec<(usize, usize)>],
+    ) {
+        self.arrive(time_passed);
+
+        for (line_index, line) in lines.iter().enumerate() {
+            let mut trains = progress[line_index].clone();
+            for &train_index in line.active_indices() {
+                if trains.iter().all(|&(other, _)| other != train_index) {
+                    trains.push((train_index, 0));
+                }
+            }
+
+            for (train_index, since) in trains {
+                let train = &line.trains()[train_index];
+                let until = train.duration_index();
+                for (_, at) in train
+                    .served_stops()
+                    .into_iter()
+                    .filter(|&(index, _)| since <= index && index <= until)
+                {
+                    self.stop(line_index, line, train_index, at);
+                }
+            }
+        }
+    }
+
+    /// The duration index of each active train of the lines, before they move on.
+    pub(crate) fn progress(lines: &[Line]) -> Vec<Vec<(usize, usize)>> {
+        lines
+            .iter()
+            .map(|line| {
+                line.active_indices()
+                    .iter()
+                    .map(|&train| (train, line.trains()[train].duration_index()))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Lets passengers alight and board the train at the stop node.
+    fn stop(&mut self, line_index: usize, line: &Line, train_index: usize, at: usize) {
+        let stations = &self.stations[line_index];
+        let station = match stations[at] {
+            Some(station) => station,
+            None => return,
+        };
+
+        let onboard = &mut self.onboard[line_index][train_index];
+        onboard.retain(|group| group.destination != station);
+
+        let direction = line.trains()[train_index].direction();
+        let reachable = reachable(stations, line.nodes(), direction, at);
+        let mut free = line.kind().capacity().saturating_sub(total(onboard));
+        for group in &mut self.waiting[station] {
+            if reachable.contains(&group.destination) {
+                let boarding = group.count.min(free);
+                group.count -= boarding;
+                free -= boarding;
+                add(onboard, group.origin, group.destination, boarding);
+            }
+        }
+        self.waiting[station].retain(|group| group.count > 0);
+    }
+
+    /// Number of passengers waiting at the station.
+    pub fn waiting(&self, station: usize) -> u32 {
+        total(&self.waiting[station])
+    }
+
+    /// Number of passengers in the train with the index on the line.
+    pub fn load(&self, line: usize, train: usize) -> u32 {
+        total(&self.onboard[line][train])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Color;
+    use crate::fixtures::{lines, nodes, stations};
+    use crate::line::Kind;
+    use crate::station::station_indices;
+    use crate::train::Train;
+    use test_utils::{time, times};
+
+    fn stations() -> Vec<Rc<Station>> {
+        vec![
+            stations::shared(stations::oranienburger_tor),
+            stations::shared(stations::friedrichstr),
+            stations::shared(stations::universitaetsstr),
+            stations::shared(stations::am_kupfergraben),
+            stations::shared(stations::georgenstr_am_kupfergraben),
+        ]
+    }
+
+    #[test]
+    fn test_gravity_flows() {
+        let lines = vec![lines::tram_12()];
+        let stations = stations();
+        let flows = gravity_flows(&stations, &station_indices(&stations, &lines), 600.0);
+        assert_eq!(flows.len(), 20);
+        let total = flows.iter().map(|&(_, _, per_hour)| per_hour).sum::<f32>();
+        assert!((total - 600.0).abs() < 0.01);
+
+        let per_hour = |origin, destination| {
+            flows
+                .iter()
+                .find(|flow| flow.0 == origin && flow.1 == destination)
+                .unwrap()
+                .2
+        };
+        // interchanges attract more passengers than tram stops
+        assert!(per_hour(0, 1) > per_hour(0, 3));
+        assert!(per_hour(2, 3) < per_hour(0, 1));
+    }
+
+    fn passengers(flows: Vec<(usize, usize, f32)>, lines: &[Line]) -> Passengers {
+        Passengers::new(flows, station_indices(&stations(), lines), 5, lines)
+    }
+
+    fn update(passengers: &mut Passengers, lines: &mut [Line], time_passed: u32) {
+        let progress = Passengers::progress(lines);
+        for line in lines.iter_mut() {
+            line.update(time_passed);
+        }
+        passengers.update(time_passed, lines, &progress);
+    }
+
+    #[test]
+    fn test_boarding_and_alighting() {
+        let mut lines = vec![lines::tram_12()];
+        let mut passengers = passengers(vec![(0, 3, 6.0), (0, 4, 6.0), (3, 0, 6.0)], &lines);
+
+        update(&mut passengers, &mut lines, time!(9:01:45));
+        // the upstream train stands at Oranienburger Tor, but does not serve Georgenstr.
+        assert_eq!(passengers.load(0, 0), 54);
+        assert_eq!(passengers.waiting(0), 54);
+        assert_eq!(passengers.waiting(3), 54);
+
+        update(&mut passengers, &mut lines, time!(0:05:25));
+        // at the terminus Am Kupfergraben
+        assert_eq!(passengers.load(0, 0), 0);
+        assert_eq!(passengers.waiting(3), 54);
+    }
+
+    #[test]
+    fn test_stops_passed_within_update() {
+        // the train does not dwell at Friedrichstr.
+        let train = Train::new(
+            Kind::Tram,
+            Direction::Upstream,
+            0b10,
+            times!(time!(9:01:40), [
+                0:20, 0:28, 1:19, 0:28, 0:00, 0:20, 0:11, 0:10, 0:11, 0:13, 0:20, 1:00, 0:20
+            ]),
+        );
+        let mut lines = vec![Line::new(
+            "12".to_string(),
+            Kind::Tram.color(),
+            Color::new(0, 0, 0),
+            Kind::Tram,
+            nodes::tram_12(),
+            vec![train],
+        )];
+        let mut passengers = passengers(vec![(0, 3, 6.0), (1, 3, 6.0)], &lines);
+
+        update(&mut passengers, &mut lines, time!(9:04:30));
+        assert_eq!(passengers.load(0, 0), 108);
+        assert_eq!(passengers.waiting(0), 0);
+        assert_eq!(passengers.waiting(1), 0);
+    }
+
+    #[test]
+    fn test_capacity() {
+        let mut lines = vec![lines::tram_12()];
+        let mut passengers = passengers(vec![(0, 3, 3600.0)], &lines);
+        update(&mut passengers, &mut lines, time!(9:01:45));
+        assert_eq!(passengers.load(0, 0), Kind::Tram.capacity());
+        assert_eq!(
+            passengers.waiting(0),
+            time!(9:01:45) - Kind::Tram.capacity()
+        );
+    }
+
+    #[test]
+    fn test_empty_vehicles() {
+        let mut lines = vec![lines::tram_12()];
+        let mut passengers = passengers(vec![(0, 3, 6.0)], &lines);
+        update(&mut passengers, &mut lines, time!(9:01:45));
+        passengers.empty_vehicles(&lines);
+        assert_eq!(passengers.load(0, 0), 0);
+        assert_eq!(passengers.waiting(0), 54);
+    }
+}
@@ -0,0 +1,494 @@
+use std::fmt;
+use std::ops::AddAssign;
+
+use serde::de::{Deserialize, Deserializer, Error};
+use serde_derive::Deserialize;
+
+use crate::node::Node;
+use crate::station::Station;
+use crate::time::{parse_time, TimeDisplay};
+use crate::train::Train;
+
+/// A set of disruptions applied to the timetables for planning exercises.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Scenario {
+    #[serde(default)]
+    name: String,
+    disruptions: Vec<Disruption>,
+}
+
+impl Scenario {
+    pub fn new(name: String, disruptions: Vec<Disruption>) -> Self {
+        Self { name, disruptions }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn disruptions(&self) -> &[Disruption] {
+        &self.disruptions
+    }
+}
+
+/// A change to the trips of all lines with the name.
+///
+/// Only trips running during the period are affected, which is given in seconds since midnight
+/// of their service day and open-ended if a bound is missing.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Disruption {
+    line: String,
+    #[serde(default, deserialize_with = "deserialize_time")]
+    start: Option<u32>,
+    #[serde(default, deserialize_with = "deserialize_time")]
+    end: Option<u32>,
+    #[serde(flatten)]
+    kind: DisruptionKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DisruptionKind {
+    /// Trips leave the station, or their first stop, later by the seconds.
+    Delay { at: Option<String>, delay: u32 },
+    /// Trips starting during the period do not run at all.
+    Cancellation,
+    /// Trips skip the section between the two stations, continuing behind it as scheduled.
+    Closure { between: String, and: String },
+    /// Trips turn at the station instead of serving the section towards the other one.
+    ShortTurn { at: String, towards: String },
+}
+
+fn deserialize_time<'de, D>(deserializer: D) -> Result<Option<u32>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Option::<String>::deserialize(deserializer)?
+        .map(|value| {
+            parse_time(&value).ok_or_else(|| {
+                D::Error::custom(format!("invalid time {}, expected hh:mm[:ss]", value))
+            })
+        })
+        .transpose()
+}
+
+impl Disruption {
+    pub fn new(line: String, start: Option<u32>, end: Option<u32>, kind: DisruptionKind) -> Self {
+        Self {
+            line,
+            start,
+            end,
+            kind,
+        }
+    }
+
+    pub fn line(&self) -> &str {
+        &self.line
+    }
+
+    pub fn kind(&self) -> &DisruptionKind {
+        &self.kind
+    }
+
+    /// Whether a trip running after the first and before the second time is affected.
+    fn overlaps(&self, after: u32, before: u32) -> bool {
+        self.start.iter().all(|&start| before > start) && self.end.iter().all(|&end| after < end)
+    }
+
+    /// Whether a trip at the time is affected.
+    fn passes(&self, time: u32) -> bool {
+        self.overlaps(time, time + 1)
+    }
+
+    /// Changes the trips of a line, splitting or removing trains where necessary.
+    pub(crate) fn apply(&self, trains: &mut Vec<Train>, nodes: &[Node]) -> Impact {
+        let position = |stops: &[(usize, usize)], name: &str| {
+            stops
+                .iter()
+                .position(|&(_, node)| nodes[node].station().map(Station::name) == Some(name))
+        };
+
+        let mut impact = Impact::default();
+        let mut changed = Vec::with_capacity(trains.len());
+        for mut train in trains.drain(..) {
+            let stops = train.stops();
+            if stops.is_empty() {
+                changed.push(train);
+                continue;
+            }
+            let last = stops.len() - 1;
+            let (first_arrival, last_arrival) =
+                (train.arrival(stops[0].0), train.arrival(stops[last].0));
+
+            let kept = match &self.kind {
+                DisruptionKind::Delay { at, delay } => {
+                    let at = match at {
+                        Some(name) => position(&stops, name),
+                        None => Some(0),
+                    };
+                    match at {
+                        Some(at) if self.passes(train.arrival(stops[at].0)) => {
+                            let index = if at == 0 { 0 } else { stops[at].0 };
+                            train.delay_at(index, *delay, nodes);
+                            impact.delayed_trips += 1;
+                            impact.delayed_stops += stops.len() - at;
+                        }
+                        _ => {}
+                    }
+                    (Some(train), None)
+                }
+                DisruptionKind::Cancellation => {
+                    if self.passes(first_arrival) {
+                        impact.cancelled_stops += stops.len();
+                        (None, None)
+                    } else {
+                        (Some(train), None)
+                    }
+                }
+                DisruptionKind::Closure { between, and } => {
+                    match (position(&stops, between), position(&stops, and)) {
+                        (Some(a), Some(b)) if a != b => {
+                            let (before, after) = (a.min(b), a.max(b));
+                            let section = (
+                                train.departure(stops[before].0),
+                                train.arrival(stops[after].0),
+                            );
+                            if self.overlaps(section.0, section.1) {
+                                impact.cancelled_stops += after - before - 1;
+                                self.split(&train, &stops, before, after, nodes, &mut impact)
+                            } else {
+                                (Some(train), None)
+                            }
+                        }
+                        _ => (Some(train), None),
+                    }
+                }
+                DisruptionKind::ShortTurn { at, towards } => {
+                    match (position(&stops, at), position(&stops, towards)) {
+                        (Some(at), Some(towards)) if at < towards => {
+                            let section = (train.departure(stops[at].0), last_arrival);
+                            if self.overlaps(section.0, section.1) {
+                                impact.cancelled_stops += last - at;
+                                self.split(&train, &stops, at, stops.len(), nodes, &mut impact)
+                            } else {
+                                (Some(train), None)
+                            }
+                        }
+                        (Some(at), Some(towards)) if towards < at => {
+                            let section = (train.departure(stops[0].0), train.arrival(stops[at].0));
+                            if self.overlaps(section.0, section.1) {
+                                impact.cancelled_stops += at;
+                                self.split(&train, &stops, usize::MAX, at, nodes, &mut impact)
+                            } else {
+                                (Some(train), None)
+                            }
+                        }
+                        _ => (Some(train), None),
+                    }
+                }
+            };
+
+            match kept {
+                (None, None) => impact.cancelled_trips += 1,
+                (first, second) => changed.extend(first.into_iter().chain(second)),
+            }
+        }
+        *trains = changed;
+        impact
+    }
+
+    /// Keeps the parts of the trip until the stop before and from the stop after.
+    ///
+    /// The stop indices may lie outside of the trip to keep only one of the parts. Parts serving
+    /// just a single stop are dropped.
+    fn split(
+        &self,
+        train: &Train,
+        stops: &[(usize, usize)],
+        before: usize,
+        after: usize,
+        nodes: &[Node],
+        impact: &mut Impact,
+    ) -> (Option<Train>, Option<Train>) {
+        let mut parts = (None, None);
+        if before < stops.len() {
+            if before > 0 {
+                parts.0 = Some(train.part_until_stop(stops[before].0, nodes));
+            } else {
+                impact.cancelled_stops += 1;
+            }
+        }
+        if after < stops.len() {
+            if after + 1 < stops.len() {
+                parts.1 = Some(train.part_from_stop(stops[after].0, nodes));
+            } else {
+                impact.cancelled_stops += 1;
+            }
+        }
+        if parts.0.is_some() || parts.1.is_some() {
+            impact.shortened_trips += 1;
+        }
+        parts
+    }
+}
+
+impl fmt::Display for Disruption {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match &self.kind {
+            DisruptionKind::Delay {
+                at: Some(at),
+                delay,
+            } => write!(formatter, "delay of {} by {}s at {}", self.line, delay, at)?,
+            DisruptionKind::Delay { at: None, delay } => {
+                write!(formatter, "delay of {} by {}s", self.line, delay)?
+            }
+            DisruptionKind::Cancellation => write!(formatter, "cancellation of {}", self.line)?,
+            DisruptionKind::Closure { between, and } => write!(
+                formatter,
+                "closure of {} between {} and {}",
+                self.line, between, and
+            )?,
+            DisruptionKind::ShortTurn { at, towards } => write!(
+                formatter,
+                "short turn of {} at {} towards {}",
+                self.line, at, towards
+            )?,
+        }
+        if let Some(start) = self.start {
+            write!(formatter, " from {}", TimeDisplay(start))?;
+        }
+        if let Some(end) = self.end {
+            write!(formatter, " until {}", TimeDisplay(end))?;
+        }
+        Ok(())
+    }
+}
+
+/// Number of trips and stops changed by a disruption.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Impact {
+    delayed_trips: usize,
+    shortened_trips: usize,
+    cancelled_trips: usize,
+    delayed_stops: usize,
+    cancelled_stops: usize,
+}
+
+impl Impact {
+    pub fn delayed_trips(&self) -> usize {
+        self.delayed_trips
+    }
+
+    pub fn shortened_trips(&self) -> usize {
+        self.shortened_trips
+    }
+
+    pub fn cancelled_trips(&self) -> usize {
+        self.cancelled_trips
+    }
+
+    pub fn affected_trips(&self) -> usize {
+        self.delayed_trips + self.shortened_trips + self.cancelled_trips
+    }
+
+    pub fn delayed_stops(&self) -> usize {
+        self.delayed_stops
+    }
+
+    pub fn cancelled_stops(&self) -> usize {
+        self.cancelled_stops
+    }
+
+    pub fn affected_stops(&self) -> usize {
+        self.delayed_stops + self.cancelled_stops
+    }
+}
+
+impl AddAssign for Impact {
+    fn add_assign(&mut self, other: Self) {
+        self.delayed_trips += other.delayed_trips;
+        self.shortened_trips += other.shortened_trips;
+        self.cancelled_trips += other.cancelled_trips;
+        self.delayed_stops += other.delayed_stops;
+        self.cancelled_stops += other.cancelled_stops;
+    }
+}
+
+impl fmt::Display for Impact {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "{} trips affected ({} delayed, {} shortened, {} cancelled), \
+             {} stops affected ({} delayed, {} cancelled)",
+            self.affected_trips(),
+            self.delayed_trips,
+            self.shortened_trips,
+            self.cancelled_trips,
+            self.affected_stops(),
+            self.delayed_stops,
+            self.cancelled_stops,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{nodes, trains};
+    use test_utils::time;
+
+    fn trains() -> Vec<Train> {
+        let mut trains = vec![
+            trains::tram_12::oranienburger_tor_am_kupfergraben(time!(9:02:00)),
+            trains::tram_12::am_kupfergraben_oranienburger_tor(time!(8:33:40)),
+        ];
+        for train in &mut trains {
+            train.prepare(&nodes::tram_12());
+        }
+        trains
+    }
+
+    fn apply(json: &str, trains: &mut Vec<Train>) -> Impact {
+        let disruption: Disruption = serde_json::from_str(json).unwrap();
+        disruption.apply(trains, &nodes::tram_12())
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let scenario: Scenario = serde_json::from_str(
+            r#"{
+                "name": "U6 closure",
+                "disruptions": [{
+                    "line": "U6",
+                    "kind": "closure",
+                    "between": "Friedrichstr.",
+                    "and": "Französische Str.",
+                    "start": "10:00",
+                    "end": "12:00"
+                }]
+            }"#,
+        )
+        .unwrap();
+        let closure = DisruptionKind::Closure {
+            between: "Friedrichstr.".to_string(),
+            and: "Französische Str.".to_string(),
+        };
+        let disruption = Disruption::new(
+            "U6".to_string(),
+            Some(time!(10:00:00)),
+            Some(time!(12:00:00)),
+            closure,
+        );
+        assert_eq!(
+            scenario,
+            Scenario::new("U6 closure".to_string(), vec![disruption])
+        );
+    }
+
+    #[test]
+    fn test_deserialize_invalid_time() {
+        let error = serde_json::from_str::<Disruption>(
+            r#"{"line": "M10", "kind": "cancellation", "start": "10h"}"#,
+        )
+        .unwrap_err();
+        assert!(error.to_string().starts_with("invalid time 10h"));
+    }
+
+    #[test]
+    fn test_display() {
+        let disruption: Disruption = serde_json::from_str(
+            r#"{"line": "M10", "kind": "delay", "delay": 300, "start": "7:00", "end": "9:30:15"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            disruption.to_string(),
+            "delay of M10 by 300s from 7:00 until 9:30:15"
+        );
+    }
+
+    #[test]
+    fn test_delay() {
+        let mut trains = trains();
+        let impact = apply(
+            r#"{"line": "12", "kind": "delay", "delay": 300}"#,
+            &mut trains,
+        );
+        assert_eq!(impact.delayed_trips(), 2);
+        assert_eq!(impact.delayed_stops(), 8);
+        assert_eq!(trains[0].start_time(), time!(9:07:00));
+        assert_eq!(trains[0].end_time(), time!(9:12:40));
+    }
+
+    #[test]
+    fn test_delay_propagates_from_station() {
+        let mut trains = trains();
+        let impact = apply(
+            r#"{"line": "12", "kind": "delay", "at": "Friedrichstr.", "delay": 300, "start": "9:00"}"#,
+            &mut trains,
+        );
+        assert_eq!(impact.delayed_trips(), 1);
+        assert_eq!(impact.delayed_stops(), 3);
+        let stops = trains[0].stops();
+        assert_eq!(trains[0].arrival(stops[1].0), time!(9:04:35));
+        assert_eq!(trains[0].departure(stops[1].0), time!(9:09:55));
+        assert_eq!(trains[0].end_time(), time!(9:12:40));
+        assert_eq!(trains[1], self::trains()[1]);
+    }
+
+    #[test]
+    fn test_cancellation() {
+        let mut trains = trains();
+        let impact = apply(
+            r#"{"line": "12", "kind": "cancellation", "start": "9:00", "end": "10:00"}"#,
+            &mut trains,
+        );
+        assert_eq!(impact.cancelled_trips(), 1);
+        assert_eq!(impact.cancelled_stops(), 4);
+        assert_eq!(trains, &self::trains()[1..]);
+    }
+
+    #[test]
+    fn test_closure() {
+        let mut trains = trains();
+        let impact = apply(
+            r#"{"line": "12", "kind": "closure", "between": "Friedrichstr.", "and": "Universitätsstr."}"#,
+            &mut trains,
+        );
+        assert_eq!(impact.shortened_trips(), 1);
+        assert_eq!(impact.affected_stops(), 0);
+        assert_eq!(trains.len(), 3);
+        assert_eq!(trains[0].start_time(), time!(9:02:00));
+        assert_eq!(trains[0].end_time(), time!(9:04:55));
+        assert_eq!(trains[1].start_time(), time!(9:06:00));
+        assert_eq!(trains[1].end_time(), time!(9:07:40));
+        assert_eq!(trains[1].stops().len(), 4);
+        assert_eq!(trains[2], self::trains()[1]);
+    }
+
+    #[test]
+    fn test_closure_outside_of_period() {
+        let mut trains = trains();
+        let impact = apply(
+            r#"{"line": "12", "kind": "closure", "between": "Friedrichstr.", "and": "Universitätsstr.", "start": "10:00", "end": "12:00"}"#,
+            &mut trains,
+        );
+        assert_eq!(impact, Impact::default());
+        assert_eq!(trains, self::trains());
+    }
+
+    #[test]
+    fn test_short_turn() {
+        let mut trains = trains();
+        let impact = apply(
+            r#"{"line": "12", "kind": "short_turn", "at": "Friedrichstr.", "towards": "Am Kupfergraben"}"#,
+            &mut trains,
+        );
+        assert_eq!(impact.shortened_trips(), 2);
+        assert_eq!(impact.cancelled_stops(), 4);
+        assert_eq!(trains[0].end_time(), time!(9:04:55));
+        let original = &self::trains()[1];
+        let friedrichstr = original.stops()[2].0;
+        assert_eq!(trains[1].start_time(), original.arrival(friedrichstr));
+        assert_eq!(trains[1].end_time(), original.end_time());
+    }
+}
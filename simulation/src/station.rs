@@ -1,27 +1,45 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+
 use na::Point2;
 
-use crate::line;
+use crate::complex::Complex;
+use crate::line::{self, Line};
 
 #[derive(Debug, PartialEq)]
 pub struct Station {
     position: Point2<f32>,
     name: String,
     kind: Kind,
+    complex: Complex,
 }
 
 impl Station {
-    pub fn new(position: Point2<f32>, name: String, kind: Kind) -> Station {
+    pub fn new(position: Point2<f32>, name: String, kind: Kind, complex: Complex) -> Station {
         Station {
             position,
             name,
             kind,
+            complex,
         }
     }
 
+    pub fn position(&self) -> Point2<f32> {
+        self.position
+    }
+
     pub fn name(&self) -> &str {
         &self.name
     }
 
+    pub fn kind(&self) -> Kind {
+        self.kind
+    }
+
+    pub fn complex(&self) -> &Complex {
+        &self.complex
+    }
+
     pub fn contains(&self, position: Point2<f32>) -> bool {
         na::distance(&self.position, &position) <= 45.0
     }
@@ -44,28 +62,90 @@ pub enum Kind {
 }
 
 impl Kind {
-    pub fn from_line_kinds(kinds: &[line::Kind]) -> Self {
-        if kinds.contains(&line::Kind::Railway)
-            || kinds.contains(&line::Kind::SuburbanRailway)
-            || kinds.contains(&line::Kind::UrbanRailway)
-        {
-            Self::Interchange
-        } else if kinds.contains(&line::Kind::Tram) {
-            Self::TramStop
-        } else if kinds.contains(&line::Kind::Bus) {
-            Self::BusStop
-        } else if kinds.contains(&line::Kind::WaterTransport) {
-            Self::FerryPier
-        } else {
-            unreachable!()
+    fn from_line_kind(kind: line::Kind) -> Self {
+        match kind {
+            line::Kind::Railway
+            | line::Kind::RegionalRailway
+            | line::Kind::SuburbanRailway
+            | line::Kind::UrbanRailway
+            | line::Kind::Monorail
+            | line::Kind::Air => Self::Interchange,
+            line::Kind::Tram
+            | line::Kind::CableTram
+            | line::Kind::AerialLift
+            | line::Kind::Funicular => Self::TramStop,
+            line::Kind::Coach | line::Kind::Bus | line::Kind::Trolleybus | line::Kind::Taxi => {
+                Self::BusStop
+            }
+            line::Kind::WaterTransport => Self::FerryPier,
         }
     }
+
+    fn precedence(self) -> u8 {
+        match self {
+            Self::FerryPier => 0,
+            Self::BusStop => 1,
+            Self::TramStop => 2,
+            Self::Interchange => 3,
+        }
+    }
+
+    /// Picks the most prominent kind of station among the ones implied by the line kinds.
+    ///
+    /// Stations without any lines are treated as bus stops.
+    pub fn from_line_kinds(kinds: &[line::Kind]) -> Self {
+        kinds
+            .iter()
+            .map(|&kind| Self::from_line_kind(kind))
+            .max_by_key(|kind| kind.precedence())
+            .unwrap_or(Self::BusStop)
+    }
+}
+
+/// The index of the station of each node of the lines.
+pub(crate) fn station_indices(stations: &[Rc<Station>], lines: &[Line]) -> Vec<Vec<Option<usize>>> {
+    let by_address = stations
+        .iter()
+        .enumerate()
+        .map(|(index, station)| (Rc::as_ptr(station), index))
+        .collect::<HashMap<_, _>>();
+    lines
+        .iter()
+        .map(|line| {
+            line.nodes()
+                .iter()
+                .map(|node| {
+                    let station = node.station()?;
+                    by_address.get(&(station as *const Station)).copied()
+                })
+                .collect()
+        })
+        .collect()
 }
 
 #[cfg(any(test, feature = "fixtures"))]
 pub mod fixtures {
+    use std::cell::RefCell;
+
     use super::*;
 
+    thread_local! {
+        static SHARED: RefCell<HashMap<String, Rc<Station>>> = RefCell::new(HashMap::new());
+    }
+
+    /// The station shared by all fixtures of the thread, which lets the nodes of the fixture lines
+    /// refer to the same stations as the fixture datasets, like the ones of a loaded dataset do.
+    pub fn shared(station: fn() -> Station) -> Rc<Station> {
+        let station = station();
+        SHARED.with(|shared| {
+            let mut shared = shared.borrow_mut();
+            let station = shared
+                .entry(station.name.clone())
+                .or_insert_with(|| Rc::new(station));
+            Rc::clone(station)
+        })
+    }
+
     macro_rules! stations {
         ($($station:ident: $x:expr, $y:expr, $kind:ident, $name:expr);* $(;)?) => {
             $(
@@ -74,6 +154,7 @@ pub mod fixtures {
                         position: Point2::new($x as f32, $y as f32),
                         name: $name.to_string(),
                         kind: Kind::$kind,
+                        complex: Complex::default(),
                     }
                 }
             )*
@@ -106,7 +187,7 @@ mod tests {
     use approx::assert_relative_eq;
 
     use super::*;
-    use crate::fixtures::stations;
+    use crate::fixtures::{lines, stations};
 
     #[test]
     fn test_station_kind_from_line_kinds() {
@@ -120,12 +201,30 @@ mod tests {
             Kind::TramStop
         );
         assert_eq!(Kind::from_line_kinds(&[line::Kind::Bus]), Kind::BusStop);
+        assert_eq!(
+            Kind::from_line_kinds(&[line::Kind::Bus, line::Kind::WaterTransport]),
+            Kind::BusStop
+        );
+        assert_eq!(
+            Kind::from_line_kinds(&[line::Kind::WaterTransport]),
+            Kind::FerryPier
+        );
+        assert_eq!(
+            Kind::from_line_kinds(&[line::Kind::Coach, line::Kind::RegionalRailway]),
+            Kind::Interchange
+        );
+        assert_eq!(
+            Kind::from_line_kinds(&[line::Kind::AerialLift]),
+            Kind::TramStop
+        );
+        assert_eq!(Kind::from_line_kinds(&[]), Kind::BusStop);
     }
 
     #[test]
     fn test_getters() {
         let station = stations::hauptbahnhof();
         assert_eq!(station.name(), "Hauptbahnhof");
+        assert!(station.complex().is_empty());
     }
 
     #[test]
@@ -175,4 +274,20 @@ mod tests {
         stations::weskammstr().fill_type_buffer(&mut buffer);
         assert_eq!(*buffer, [3, 1, 0])
     }
+
+    #[test]
+    fn test_station_indices() {
+        let stations = vec![
+            stations::shared(stations::oranienburger_tor),
+            stations::shared(stations::friedrichstr),
+            stations::shared(stations::universitaetsstr),
+            Rc::new(stations::am_kupfergraben()),
+        ];
+        let indices = station_indices(&stations, &[lines::tram_12()]);
+        assert_eq!(indices[0][0], Some(0));
+        assert_eq!(indices[0][1], None);
+        assert_eq!(indices[0][4], Some(1));
+        // an equal station which is not the one of the node
+        assert_eq!(indices[0][16], None);
+    }
 }
@@ -0,0 +1,52 @@
+use std::fmt;
+
+/// Parses a time given as hh:mm[:ss] into seconds, allowing hours beyond midnight.
+pub fn parse_time(value: &str) -> Option<u32> {
+    let parts = value
+        .split(':')
+        .map(|part| part.parse::<u32>().ok())
+        .collect::<Option<Vec<_>>>()?;
+    match parts[..] {
+        [hours, minutes] if minutes < 60 => Some(hours * 3600 + minutes * 60),
+        [hours, minutes, seconds] if minutes < 60 && seconds < 60 => {
+            Some(hours * 3600 + minutes * 60 + seconds)
+        }
+        _ => None,
+    }
+}
+
+/// Formats seconds as h:mm, adding the seconds only if there are any.
+pub struct TimeDisplay(pub u32);
+
+impl fmt::Display for TimeDisplay {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let (hours, minutes, seconds) = (self.0 / 3600, self.0 / 60 % 60, self.0 % 60);
+        if seconds == 0 {
+            write!(formatter, "{}:{:02}", hours, minutes)
+        } else {
+            write!(formatter, "{}:{:02}:{:02}", hours, minutes, seconds)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use test_utils::time;
+
+    #[test]
+    fn test_parse_time() {
+        assert_eq!(parse_time("8:05"), Some(time!(8:05:00)));
+        assert_eq!(parse_time("25:00:30"), Some(time!(25:00:30)));
+        assert_eq!(parse_time("8:60"), None);
+        assert_eq!(parse_time("8"), None);
+        assert_eq!(parse_time("8:0x"), None);
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(TimeDisplay(time!(8:05:00)).to_string(), "8:05");
+        assert_eq!(TimeDisplay(time!(25:00:30)).to_string(), "25:00:30");
+    }
+}
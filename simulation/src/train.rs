@@ -4,6 +4,8 @@ use crate::direction::Direction;
 use crate::line::Kind;
 use crate::node::Node;
 
+pub(crate) const DAY: u32 = 24 * 60 * 60;
+
 #[derive(Debug, PartialEq, Clone, Copy)]
 enum TrainState {
     WaitingForDispatch,
@@ -13,6 +15,13 @@ enum TrainState {
 }
 
 impl TrainState {
+    fn is_active(&self) -> bool {
+        match self {
+            TrainState::Driving { .. } | TrainState::Stopped { .. } => true,
+            TrainState::WaitingForDispatch | TrainState::Finished => false,
+        }
+    }
+
     fn next(self, direction: Direction, nodes: &[Node]) -> TrainState {
         let (at, already_stopped) = match self {
             TrainState::WaitingForDispatch => (direction.start(nodes.len()), false),
@@ -31,28 +40,95 @@ impl TrainState {
     }
 }
 
-#[derive(Debug, PartialEq)]
+/// Whether an active train stands at a stop or is on its way to the next one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopStatus {
+    StoppedAt(usize),
+    InTransitTo(usize),
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct Train {
     kind: Kind,
     direction: Direction,
+    days: u64,
     durations: Vec<u32>,
+    ends: Vec<u32>,
+    scheduled_ends: Vec<u32>,
+    trip: usize,
+    /// Duration indices of the first and the last stop served.
+    served: (usize, usize),
+    /// Platform of each stop, which is empty if none is known.
+    platforms: Vec<Option<usize>>,
+    states: Vec<TrainState>,
+    active_span: Option<(u32, u32)>,
+    from_previous_day: bool,
     current: usize,
     current_passed: u32,
     state: TrainState,
 }
 
 impl Train {
-    pub fn new(kind: Kind, direction: Direction, durations: Vec<u32>) -> Train {
+    /// A train running on the service days set in the bitmask.
+    ///
+    /// The lowest bit stands for the day before the first stored day, the following ones for the
+    /// stored days.
+    pub fn new(kind: Kind, direction: Direction, days: u64, durations: Vec<u32>) -> Train {
+        let ends = Self::ends(&durations);
         Train {
             kind,
             direction,
+            days,
             durations,
+            scheduled_ends: ends.clone(),
+            ends,
+            trip: 0,
+            served: (0, usize::MAX),
+            platforms: Vec::new(),
+            states: Vec::new(),
+            active_span: None,
+            from_previous_day: false,
             current: 0,
             current_passed: 0,
             state: TrainState::WaitingForDispatch,
         }
     }
 
+    fn ends(durations: &[u32]) -> Vec<u32> {
+        durations
+            .iter()
+            .scan(0, |end, &duration| {
+                *end += duration;
+                Some(*end)
+            })
+            .collect()
+    }
+
+    /// Sets the platform of each stop as index into the places of the complex of its station.
+    pub fn with_platforms(mut self, platforms: Vec<Option<usize>>) -> Train {
+        self.platforms = platforms;
+        self
+    }
+
+    /// Index of the trip in the timetable of its line, which identifies it across days.
+    pub fn trip(&self) -> usize {
+        self.trip
+    }
+
+    pub(crate) fn set_trip(&mut self, trip: usize) {
+        self.trip = trip;
+    }
+
+    /// Tells the parts of a split trip apart, as the duration index of the first stop served.
+    pub(crate) fn part(&self) -> usize {
+        self.served.0
+    }
+
+    /// Index of the duration the train is currently in, which counts the states passed.
+    pub(crate) fn duration_index(&self) -> usize {
+        self.current
+    }
+
     pub fn update(&mut self, time_passed: u32, nodes: &[Node]) {
         self.current_passed += time_passed;
 
@@ -65,10 +141,251 @@ impl Train {
         }
     }
 
+    /// Computes the state reached after each of the durations, which is needed for seeking.
+    pub(crate) fn prepare(&mut self, nodes: &[Node]) {
+        if self.states.is_empty() {
+            let mut state = TrainState::WaitingForDispatch;
+            self.states.push(state);
+            for _ in &self.durations {
+                state = state.next(self.direction, nodes);
+                self.states.push(state);
+            }
+
+            let dispatch = self.states.iter().position(TrainState::is_active);
+            let finish = self
+                .states
+                .iter()
+                .position(|&state| state == TrainState::Finished);
+            self.active_span = dispatch.map(|dispatch| {
+                let until = finish.map_or(u32::MAX, |finish| self.ends[finish - 1]);
+                (self.ends[dispatch - 1], until)
+            });
+        }
+    }
+
+    /// The train is active after the first and until the second time of the span, both given in
+    /// seconds since midnight of its service day.
+    ///
+    /// This is `None` if the train is never active or not prepared yet.
+    pub(crate) fn active_span(&self) -> Option<(u32, u32)> {
+        self.active_span
+    }
+
+    /// Applies changed durations and moves the train back before its dispatch.
+    fn reschedule(&mut self, nodes: &[Node]) {
+        self.ends = Self::ends(&self.durations);
+        self.states.clear();
+        self.prepare(nodes);
+        self.current = 0;
+        self.current_passed = 0;
+        self.state = TrainState::WaitingForDispatch;
+    }
+
+    /// The duration indices at which a prepared train stands at a stop, with the node of the stop.
+    pub(crate) fn stops(&self) -> Vec<(usize, usize)> {
+        self.states
+            .iter()
+            .enumerate()
+            .filter_map(|(index, &state)| match state {
+                TrainState::Stopped { at } => Some((index, at)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// The stops like in [`stops`](Self::stops), without the ones left out by splitting the trip.
+    pub(crate) fn served_stops(&self) -> Vec<(usize, usize)> {
+        let (first, last) = self.served;
+        self.stops()
+            .into_iter()
+            .filter(|&(index, _)| first <= index && index <= last)
+            .collect()
+    }
+
+    /// Time at which the train arrives at the stop of the duration index.
+    pub(crate) fn arrival(&self, index: usize) -> u32 {
+        self.ends[index - 1]
+    }
+
+    /// The platform of the stop of the duration index, if it is known.
+    pub(crate) fn platform(&self, index: usize) -> Option<usize> {
+        let stop = self.stops().iter().position(|&(stop, _)| stop == index)?;
+        self.platforms.get(stop).copied().flatten()
+    }
+
+    /// Time at which the train leaves the stop of the duration index.
+    pub(crate) fn departure(&self, index: usize) -> u32 {
+        self.ends[index]
+    }
+
+    /// Time at which the train was originally scheduled to leave the stop of the duration index.
+    pub(crate) fn scheduled_departure(&self, index: usize) -> u32 {
+        self.scheduled_ends[index]
+    }
+
+    /// Extends the duration at the index, delaying the remaining trip by the same amount.
+    pub(crate) fn delay_at(&mut self, index: usize, delay: u32, nodes: &[Node]) {
+        self.durations[index] += delay;
+        self.reschedule(nodes);
+    }
+
+    /// The part of the trip until leaving the stop of the duration index.
+    pub(crate) fn part_until_stop(&self, index: usize, nodes: &[Node]) -> Train {
+        let mut train = self.clone();
+        for duration in &mut train.durations[index + 1..] {
+            *duration = 0;
+        }
+        train.served.1 = index;
+        train.reschedule(nodes);
+        train
+    }
+
+    /// The part of the trip starting with the arrival at the stop of the duration index.
+    pub(crate) fn part_from_stop(&self, index: usize, nodes: &[Node]) -> Train {
+        let mut train = self.clone();
+        train.durations[0] = self.arrival(index);
+        for duration in &mut train.durations[1..index] {
+            *duration = 0;
+        }
+        train.served.0 = index;
+        train.reschedule(nodes);
+        train
+    }
+
+    /// Moves the train forwards or backwards to the time since midnight of its service day.
+    pub fn set_time(&mut self, time: u32, nodes: &[Node]) {
+        self.prepare(nodes);
+        // the first duration which has not completely passed yet
+        let current = self.ends.partition_point(|&end| end < time);
+        let passed_before = current.checked_sub(1).map_or(0, |before| self.ends[before]);
+        self.current = current;
+        self.current_passed = time - passed_before;
+        self.state = self.states[current];
+    }
+
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    pub fn start_time(&self) -> u32 {
+        self.durations[0]
+    }
+
+    /// Time since midnight of the service day at which the train finishes its trip.
+    pub fn end_time(&self) -> u32 {
+        self.ends.last().copied().unwrap_or(0)
+    }
+
+    fn runs_on_bit(&self, bit: usize) -> bool {
+        bit < 64 && self.days & (1 << bit) != 0
+    }
+
+    /// Whether the train runs on the service day, counted from the first stored day.
+    pub fn runs_on(&self, day: usize) -> bool {
+        self.runs_on_bit(day + 1)
+    }
+
+    /// Whether the train runs on the service day before the given one.
+    pub(crate) fn runs_on_day_before(&self, day: usize) -> bool {
+        self.runs_on_bit(day)
+    }
+
+    /// Continues a train of the previous service day at midnight.
+    pub(crate) fn continue_at_midnight(&mut self, nodes: &[Node]) {
+        self.set_time(DAY, nodes);
+        self.from_previous_day = true;
+    }
+
+    /// Whether the train started on the previous service day and runs past midnight.
+    pub fn is_from_previous_day(&self) -> bool {
+        self.from_previous_day
+    }
+
     pub fn is_active(&self) -> bool {
+        self.state.is_active()
+    }
+
+    /// Time since midnight of the service day the train has reached.
+    fn time(&self) -> u32 {
+        let passed_before = self
+            .current
+            .checked_sub(1)
+            .map_or(0, |before| self.ends[before]);
+        passed_before + self.current_passed
+    }
+
+    /// Seconds the train runs behind its original schedule, for example after a disruption.
+    pub fn delay(&self) -> u32 {
+        let current = self.current.min(self.ends.len().saturating_sub(1));
+        self.ends
+            .get(current)
+            .map_or(0, |end| end.saturating_sub(self.scheduled_ends[current]))
+    }
+
+    /// Fraction of the run from the first to the last served stop which is completed.
+    pub fn progress(&self) -> f32 {
+        let stops = self.served_stops();
+        let (first, last) = match (stops.first(), stops.last()) {
+            (Some(&(first, _)), Some(&(last, _))) => (first, last),
+            _ => return 0.0,
+        };
+        let (start, end) = (self.departure(first), self.arrival(last));
+        let time = self.time();
+        if end <= start {
+            return if time >= end { 1.0 } else { 0.0 };
+        }
+        (time.saturating_sub(start) as f32 / (end - start) as f32).clamp(0.0, 1.0)
+    }
+
+    /// Node indices of the stop the train stands at and of the next stop it serves.
+    pub fn current_and_next_stop(&self) -> (Option<usize>, Option<usize>) {
+        let stops = self.served_stops();
+        let current = stops
+            .iter()
+            .find(|&&(index, _)| index == self.current && self.state.is_active())
+            .map(|&(_, node)| node);
+        let next = stops
+            .iter()
+            .find(|&&(index, _)| index > self.current)
+            .map(|&(_, node)| node);
+        (current, next)
+    }
+
+    /// Whether the position lies within the rectangle drawn for an active train.
+    pub fn contains(&self, position: Point2<f32>, nodes: &[Node]) -> bool {
+        self.position(nodes).iter().any(|&(center, orientation)| {
+            let offset = position - center;
+            let along = offset.dot(&orientation);
+            let across = offset.perp(&orientation);
+            let size = self.kind.train_size();
+            along.abs() <= 0.5 * size.x && across.abs() <= 0.5 * size.y
+        })
+    }
+
+    /// Position and normalized orientation of an active train.
+    pub fn position(&self, nodes: &[Node]) -> Option<(Point2<f32>, Vector2<f32>)> {
+        if self.is_active() {
+            Some(self.calculate_rectangle(nodes))
+        } else {
+            None
+        }
+    }
+
+    /// The node index of the stop the train is at or heading to.
+    pub fn stop_status(&self, nodes: &[Node]) -> Option<StopStatus> {
         match self.state {
-            TrainState::Driving { .. } | TrainState::Stopped { .. } => true,
-            TrainState::WaitingForDispatch | TrainState::Finished => false,
+            TrainState::Stopped { at } => Some(StopStatus::StoppedAt(at)),
+            TrainState::Driving { to, .. } => {
+                let mut next = Some(to);
+                while let Some(at) = next {
+                    if nodes[at].is_stop() {
+                        return Some(StopStatus::InTransitTo(at));
+                    }
+                    next = self.direction.find_next(at, nodes);
+                }
+                None
+            }
+            TrainState::WaitingForDispatch | TrainState::Finished => None,
         }
     }
 
@@ -87,7 +404,7 @@ impl Train {
                 (current, orientation.normalize())
             }
             TrainState::Driving { from, to } => {
-                let travelled = self.current_passed as f32 / self.durations[self.current] as f32;
+                let travelled = self.travelled_on_segment(from, to, nodes);
                 let from = nodes[from].position();
                 let to = nodes[to].position();
                 let segment = to - from;
@@ -97,6 +414,52 @@ impl Train {
         }
     }
 
+    /// Fraction of the current segment driven, following the motion profile over the whole run
+    /// between the surrounding stops.
+    fn travelled_on_segment(&self, from: usize, to: usize, nodes: &[Node]) -> f32 {
+        let length = na::distance(&nodes[from].position(), &nodes[to].position());
+        let mut elapsed = self.current_passed as f32;
+        let mut duration = self.durations[self.current] as f32;
+        let mut distance = length;
+        let mut before = 0.0;
+
+        let (mut node, mut index) = (from, self.current);
+        while !nodes[node].is_stop() && index > 0 {
+            let previous = match self.direction.find_previous(node, nodes) {
+                Some(previous) => previous,
+                None => break,
+            };
+            index -= 1;
+            elapsed += self.durations[index] as f32;
+            duration += self.durations[index] as f32;
+            let segment = na::distance(&nodes[previous].position(), &nodes[node].position());
+            before += segment;
+            distance += segment;
+            node = previous;
+        }
+
+        let (mut node, mut index) = (to, self.current);
+        while !nodes[node].is_stop() && index + 1 < self.durations.len() {
+            let next = match self.direction.find_next(node, nodes) {
+                Some(next) => next,
+                None => break,
+            };
+            index += 1;
+            duration += self.durations[index] as f32;
+            distance += na::distance(&nodes[node].position(), &nodes[next].position());
+            node = next;
+        }
+
+        if length == 0.0 {
+            return self.current_passed as f32 / self.durations[self.current] as f32;
+        }
+        let covered = self
+            .kind
+            .motion_profile()
+            .travelled(distance, duration, elapsed);
+        ((covered - before) / length).clamp(0.0, 1.0)
+    }
+
     fn write_rectangle(
         &self,
         buffer: &mut Vec<f32>,
@@ -148,6 +511,7 @@ pub mod fixtures {
                             Train::new(
                                 Kind::$kind,
                                 Direction::$direction,
+                                0b10,
                                 times!(start, $times),
                             )
                         }
@@ -172,14 +536,14 @@ pub mod fixtures {
         },
         tram_m5: Tram, {
             zingster_str_perower_platz => Upstream,
-            [0:00, 1:00, 0:00, 1:00, 0:00, 0:48, 1:12, 0:00];
+            [0:00, 1:00, 0:00, 1:00, 0:00, 0:49, 1:11, 0:00];
         },
         tram_12: Tram, {
             oranienburger_tor_am_kupfergraben => Upstream,
-            [0:20, 0:27, 1:21, 0:27, 0:20, 0:19, 0:11, 0:12, 0:12, 0:12, 0:20, 1:00, 0:20];
+            [0:20, 0:28, 1:19, 0:28, 0:20, 0:20, 0:11, 0:10, 0:11, 0:13, 0:20, 1:00, 0:20];
             am_kupfergraben_oranienburger_tor => Downstream,
-            [0:20, 0:19, 0:22, 0:12, 0:22, 0:20, 0:25, 0:12, 0:20, 0:12, 0:24, 0:20, 0:30,
-                0:31, 1:01, 0:30, 0:20];
+            [0:20, 0:20, 0:21, 0:11, 0:23, 0:20, 0:26, 0:11, 0:20, 0:11, 0:25, 0:20, 0:31,
+                0:30, 1:00, 0:31, 0:20];
         },
         bus_m82: Bus, {
             weskammstr_waldsassener_str => Upstream,
@@ -206,6 +570,8 @@ mod tests {
         train.update(0, &nodes::tram_12());
         assert_eq!(train.state, TrainState::WaitingForDispatch);
         assert!(!train.is_active());
+        assert_eq!(train.position(&nodes::tram_12()), None);
+        assert_eq!(train.stop_status(&nodes::tram_12()), None);
     }
 
     #[test]
@@ -215,6 +581,10 @@ mod tests {
         train.update(time!(9:02:30), &nodes::tram_12());
         assert_eq!(train.state, TrainState::Stopped { at: 0 });
         assert!(train.is_active());
+        assert_eq!(
+            train.stop_status(&nodes::tram_12()),
+            Some(StopStatus::StoppedAt(0))
+        );
 
         let (position, orientation) = train.calculate_rectangle(&nodes::tram_12());
         assert_relative_eq!(position, Point2::new(-98.0, -1671.0));
@@ -227,12 +597,33 @@ mod tests {
         train.update(time!(9:02:47), &nodes::tram_12());
         assert_eq!(train.state, TrainState::Driving { from: 0, to: 1 });
         assert!(train.is_active());
+        assert_eq!(
+            train.stop_status(&nodes::tram_12()),
+            Some(StopStatus::InTransitTo(4))
+        );
 
-        let (position, orientation) = train.calculate_rectangle(&nodes::tram_12());
-        assert_relative_eq!(position, Point2::new(-101.0, -1560.0));
+        let (position, orientation) = train.position(&nodes::tram_12()).unwrap();
+        assert_relative_eq!(position, Point2::new(-100.88, -1564.39), epsilon = 0.01);
         assert_relative_eq!(orientation, segment_vector(&nodes::tram_12(), 0, 1));
     }
 
+    #[test]
+    fn test_driving_accelerates_and_brakes() {
+        let nodes = nodes::tram_12();
+        let mut train = trains::tram_12::oranienburger_tor_am_kupfergraben(time!(9:02:00));
+        train.update(time!(9:02:23), &nodes);
+        assert_eq!(train.state, TrainState::Driving { from: 0, to: 1 });
+        let (from, to) = (nodes[0].position(), nodes[1].position());
+        let travelled = na::distance(&from, &train.position(&nodes).unwrap().0);
+        assert!(travelled < 0.1 * na::distance(&from, &to));
+
+        train.update(time!(0:02:09), &nodes);
+        assert_eq!(train.state, TrainState::Driving { from: 3, to: 4 });
+        let (from, to) = (nodes[3].position(), nodes[4].position());
+        let remaining = na::distance(&train.position(&nodes).unwrap().0, &to);
+        assert!(remaining < 0.1 * na::distance(&from, &to));
+    }
+
     #[test]
     fn test_upstream_ignores_downstream_only() {
         let mut train = trains::tram_12::oranienburger_tor_am_kupfergraben(time!(9:02:00));
@@ -247,6 +638,70 @@ mod tests {
         assert_eq!(train.state, TrainState::Driving { from: 10, to: 7 });
     }
 
+    #[test]
+    fn test_runs_on() {
+        let train = Train::new(Kind::Tram, Direction::Upstream, 0b1011, Vec::new());
+        assert!(train.runs_on_day_before(0));
+        assert!(train.runs_on(0));
+        assert!(!train.runs_on(1));
+        assert!(train.runs_on(2));
+        assert!(!train.runs_on(63));
+    }
+
+    #[test]
+    fn test_end_time() {
+        let train = trains::tram_12::oranienburger_tor_am_kupfergraben(time!(9:02:00));
+        assert_eq!(train.end_time(), time!(9:07:40));
+    }
+
+    #[test]
+    fn test_set_time() {
+        let nodes = nodes::tram_12();
+        for &time in &[
+            0,
+            time!(9:01:40),
+            time!(9:02:00),
+            time!(9:04:10),
+            time!(9:20:00),
+        ] {
+            let mut updated = trains::tram_12::oranienburger_tor_am_kupfergraben(time!(9:01:40));
+            updated.update(time, &nodes);
+            let mut seeked = trains::tram_12::oranienburger_tor_am_kupfergraben(time!(9:01:40));
+            seeked.set_time(time, &nodes);
+            assert_eq!(seeked.current, updated.current);
+            assert_eq!(seeked.current_passed, updated.current_passed);
+            assert_eq!(seeked.state, updated.state);
+        }
+    }
+
+    #[test]
+    fn test_set_time_backwards() {
+        let nodes = nodes::tram_12();
+        let mut train = trains::tram_12::oranienburger_tor_am_kupfergraben(time!(9:01:40));
+        train.set_time(time!(9:06:00), &nodes);
+        train.set_time(time!(9:02:47), &nodes);
+        let mut updated = trains::tram_12::oranienburger_tor_am_kupfergraben(time!(9:01:40));
+        updated.update(time!(9:02:47), &nodes);
+        assert_eq!(train.state, updated.state);
+        assert_eq!(train.position(&nodes), updated.position(&nodes));
+        train.set_time(time!(9:00:00), &nodes);
+        assert_eq!(train.state, TrainState::WaitingForDispatch);
+        assert!(!train.is_active());
+    }
+
+    #[test]
+    fn test_active_span() {
+        let nodes = nodes::tram_12();
+        let mut train = trains::tram_12::oranienburger_tor_am_kupfergraben(time!(9:01:40));
+        assert_eq!(train.active_span(), None);
+        train.prepare(&nodes);
+        let (after, until) = train.active_span().unwrap();
+        for &time in &[after, after + 1, until, until + 1] {
+            train.set_time(time, &nodes);
+            assert_eq!(train.is_active(), after < time && time <= until);
+        }
+    }
+
     #[test]
     fn test_finished() {
         let mut train = trains::tram_12::oranienburger_tor_am_kupfergraben(time!(9:02:00));
@@ -273,9 +728,71 @@ mod tests {
         assert!(train.is_active());
     }
 
+    #[test]
+    fn test_delay_and_next_stop() {
+        let nodes = nodes::tram_12();
+        let mut train = trains::tram_12::oranienburger_tor_am_kupfergraben(time!(9:02:00));
+        train.prepare(&nodes);
+        let friedrichstr = train.stops()[1].0;
+        train.delay_at(friedrichstr, 60, &nodes);
+
+        train.set_time(time!(9:04:00), &nodes);
+        assert_eq!(train.delay(), 0);
+        assert_eq!(train.current_and_next_stop(), (None, Some(4)));
+        train.set_time(time!(9:04:45), &nodes);
+        assert_eq!(train.delay(), 60);
+        assert_eq!(train.current_and_next_stop(), (Some(4), Some(9)));
+        train.set_time(time!(9:06:00), &nodes);
+        assert_eq!(train.delay(), 60);
+        assert_eq!(train.current_and_next_stop(), (None, Some(9)));
+    }
+
+    #[test]
+    fn test_platform() {
+        let mut train = trains::tram_12::oranienburger_tor_am_kupfergraben(time!(9:02:00))
+            .with_platforms(vec![Some(2), None, None, Some(0)]);
+        train.prepare(&nodes::tram_12());
+        assert_eq!(train.platform(1), Some(2));
+        assert_eq!(train.platform(5), None);
+        assert_eq!(train.platform(13), Some(0));
+        assert_eq!(train.platform(2), None);
+    }
+
+    #[test]
+    fn test_served_stops_of_parts() {
+        let nodes = nodes::tram_12();
+        let mut train = trains::tram_12::oranienburger_tor_am_kupfergraben(time!(9:02:00));
+        train.prepare(&nodes);
+        let friedrichstr = train.stops()[1].0;
+
+        let mut first = train.part_until_stop(friedrichstr, &nodes);
+        assert_eq!(first.served_stops(), train.stops()[..2]);
+        first.set_time(time!(9:04:08), &nodes);
+        assert_eq!(first.current_and_next_stop(), (None, Some(4)));
+        assert_relative_eq!(first.progress(), 0.8);
+
+        let second = train.part_from_stop(friedrichstr, &nodes);
+        assert_eq!(second.served_stops(), train.stops()[1..]);
+        assert_eq!(second.current_and_next_stop(), (None, Some(4)));
+        assert_relative_eq!(second.progress(), 0.0);
+    }
+
+    #[test]
+    fn test_contains() {
+        let nodes = nodes::tram_12();
+        let mut train = trains::tram_12::oranienburger_tor_am_kupfergraben(time!(9:02:00));
+        assert!(!train.contains(nodes[0].position(), &nodes));
+        train.update(time!(9:02:10), &nodes);
+        let (position, orientation) = train.position(&nodes).unwrap();
+        let side = Vector2::new(-orientation.y, orientation.x);
+        assert!(train.contains(position + 75.0 * orientation + 45.0 * side, &nodes));
+        assert!(!train.contains(position + 85.0 * orientation, &nodes));
+        assert!(!train.contains(position + 55.0 * side, &nodes));
+    }
+
     #[test]
     fn test_rectangle_horizontal() {
-        let train = Train::new(Kind::SuburbanRailway, Direction::Upstream, Vec::new());
+        let train = Train::new(Kind::SuburbanRailway, Direction::Upstream, 0b10, Vec::new());
         let mut buffer = Vec::new();
         train.write_rectangle(
             &mut buffer,
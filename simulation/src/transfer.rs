@@ -0,0 +1,71 @@
+use serde_derive::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq)]
+pub struct Transfer {
+    from: usize,
+    to: usize,
+    kind: Kind,
+    min_time: Option<u32>,
+}
+
+impl Transfer {
+    pub fn new(from: usize, to: usize, kind: Kind, min_time: Option<u32>) -> Self {
+        Self {
+            from,
+            to,
+            kind,
+            min_time,
+        }
+    }
+
+    pub fn origin(&self) -> usize {
+        self.from
+    }
+
+    pub fn target(&self) -> usize {
+        self.to
+    }
+
+    pub fn kind(&self) -> Kind {
+        self.kind
+    }
+
+    pub fn min_time(&self) -> Option<u32> {
+        self.min_time
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Kind {
+    Recommended,
+    Timed,
+    MinimumTime,
+    Forbidden,
+}
+
+impl Kind {
+    pub fn is_possible(self) -> bool {
+        self != Self::Forbidden
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_getters() {
+        let transfer = Transfer::new(1, 6, Kind::MinimumTime, Some(180));
+        assert_eq!(transfer.origin(), 1);
+        assert_eq!(transfer.target(), 6);
+        assert_eq!(transfer.kind(), Kind::MinimumTime);
+        assert_eq!(transfer.min_time(), Some(180));
+    }
+
+    #[test]
+    fn test_forbidden_is_impossible() {
+        assert!(Kind::Recommended.is_possible());
+        assert!(Kind::MinimumTime.is_possible());
+        assert!(!Kind::Forbidden.is_possible());
+    }
+}
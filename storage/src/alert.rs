@@ -0,0 +1,60 @@
+use serde_derive::{Deserialize, Serialize};
+
+use simulation::Severity;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Alert {
+    header: String,
+    severity: Severity,
+    periods: Vec<(u32, u32)>,
+    lines: Vec<usize>,
+    stations: Vec<usize>,
+}
+
+impl Alert {
+    pub fn new(
+        header: String,
+        severity: Severity,
+        periods: Vec<(u32, u32)>,
+        lines: Vec<usize>,
+        stations: Vec<usize>,
+    ) -> Self {
+        Self {
+            header,
+            severity,
+            periods,
+            lines,
+            stations,
+        }
+    }
+
+    pub fn load(self) -> simulation::Alert {
+        simulation::Alert::new(
+            self.header,
+            self.severity,
+            self.periods,
+            self.lines,
+            self.stations,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load() {
+        let alert = Alert::new(
+            "Friedrichstr. closed".to_string(),
+            Severity::Severe,
+            vec![(32400, 43200)],
+            vec![],
+            vec![1],
+        );
+        assert_eq!(
+            alert.load(),
+            simulation::fixtures::alerts::closure_friedrichstr()
+        );
+    }
+}
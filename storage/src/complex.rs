@@ -0,0 +1,133 @@
+use serde_derive::{Deserialize, Serialize};
+
+use na::Point2;
+
+use simulation::complex::{PathwayMode, PlaceKind};
+
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Complex {
+    places: Vec<Place>,
+    pathways: Vec<Pathway>,
+}
+
+impl Complex {
+    pub fn new(places: Vec<Place>, pathways: Vec<Pathway>) -> Self {
+        Self { places, pathways }
+    }
+
+    pub fn load(self) -> simulation::Complex {
+        simulation::Complex::new(
+            self.places.into_iter().map(Place::load).collect(),
+            self.pathways.into_iter().map(Pathway::load).collect(),
+        )
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Place {
+    kind: PlaceKind,
+    name: String,
+    position: Point2<f32>,
+}
+
+impl Place {
+    pub fn new(kind: PlaceKind, name: String, position: Point2<f32>) -> Self {
+        Self {
+            kind,
+            name,
+            position,
+        }
+    }
+
+    fn load(self) -> simulation::complex::Place {
+        simulation::complex::Place::new(self.kind, self.name, self.position)
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Pathway {
+    from: usize,
+    to: usize,
+    mode: PathwayMode,
+    bidirectional: bool,
+    traversal_time: u32,
+}
+
+impl Pathway {
+    pub fn new(
+        from: usize,
+        to: usize,
+        mode: PathwayMode,
+        bidirectional: bool,
+        traversal_time: u32,
+    ) -> Self {
+        Self {
+            from,
+            to,
+            mode,
+            bidirectional,
+            traversal_time,
+        }
+    }
+
+    fn load(self) -> simulation::complex::Pathway {
+        simulation::complex::Pathway::new(
+            self.from,
+            self.to,
+            self.mode,
+            self.bidirectional,
+            self.traversal_time,
+        )
+    }
+}
+
+#[cfg(any(test, feature = "fixtures"))]
+pub mod fixtures {
+    use super::*;
+
+    pub fn hauptbahnhof() -> Complex {
+        Complex::new(
+            vec![
+                Place::new(
+                    PlaceKind::Entrance,
+                    "Hauptbahnhof Nord".to_string(),
+                    Point2::new(-1388.0, -1890.0),
+                ),
+                Place::new(
+                    PlaceKind::GenericNode,
+                    "Hauptbahnhof Mittelhalle".to_string(),
+                    Point2::new(-1385.0, -1812.0),
+                ),
+                Place::new(
+                    PlaceKind::Platform,
+                    "Hauptbahnhof Gleis 1".to_string(),
+                    Point2::new(-1380.0, -1810.0),
+                ),
+                Place::new(
+                    PlaceKind::Platform,
+                    "Hauptbahnhof Gleis 16".to_string(),
+                    Point2::new(-1390.0, -1815.0),
+                ),
+            ],
+            vec![
+                Pathway::new(0, 1, PathwayMode::Walkway, true, 90),
+                Pathway::new(1, 2, PathwayMode::Escalator, false, 40),
+                Pathway::new(2, 1, PathwayMode::Stairs, false, 60),
+                Pathway::new(1, 3, PathwayMode::Elevator, true, 120),
+            ],
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixtures::complexes;
+
+    #[test]
+    fn test_load() {
+        assert_eq!(
+            complexes::hauptbahnhof().load(),
+            simulation::fixtures::complexes::hauptbahnhof()
+        );
+    }
+}
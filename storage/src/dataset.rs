@@ -3,26 +3,49 @@ use std::rc::Rc;
 
 use serde_derive::{Deserialize, Serialize};
 
+use crate::alert::Alert;
 use crate::line::Line;
+use crate::metadata::Metadata;
 use crate::schedule::Schedule;
 use crate::station::Station;
+use crate::transfer::Transfer;
 
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct Dataset {
+    metadata: Metadata,
+    day_count: usize,
     stations: Vec<Station>,
     schedules: Vec<Schedule>,
     lines: Vec<Line>,
+    transfers: Vec<Transfer>,
+    alerts: Vec<Alert>,
 }
 
 impl Dataset {
-    pub fn new(stations: Vec<Station>, schedules: Vec<Schedule>, lines: Vec<Line>) -> Self {
+    pub fn new(
+        metadata: Metadata,
+        day_count: usize,
+        stations: Vec<Station>,
+        schedules: Vec<Schedule>,
+        lines: Vec<Line>,
+        transfers: Vec<Transfer>,
+        alerts: Vec<Alert>,
+    ) -> Self {
         Self {
+            metadata,
+            day_count,
             stations,
             schedules,
             lines,
+            transfers,
+            alerts,
         }
     }
 
+    pub fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
     pub fn load(self) -> simulation::Dataset {
         let mut station_infos = repeat_with(Vec::new).take(self.stations.len()).collect();
         for line in &self.lines {
@@ -43,30 +66,49 @@ impl Dataset {
             .into_iter()
             .map(|line| line.load(&stations, schedules))
             .collect();
-        simulation::Dataset::new(stations, lines)
+        let transfers = self
+            .transfers
+            .into_iter()
+            .map(|transfer| transfer.load())
+            .collect();
+        let alerts = self.alerts.into_iter().map(|alert| alert.load()).collect();
+        simulation::Dataset::new(stations, lines, transfers, alerts, self.day_count)
     }
 }
 
 #[cfg(any(test, feature = "fixtures"))]
 pub mod fixtures {
     use super::*;
-    use crate::fixtures::lines;
+    use crate::fixtures::{lines, metadata};
     use crate::fixtures_with_ids;
+    use simulation::transfer::Kind as TransferKind;
 
     macro_rules! datasets {
         ( $( $dataset:ident => {
                 stations: [ $($station:ident),* $(,)? ],
                 schedules: [ $($schedule:ident),* $(,)? ],
                 lines: [ $($line:ident),* $(,)? ],
+                transfers: [ $( $from:ident -> $to:ident: $kind:ident, $min_time:expr );* $(;)? ],
             } ),* $(,)? ) => (
             $(
                 pub fn $dataset() -> Dataset {
                     let (stations, station_ids) = fixtures_with_ids!(stations::{$($station),*});
                     let (schedules, schedule_ids) = fixtures_with_ids!(schedules::{$($schedule),*});
                     Dataset {
+                        metadata: metadata::vbb(),
+                        day_count: 1,
                         stations,
                         schedules,
                         lines: vec![ $( lines::$line(&station_ids, &schedule_ids) ),* ],
+                        transfers: vec![ $(
+                            Transfer::new(
+                                station_ids[stringify!($from)],
+                                station_ids[stringify!($to)],
+                                TransferKind::$kind,
+                                $min_time,
+                            )
+                        ),* ],
+                        alerts: Vec::new(),
                     }
                 }
             )*
@@ -86,6 +128,11 @@ pub mod fixtures {
                 oranienburger_tor_am_kupfergraben, am_kupfergraben_oranienburger_tor,
             ],
             lines: [u6, s3, tram_12],
+            transfers: [
+                friedrichstr -> oranienburger_tor: MinimumTime, Some(240);
+                oranienburger_tor -> friedrichstr: MinimumTime, Some(240);
+                friedrichstr -> universitaetsstr: Recommended, None;
+            ],
         },
     }
 }
@@ -1,23 +1,33 @@
 #![allow(clippy::zero_prefixed_literal)]
 
+mod alert;
+mod complex;
 mod dataset;
 mod line;
+mod metadata;
 mod node;
 mod schedule;
 mod station;
 mod train;
+mod transfer;
 
+pub use crate::alert::Alert;
+pub use crate::complex::{Complex, Pathway, Place};
 pub use crate::dataset::Dataset;
 pub use crate::line::Line;
+pub use crate::metadata::{FeedInfo, Metadata};
 pub use crate::node::{Kind as NodeKind, Node};
 pub use crate::schedule::Schedule;
 pub use crate::station::Station;
-pub use crate::train::Train;
+pub use crate::train::{Train, MAX_DAYS};
+pub use crate::transfer::Transfer;
 
 #[cfg(any(test, feature = "fixtures"))]
 pub mod fixtures {
+    pub use crate::complex::fixtures as complexes;
     pub use crate::dataset::fixtures as datasets;
     pub use crate::line::fixtures as lines;
+    pub use crate::metadata::fixtures as metadata;
     pub use crate::node::fixtures as nodes;
     pub use crate::schedule::fixtures as schedules;
     pub use crate::station::fixtures as stations;
@@ -12,6 +12,7 @@ use simulation::Color;
 pub struct Line {
     name: String,
     color: Color,
+    text_color: Color,
     kind: Kind,
     nodes: Vec<Node>,
     trains: Vec<Train>,
@@ -21,6 +22,7 @@ impl Line {
     pub fn new(
         name: String,
         color: Color,
+        text_color: Color,
         kind: Kind,
         nodes: Vec<Node>,
         trains: Vec<Train>,
@@ -28,6 +30,7 @@ impl Line {
         Line {
             name,
             color,
+            text_color,
             kind,
             nodes,
             trains,
@@ -61,7 +64,7 @@ impl Line {
             .map(|train| train.load(kind, &nodes, schedules))
             .collect();
 
-        simulation::Line::new(self.name, self.color, kind, nodes, trains)
+        simulation::Line::new(self.name, self.color, self.text_color, kind, nodes, trains)
     }
 }
 
@@ -86,6 +89,7 @@ pub mod fixtures {
                     Line {
                         name: $name.to_string(),
                         color: Kind::$kind.color(),
+                        text_color: Color::new(0, 0, 0),
                         kind: Kind::$kind,
                         nodes: nodes::$line(station_ids),
                         trains: vec![
@@ -0,0 +1,206 @@
+use std::fmt;
+
+use serde_derive::{Deserialize, Serialize};
+
+/// Publisher and validity of one of the feeds a dataset was imported from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeedInfo {
+    publisher_name: String,
+    publisher_url: String,
+    version: Option<String>,
+    start_date: Option<String>,
+    end_date: Option<String>,
+}
+
+impl FeedInfo {
+    pub fn new(
+        publisher_name: String,
+        publisher_url: String,
+        version: Option<String>,
+        start_date: Option<String>,
+        end_date: Option<String>,
+    ) -> Self {
+        Self {
+            publisher_name,
+            publisher_url,
+            version,
+            start_date,
+            end_date,
+        }
+    }
+
+    pub fn publisher_name(&self) -> &str {
+        &self.publisher_name
+    }
+
+    pub fn version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
+}
+
+impl fmt::Display for FeedInfo {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            formatter,
+            "{} ({})",
+            self.publisher_name, self.publisher_url
+        )?;
+        if let Some(version) = &self.version {
+            write!(formatter, ", version {}", version)?;
+        }
+        match (&self.start_date, &self.end_date) {
+            (Some(start), Some(end)) => write!(formatter, ", valid from {} to {}", start, end),
+            (Some(start), None) => write!(formatter, ", valid from {}", start),
+            (None, Some(end)) => write!(formatter, ", valid until {}", end),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+/// Describes how a dataset was produced.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Metadata {
+    feeds: Vec<FeedInfo>,
+    first_date: String,
+    last_date: String,
+    profile: String,
+    shape_smoothing: String,
+    imported_at: String,
+}
+
+impl Metadata {
+    pub fn new(
+        feeds: Vec<FeedInfo>,
+        first_date: String,
+        last_date: String,
+        profile: String,
+        shape_smoothing: String,
+        imported_at: String,
+    ) -> Self {
+        Self {
+            feeds,
+            first_date,
+            last_date,
+            profile,
+            shape_smoothing,
+            imported_at,
+        }
+    }
+
+    pub fn feeds(&self) -> &[FeedInfo] {
+        &self.feeds
+    }
+
+    /// The first service day whose trains were exported.
+    pub fn first_date(&self) -> &str {
+        &self.first_date
+    }
+
+    /// The last service day whose trains were exported.
+    pub fn last_date(&self) -> &str {
+        &self.last_date
+    }
+
+    pub fn profile(&self) -> &str {
+        &self.profile
+    }
+
+    pub fn shape_smoothing(&self) -> &str {
+        &self.shape_smoothing
+    }
+
+    pub fn imported_at(&self) -> &str {
+        &self.imported_at
+    }
+}
+
+impl fmt::Display for Metadata {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.first_date == self.last_date {
+            writeln!(formatter, "Date: {}", self.first_date)?;
+        } else {
+            writeln!(
+                formatter,
+                "Dates: {} to {}",
+                self.first_date, self.last_date
+            )?;
+        }
+        writeln!(formatter, "Profile: {}", self.profile)?;
+        writeln!(formatter, "Shape smoothing: {}", self.shape_smoothing)?;
+        write!(formatter, "Imported at: {}", self.imported_at)?;
+        for feed in &self.feeds {
+            write!(formatter, "\nFeed: {}", feed)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(any(test, feature = "fixtures"))]
+pub mod fixtures {
+    use super::*;
+
+    pub fn vbb() -> Metadata {
+        Metadata::new(
+            vec![FeedInfo::new(
+                "VBB".to_string(),
+                "https://www.vbb.de".to_string(),
+                Some("2019-08".to_string()),
+                Some("2019-08-01".to_string()),
+                Some("2019-12-31".to_string()),
+            )],
+            "2019-08-26".to_string(),
+            "2019-08-26".to_string(),
+            "berlin-no-r".to_string(),
+            "full".to_string(),
+            "2019-08-20T12:00:00+00:00".to_string(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::metadata;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            metadata::vbb().to_string(),
+            "Date: 2019-08-26\n\
+             Profile: berlin-no-r\n\
+             Shape smoothing: full\n\
+             Imported at: 2019-08-20T12:00:00+00:00\n\
+             Feed: VBB (https://www.vbb.de), version 2019-08, valid from 2019-08-01 to 2019-12-31"
+        );
+    }
+
+    #[test]
+    fn test_display_date_range() {
+        let metadata = Metadata::new(
+            Vec::new(),
+            "2019-08-26".to_string(),
+            "2019-09-01".to_string(),
+            "berlin".to_string(),
+            "off".to_string(),
+            "2019-08-20T12:00:00+00:00".to_string(),
+        );
+        assert!(metadata
+            .to_string()
+            .starts_with("Dates: 2019-08-26 to 2019-09-01\n"));
+    }
+
+    #[test]
+    fn test_display_feed_without_validity() {
+        let feed = FeedInfo::new(
+            "DB".to_string(),
+            "https://www.bahn.de".to_string(),
+            None,
+            None,
+            Some("2019-12-14".to_string()),
+        );
+        assert_eq!(
+            feed.to_string(),
+            "DB (https://www.bahn.de), valid until 2019-12-14"
+        );
+    }
+}
@@ -23,7 +23,7 @@ impl Node {
 
     pub(crate) fn station(&self) -> Option<usize> {
         match self.kind {
-            Kind::Waypoint => None,
+            Kind::Waypoint | Kind::PassThrough => None,
             Kind::Stop { at } => Some(at),
         }
     }
@@ -36,7 +36,11 @@ impl Node {
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum Kind {
     Waypoint,
-    Stop { at: usize },
+    Stop {
+        at: usize,
+    },
+    /// A stop the trains pass through at a scheduled time without stopping.
+    PassThrough,
 }
 
 impl Kind {
@@ -46,6 +50,7 @@ impl Kind {
             Self::Stop { at } => simulation::NodeKind::Stop {
                 at: stations[at].clone(),
             },
+            Self::PassThrough => simulation::NodeKind::PassThrough,
         }
     }
 }
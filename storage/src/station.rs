@@ -2,19 +2,26 @@ use serde_derive::{Deserialize, Serialize};
 
 use na::Point2;
 
+use crate::complex::Complex;
+
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct Station {
     position: Point2<f32>,
     name: String,
+    complex: Complex,
 }
 
 impl Station {
-    pub fn new(position: Point2<f32>, name: String) -> Station {
-        Station { position, name }
+    pub fn new(position: Point2<f32>, name: String, complex: Complex) -> Station {
+        Station {
+            position,
+            name,
+            complex,
+        }
     }
 
     pub fn load(self, kind: simulation::station::Kind) -> simulation::Station {
-        simulation::Station::new(self.position, self.name, kind)
+        simulation::Station::new(self.position, self.name, kind, self.complex.load())
     }
 }
 
@@ -26,7 +33,11 @@ pub mod fixtures {
         ($($station:ident: $x:expr, $y:expr, $name:expr);* $(;)?) => {
             $(
                 pub fn $station() -> Station {
-                    Station::new(Point2::new($x as f32, $y as f32), $name.to_string())
+                    Station::new(
+                        Point2::new($x as f32, $y as f32),
+                        $name.to_string(),
+                        Complex::default(),
+                    )
                 }
             )*
         }
@@ -48,6 +59,10 @@ pub mod fixtures {
 
 #[cfg(test)]
 mod tests {
+    use na::Point2;
+
+    use super::*;
+    use crate::fixtures::complexes;
     use crate::station::fixtures as stations;
 
     #[test]
@@ -58,4 +73,18 @@ mod tests {
             simulation::fixtures::stations::hauptbahnhof()
         );
     }
+
+    #[test]
+    fn test_load_with_complex() {
+        let station = Station::new(
+            Point2::new(-1385.0, -1812.0),
+            "Hauptbahnhof".to_string(),
+            complexes::hauptbahnhof(),
+        );
+        let station = station.load(simulation::station::Kind::Interchange);
+        assert_eq!(
+            station.complex(),
+            &simulation::fixtures::complexes::hauptbahnhof()
+        );
+    }
 }
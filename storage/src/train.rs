@@ -6,44 +6,78 @@ use serde_derive::{Deserialize, Serialize};
 
 use crate::schedule::Schedule;
 use simulation::line::Kind;
-use simulation::{Direction, Node};
+use simulation::{Direction, MotionProfile, Node};
+
+/// Maximum number of service days a dataset can be stored for.
+///
+/// One bit of the day masks is taken by the day before the first stored day.
+pub const MAX_DAYS: usize = 63;
 
 #[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Train {
     direction: Direction,
     start_time: u32,
     schedule: usize,
+    days: u64,
+    platforms: Vec<Option<usize>>,
 }
 
 impl Train {
-    pub fn new(direction: Direction, start_time: u32, schedule: usize) -> Train {
+    /// A train running on the service days set in the bitmask.
+    ///
+    /// The lowest bit stands for the day before the first stored day and is only set for trains
+    /// running past midnight, the following ones for the stored days.
+    pub fn new(direction: Direction, start_time: u32, schedule: usize, days: u64) -> Train {
         Train {
             direction,
             start_time,
             schedule,
+            days,
+            platforms: Vec::new(),
         }
     }
 
+    /// Sets the platform of each stop as index into the places of the complex of its station,
+    /// which are left empty if none is known.
+    pub fn with_platforms(mut self, platforms: Vec<Option<usize>>) -> Train {
+        self.platforms = platforms;
+        self
+    }
+
     pub fn load(self, kind: Kind, nodes: &[Node], schedules: &[Schedule]) -> simulation::Train {
-        let durations = self.interpolate_times(nodes.to_vec(), schedules);
-        simulation::Train::new(kind, self.direction, durations)
+        let durations = self.interpolate_times(kind, nodes.to_vec(), schedules);
+        simulation::Train::new(kind, self.direction, self.days, durations)
+            .with_platforms(self.platforms)
     }
 
-    fn interpolate_times(&self, mut nodes: Vec<Node>, schedules: &[Schedule]) -> Vec<u32> {
+    fn interpolate_times(
+        &self,
+        kind: Kind,
+        mut nodes: Vec<Node>,
+        schedules: &[Schedule],
+    ) -> Vec<u32> {
         if self.direction == Direction::Downstream {
             nodes.reverse();
         }
 
-        let stop_positions = nodes.iter().positions(|node| self.is_node_allowed(node));
+        let timed_positions = nodes
+            .iter()
+            .positions(|node| node.is_timed() && node.allows(self.direction));
 
         let mut durations = vec![self.start_time];
         self.fill_before_dispatch(&nodes, &mut durations);
 
         let schedule = &schedules[self.schedule];
         for ((stopping, driving), (start, end)) in
-            schedule.into_iter().zip_eq(stop_positions.tuple_windows())
+            schedule.into_iter().zip_eq(timed_positions.tuple_windows())
         {
-            self.fill_driving(*stopping, *driving, &nodes[start..=end], &mut durations);
+            self.fill_driving(
+                kind.motion_profile(),
+                *stopping,
+                *driving,
+                &nodes[start..=end],
+                &mut durations,
+            );
         }
         durations.push(schedule.stop_duration_at_terminus());
 
@@ -63,14 +97,38 @@ impl Train {
         durations.extend(iter::repeat(0).take(count));
     }
 
-    fn fill_driving(&self, stopping: u32, driving: u32, nodes: &[Node], durations: &mut Vec<u32>) {
-        durations.push(stopping);
+    /// Splits the driving time between two timed nodes over the segments following the motion
+    /// profile.
+    ///
+    /// The times at which the nodes are passed are rounded, so that they add up to the driving time.
+    /// Trains do not stop at nodes they pass through, any time spent there is driven instead.
+    fn fill_driving(
+        &self,
+        profile: MotionProfile,
+        stopping: u32,
+        mut driving: u32,
+        nodes: &[Node],
+        durations: &mut Vec<u32>,
+    ) {
+        if nodes[0].is_stop() {
+            durations.push(stopping);
+        } else {
+            driving += stopping;
+        }
 
         let total_distance = self.segments_between(nodes).sum::<f32>();
-        let times = self.segments_between(nodes).map(|distance| {
-            let travelled = distance / total_distance;
-            (driving as f32 * travelled).round() as u32
-        });
+        let times =
+            self.segments_between(nodes)
+                .scan((0.0, 0), |(covered, passed_before), distance| {
+                    *covered += distance;
+                    let passed = profile
+                        .time_to(total_distance, driving as f32, *covered)
+                        .round() as u32;
+                    let passed = passed.min(driving).max(*passed_before);
+                    let duration = passed - *passed_before;
+                    *passed_before = passed;
+                    Some(duration)
+                });
         durations.extend(times);
     }
 
@@ -112,6 +170,8 @@ pub mod fixtures {
                                 direction: Direction::$direction,
                                 start_time,
                                 schedule: schedule_ids[stringify!($trip)],
+                                days: 0b10,
+                                platforms: Vec::new(),
                             }
                         }
                     )*
@@ -157,9 +217,13 @@ mod tests {
         let train =
             trains::tram_12::oranienburger_tor_am_kupfergraben(time!(9:01:40), &schedule_ids);
         assert_eq!(
-            train.interpolate_times(simulation::fixtures::nodes::tram_12(), &schedules),
-            times![9:01:40, 0:20, 0:27, 1:21, 0:27, 0:20, 0:19, 0:11, 0:12, 0:12,
-                0:12, 0:20, 1:00, 0:20]
+            train.interpolate_times(
+                Kind::Tram,
+                simulation::fixtures::nodes::tram_12(),
+                &schedules
+            ),
+            times![9:01:40, 0:20, 0:28, 1:19, 0:28, 0:20, 0:20, 0:11, 0:10, 0:11,
+                0:13, 0:20, 1:00, 0:20]
         );
     }
 
@@ -171,9 +235,32 @@ mod tests {
         let train =
             trains::tram_12::am_kupfergraben_oranienburger_tor(time!(8:33:40), &schedule_ids);
         assert_eq!(
-            train.interpolate_times(simulation::fixtures::nodes::tram_12(), &schedules),
-            times![8:33:40, 0:20, 0:19, 0:22, 0:12, 0:22, 0:20, 0:25, 0:12, 0:20,
-                0:12, 0:24, 0:20, 0:30, 0:31, 1:01, 0:30, 0:20]
+            train.interpolate_times(
+                Kind::Tram,
+                simulation::fixtures::nodes::tram_12(),
+                &schedules
+            ),
+            times![8:33:40, 0:20, 0:20, 0:21, 0:11, 0:23, 0:20, 0:26, 0:11, 0:20,
+                0:11, 0:25, 0:20, 0:31, 0:30, 1:00, 0:31, 0:20]
+        );
+    }
+
+    #[test]
+    fn test_time_interpolation_through_pass_through() {
+        let (schedules, schedule_ids) = fixtures_with_ids!(schedules::{
+            oranienburger_tor_am_kupfergraben,
+        });
+        let train =
+            trains::tram_12::oranienburger_tor_am_kupfergraben(time!(9:01:40), &schedule_ids);
+        let mut nodes = simulation::fixtures::nodes::tram_12();
+        nodes[4] = Node::new(
+            nodes[4].position(),
+            simulation::NodeKind::PassThrough,
+            simulation::Directions::Both,
+        );
+        assert_eq!(
+            train.interpolate_times(Kind::Tram, nodes, &schedules),
+            times![9:01:40, 0:20, 0:28, 1:19, 0:28, 0:26, 0:14, 0:14, 0:15, 0:16, 0:20, 1:00, 0:20]
         );
     }
 
@@ -184,8 +271,12 @@ mod tests {
         });
         let train = trains::tram_m5::zingster_str_perower_platz(time!(8:13:00), &schedule_ids);
         assert_eq!(
-            train.interpolate_times(simulation::fixtures::nodes::tram_m5(), &schedules),
-            times!(8:13:00, 0:00, 0:00, 1:00, 0:00, 1:00, 0:00, 0:48, 1:12, 0:00)
+            train.interpolate_times(
+                Kind::Tram,
+                simulation::fixtures::nodes::tram_m5(),
+                &schedules
+            ),
+            times!(8:13:00, 0:00, 0:00, 1:00, 0:00, 1:00, 0:00, 0:49, 1:11, 0:00)
         );
     }
 
@@ -196,8 +287,12 @@ mod tests {
         });
         let train = trains::bus_m82::weskammstr_waldsassener_str(time!(9:46:00), &schedule_ids);
         assert_eq!(
-            train.interpolate_times(simulation::fixtures::nodes::bus_m82(), &schedules),
-            times![9:46:00, 0:00, 0:00, 0:15, 0:15, 0:00, 0:14, 0:14, 0:17, 0:14,
+            train.interpolate_times(
+                Kind::Bus,
+                simulation::fixtures::nodes::bus_m82(),
+                &schedules
+            ),
+            times![9:46:00, 0:00, 0:00, 0:15, 0:15, 0:00, 0:16, 0:13, 0:15, 0:16,
                 0:00, 0:00, 0:00, 0:00]
         );
     }
@@ -220,4 +315,26 @@ mod tests {
             )
         );
     }
+
+    #[test]
+    fn test_load_with_platforms() {
+        let (schedules, schedule_ids) = fixtures_with_ids!(schedules::{
+            oranienburger_tor_am_kupfergraben,
+        });
+        let platforms = vec![Some(1), None, None, None];
+        let train =
+            trains::tram_12::oranienburger_tor_am_kupfergraben(time!(8:13:00), &schedule_ids)
+                .with_platforms(platforms.clone());
+        assert_eq!(
+            train.load(
+                Kind::Tram,
+                &simulation::fixtures::nodes::tram_12(),
+                &schedules
+            ),
+            simulation::fixtures::trains::tram_12::oranienburger_tor_am_kupfergraben(
+                time!(8:13:00)
+            )
+            .with_platforms(platforms)
+        );
+    }
 }
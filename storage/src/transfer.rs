@@ -0,0 +1,40 @@
+use serde_derive::{Deserialize, Serialize};
+
+use simulation::transfer::Kind;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Transfer {
+    from: usize,
+    to: usize,
+    kind: Kind,
+    min_time: Option<u32>,
+}
+
+impl Transfer {
+    pub fn new(from: usize, to: usize, kind: Kind, min_time: Option<u32>) -> Self {
+        Self {
+            from,
+            to,
+            kind,
+            min_time,
+        }
+    }
+
+    pub fn load(self) -> simulation::Transfer {
+        simulation::Transfer::new(self.from, self.to, self.kind, self.min_time)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load() {
+        let transfer = Transfer::new(1, 6, Kind::MinimumTime, Some(240));
+        assert_eq!(
+            transfer.load(),
+            simulation::Transfer::new(1, 6, Kind::MinimumTime, Some(240))
+        );
+    }
+}
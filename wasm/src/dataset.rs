@@ -6,6 +6,7 @@ use crate::view::View;
 #[wasm_bindgen]
 pub struct Dataset {
     inner: simulation::Dataset,
+    metadata: storage::Metadata,
 }
 
 #[wasm_bindgen]
@@ -13,21 +14,144 @@ impl Dataset {
     pub fn parse(data: &[u8]) -> Self {
         let dataset = bincode::deserialize::<storage::Dataset>(data).unwrap();
         Self {
+            metadata: dataset.metadata().clone(),
             inner: dataset.load(),
         }
     }
 
+    pub fn metadata(&self) -> String {
+        self.metadata.to_string()
+    }
+
     pub fn update(&mut self, time_passed: u32) {
         self.inner.update(time_passed)
     }
 
+    #[wasm_bindgen(js_name = setTime)]
+    pub fn set_time(&mut self, time: u32) {
+        self.inner.set_time(time)
+    }
+
+    #[wasm_bindgen(js_name = shiftTime)]
+    pub fn shift_time(&mut self, delta: i32) {
+        self.inner.shift_time(delta)
+    }
+
+    /// Restarts at midnight of the service day, returning `false` if it is not stored.
+    #[wasm_bindgen(js_name = selectDay)]
+    pub fn select_day(&mut self, day: usize) -> bool {
+        self.inner.select_day(day)
+    }
+
+    #[wasm_bindgen(js_name = advanceDay)]
+    pub fn advance_day(&mut self) -> bool {
+        self.inner.advance_day()
+    }
+
+    #[wasm_bindgen(js_name = setGravityDemand)]
+    pub fn set_gravity_demand(&mut self, per_hour: f32) {
+        self.inner.set_gravity_demand(per_hour)
+    }
+
+    #[wasm_bindgen(js_name = firstDate)]
+    pub fn first_date(&self) -> String {
+        self.metadata.first_date().to_string()
+    }
+
+    /// The index of the station at the position, which identifies it even if its name is shared.
     #[wasm_bindgen(js_name = findStation)]
-    pub fn find_station(&self, view: &View, x: f32, y: f32) -> Option<String> {
+    pub fn find_station(&self, view: &View, x: f32, y: f32) -> Option<usize> {
         let position = view.unproject(Point2::new(x, y));
+        self.inner.find_station_index(position)
+    }
+
+    #[wasm_bindgen(js_name = stationName)]
+    pub fn station_name(&self, station: usize) -> Option<String> {
         self.inner
-            .find_station(position)
+            .station(station)
             .map(|station| station.name().to_string())
     }
+
+    /// Up to `count` departures from the station, ordered by the time they are
+    /// expected to leave and written one departure per text line.
+    pub fn departures(&self, station: usize, from_time: u32, count: usize) -> String {
+        self.inner
+            .departures(station, from_time, count)
+            .iter()
+            .map(|departure| departure.to_string())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// The id of the train at the position, formatted as text to pass it back.
+    #[wasm_bindgen(js_name = findTrain)]
+    pub fn find_train(&self, view: &View, x: f32, y: f32) -> Option<String> {
+        let position = view.unproject(Point2::new(x, y));
+        self.inner.find_train(position).map(|id| id.to_string())
+    }
+
+    #[wasm_bindgen(js_name = trainInfo)]
+    pub fn train_info(&self, id: &str) -> Option<TrainInfo> {
+        let id = id.parse().ok()?;
+        self.inner.train_info(id).map(|inner| TrainInfo { inner })
+    }
+
+    /// The ids of the active trains of the line, separated by newlines.
+    #[wasm_bindgen(js_name = trainsOfLine)]
+    pub fn trains_of_line(&self, line: usize) -> String {
+        self.inner
+            .trains_of_line(line)
+            .iter()
+            .map(|id| id.to_string())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+#[wasm_bindgen]
+pub struct TrainInfo {
+    inner: simulation::TrainInfo,
+}
+
+#[wasm_bindgen]
+impl TrainInfo {
+    pub fn id(&self) -> String {
+        self.inner.id().to_string()
+    }
+
+    pub fn line(&self) -> String {
+        self.inner.line().to_string()
+    }
+
+    pub fn headsign(&self) -> String {
+        self.inner.headsign().to_string()
+    }
+
+    pub fn origin(&self) -> String {
+        self.inner.origin().to_string()
+    }
+
+    pub fn destination(&self) -> String {
+        self.inner.destination().to_string()
+    }
+
+    #[wasm_bindgen(js_name = currentStop)]
+    pub fn current_stop(&self) -> Option<String> {
+        self.inner.current_stop().map(str::to_string)
+    }
+
+    #[wasm_bindgen(js_name = nextStop)]
+    pub fn next_stop(&self) -> Option<String> {
+        self.inner.next_stop().map(str::to_string)
+    }
+
+    pub fn delay(&self) -> u32 {
+        self.inner.delay()
+    }
+
+    pub fn progress(&self) -> f32 {
+        self.inner.progress()
+    }
 }
 
 macro_rules! delegate {
@@ -45,15 +169,23 @@ macro_rules! delegate {
 }
 
 delegate! {
-    pub fn station_count(&self) -> usize; [stationCount]
+    pub fn day_count(&self) -> usize; [dayCount]
+        pub fn day(&self) -> usize; [day]
+        pub fn time(&self) -> u32; [time]
+
+        pub fn station_count(&self) -> usize; [stationCount]
         pub fn station_positions(&self) -> Vec<f32>; [stationPositions]
         pub fn station_types(&self) -> Vec<u8>; [stationTypes]
+        pub fn station_severities(&self) -> Vec<u8>; [stationSeverities]
+        pub fn station_waiting_counts(&self) -> Vec<u32>; [stationWaitingCounts]
 
         pub fn line_count(&self) -> usize; [lineCount]
         pub fn line_colors(&self) -> Vec<f32>; [lineColors]
+        pub fn line_text_colors(&self) -> Vec<f32>; [lineTextColors]
         pub fn line_vertices_sizes(&self) -> Vec<usize>; [lineVerticesSizes]
         pub fn line_vertices(&self) -> Vec<f32>; [lineVertices]
         pub fn line_names(&self) -> String; [lineNames]
+        pub fn line_severities(&self) -> Vec<u8>; [lineSeverities]
 
         pub fn train_count(&self) -> usize; [trainCount]
         pub fn train_vertices(&self) -> Vec<f32>; [trainVertices]
@@ -61,4 +193,5 @@ delegate! {
         pub fn train_line_numbers(&self) -> Vec<u16>; [trainLineNumbers]
         pub fn train_sides(&self) -> Vec<u8>; [trainSides]
         pub fn train_extents(&self) -> Vec<f32>; [trainExtents]
+        pub fn train_loads(&self) -> Vec<f32>; [trainLoads]
 }
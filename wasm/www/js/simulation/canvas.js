@@ -38,6 +38,7 @@ export class SimulationCanvas extends Canvas {
 
     update() {
         this.renderer.train.fillBuffers(this.model);
+        this.updateTrainInfo();
     }
 
     resizeCanvas() {
@@ -57,6 +58,14 @@ export class SimulationCanvas extends Canvas {
                 this.view.viewProjection = this.view.calculateViewProjection();
             }
         });
+        this.canvas.addEventListener("click", event => {
+            if (this.model) {
+                const x = event.clientX - this.canvas.offsetLeft;
+                const y = event.clientY - this.canvas.offsetTop;
+                this.selectedTrain = this.model.findTrain(this.view, x, y);
+                this.updateTrainInfo();
+            }
+        });
         this.canvas.addEventListener("wheel", event => {
             const scaling = event.deltaY < 0 ? 11 / 10 : 10 / 11;
             this.view.zoom(scaling, event.clientX - this.canvas.offsetLeft, event.clientY - this.canvas.offsetTop);
@@ -66,11 +75,37 @@ export class SimulationCanvas extends Canvas {
 
     updateTooltip(x, y) {
         if (this.model) {
-            const name = this.model.findStation(this.view, x, y);
-            this.canvas.title = name ? name : "";
+            const station = this.model.findStation(this.view, x, y);
+            if (station !== undefined) {
+                const name = this.model.stationName(station);
+                const departures = this.model.departures(station, this.model.time(), 5);
+                this.canvas.title = departures ? `${name}\n${departures}` : name;
+            } else {
+                this.canvas.title = "";
+            }
         }
     }
 
+    updateTrainInfo() {
+        const element = document.querySelector(".train-info");
+        const info = this.selectedTrain ? this.model.trainInfo(this.selectedTrain) : undefined;
+        if (!info) {
+            this.selectedTrain = undefined;
+            element.hidden = true;
+            return;
+        }
+
+        element.hidden = false;
+        element.querySelector(".line").textContent = `${info.line()} → ${info.headsign()}`;
+        element.querySelector(".route").textContent = `${info.origin()} – ${info.destination()}`;
+        const current = info.currentStop();
+        element.querySelector(".stop").textContent = current ? `At ${current}` : `Next: ${info.nextStop() || "–"}`;
+        const delay = info.delay();
+        element.querySelector(".delay").textContent = delay > 0 ? `+${Math.ceil(delay / 60)} min` : "On time";
+        element.querySelector(".progress").value = info.progress();
+        info.free();
+    }
+
     clear() {
         this.gl.clearColor(0.9, 0.95, 0.95, 1.0);
         this.gl.clear(this.gl.COLOR_BUFFER_BIT);